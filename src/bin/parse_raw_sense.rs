@@ -1,25 +1,568 @@
 //#![warn(missing_docs)]
 
-use scsi::Sense;
-use std::io;
-use std::io::BufRead;
+/*!
+Decode sense data, and optionally the CDB that produced it, from several
+input forms:
+* `hex`: whitespace separated hex bytes, including `sg_raw` and
+  `sg_decode_sense` style dumps with offsets and an ASCII column. Each run of
+  consecutive hex lines is one sense buffer. A line containing `cdb:` gives
+  the CDB for the next sense buffer.
+* `binary`: each file is one raw sense buffer.
+* `kernel`: kernel log lines such as `Sense Key : Medium Error [current]`,
+  `Add. Sense: Unrecovered read error` and `CDB: Read(6) 08 00 00 00 50 00`.
+* `base64`: one base64 encoded sense buffer per line.
+*/
+
+use getopts::Options;
+use scsi::{Cdb, CdbSenseInfo, Sense, SenseKey};
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+
+struct Record {
+  source: String,
+  sense_bytes: Vec <u8>,
+  cdb_bytes: Option <Vec <u8>>,
+}
+
+struct Decoded {
+  source: String,
+  sense: Sense,
+  cdb: Option <Cdb>,
+  command_specific: Option <CdbSenseInfo>,
+}
+
+fn print_usage(error: &str, program: &str, opts: Options) {
+  let brief = format!("{}\nUsage: {} [options] [FILE...]", error, program);
+  eprint!("{}", opts.usage(&brief));
+}
 
 fn main() {
-  let mut buffer: Vec <u8> = Vec::new();
+  let args: Vec <String> = env::args().collect();
+  let program = args[0].clone();
+  let mut opts = Options::new();
+  opts.optflag("h", "help", "print this help");
+  opts.optopt("i", "input", "input form: hex (default), binary, kernel, base64", "FORM");
+  opts.optopt("c", "cdb", "hex CDB to decode with every sense buffer that has none", "HEX");
+  opts.optopt("f", "format", "output format: text (default) or json", "FORMAT");
+  opts.optflag("", "debug", "also print the debug representation in text output");
+
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => { m },
+    Err(e) => { print_usage(&e.to_string(), &program, opts); std::process::exit(2) },
+  };
+
+  if matches.opt_present("help") {
+    print_usage("Decode raw SCSI sense data", &program, opts);
+    return;
+  }
+
+  if let Err(e) = run(&matches) {
+    eprintln!("{}: {}", program, e);
+    std::process::exit(1);
+  }
+}
+
+fn run(matches: &getopts::Matches) -> Result <(), String> {
+  let input = matches.opt_str("input").unwrap_or_else(|| "hex".to_string());
+  let format = matches.opt_str("format").unwrap_or_else(|| "text".to_string());
+  if format != "text" && format != "json" {
+    return Err(format!("unknown output format '{}'", format));
+  }
+
+  let default_cdb = match matches.opt_str("cdb") {
+    Some(s) => { Some(parse_hex_tokens(&s).map_err(|e| format!("--cdb: {}", e))?) },
+    None => { None },
+  };
+
+  //Gather (name, contents) for every input
+  let mut inputs: Vec <(String, Vec <u8>)> = Vec::new();
+  if matches.free.is_empty() {
+    let mut buf = Vec::new();
+    io::stdin().read_to_end(&mut buf).map_err(|e| format!("stdin: {}", e))?;
+    inputs.push(("stdin".to_string(), buf));
+  } else {
+    for name in &matches.free {
+      let mut buf = Vec::new();
+      File::open(name)
+          .and_then(|mut f| f.read_to_end(&mut buf))
+          .map_err(|e| format!("{}: {}", name, e))?;
+      inputs.push((name.clone(), buf));
+    }
+  }
+
+  let mut records: Vec <Record> = Vec::new();
+  for (name, buf) in inputs {
+    let mut parsed = match input.as_str() {
+      "binary" => {
+        vec![Record { source: name, sense_bytes: buf, cdb_bytes: None }]
+      },
+      "hex" | "kernel" | "base64" => {
+        let text = String::from_utf8(buf)
+            .map_err(|_| format!("{}: input is not valid text", name))?;
+        match input.as_str() {
+          "hex" => { parse_hex_input(&name, &text)? },
+          "kernel" => { parse_kernel_input(&name, &text)? },
+          _ => { parse_base64_input(&name, &text)? },
+        }
+      },
+      _ => { return Err(format!("unknown input form '{}'", input)); },
+    };
+    records.append(&mut parsed);
+  }
+
+  if records.is_empty() {
+    return Err("no sense data found in input".to_string());
+  }
+
+  let mut decoded: Vec <Decoded> = Vec::new();
+  for record in records {
+    let sense = Sense::from_buf(&record.sense_bytes).ok_or_else(|| format!(
+        "{}: not valid sense data (response code {:#04x})",
+        record.source, record.sense_bytes.first().map(|b| b & 0x7F).unwrap_or(0)))?;
+
+    let cdb = match record.cdb_bytes.as_ref().or(default_cdb.as_ref()) {
+      Some(bytes) => {
+        Some(Cdb::from_bytes(bytes).ok_or_else(|| format!(
+            "{}: unsupported or truncated CDB {:02x?}", record.source, bytes))?)
+      },
+      None => { None },
+    };
+    let command_specific = cdb.as_ref().map(|c| c.parse_sense(&sense));
+
+    decoded.push(Decoded { source: record.source, sense, cdb, command_specific });
+  }
+
+  if format == "json" {
+    print_json(&decoded)
+  } else {
+    print_text(&decoded, matches.opt_present("debug"));
+    Ok(())
+  }
+}
 
-  let stdin = io::stdin();
-  let stdin_handle = stdin.lock();
-  let mut iter = stdin_handle.lines();
-  while let Some(Ok(line)) = iter.next() {
-    for token in line.split_whitespace() {
-      buffer.push(u8::from_str_radix(&token, 16).expect("Invalid hex value"));
+fn print_text(decoded: &[Decoded], debug: bool) {
+  for (i, d) in decoded.iter().enumerate() {
+    if i > 0 {
+      println!();
+    }
+    println!("== Sense {} ({}) ==", i + 1, d.source);
+    print!("{}", d.sense);
+    if debug {
+      println!("{:#?}", d.sense);
+    }
+
+    if let Some(cdb) = &d.cdb {
+      println!("== CDB ==");
+      println!("{:#?}", cdb);
+    }
+
+    if let Some(info) = &d.command_specific {
+      let info = info.to_string();
+      if !info.is_empty() {
+        print!("== Command Specific ==\n{}", info);
+      }
+    }
+  }
+}
+
+#[cfg(feature = "json")]
+fn print_json(decoded: &[Decoded]) -> Result <(), String> {
+  let values: Vec <serde_json::Value> = decoded.iter().map(|d| {
+    serde_json::json!({
+      "source": d.source,
+      "sense": d.sense,
+      "cdb": d.cdb,
+      "command_specific": d.command_specific,
+    })
+  }).collect();
+
+  let text = serde_json::to_string_pretty(&values).map_err(|e| e.to_string())?;
+  println!("{}", text);
+  Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_json(_decoded: &[Decoded]) -> Result <(), String> {
+  Err("JSON output requires building with the json feature".to_string())
+}
+
+/// Parse a single hex byte token such as "7f" or "0x7F"
+fn parse_hex_byte(token: &str) -> Option <u8> {
+  let token = token.trim_end_matches(',');
+  let digits = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")).unwrap_or(token);
+  if digits.is_empty() || digits.len() > 2 {
+    return None;
+  }
+  u8::from_str_radix(digits, 16).ok()
+}
+
+fn parse_hex_tokens(text: &str) -> Result <Vec <u8>, String> {
+  text.split(|c: char| c.is_whitespace() || c == ',')
+      .filter(|t| !t.is_empty())
+      .map(|t| parse_hex_byte(t).ok_or_else(|| format!("invalid hex byte '{}'", t)))
+      .collect()
+}
+
+/// Hex bytes on a dump line, or None if the line is not a hex dump line.
+/// A leading offset column (followed by several spaces) and a trailing
+/// ASCII column (preceded by several spaces) are skipped. A line whose
+/// first column is not entirely hex bytes is prose, even if it happens to
+/// start with a word such as "add" or "be".
+fn hex_dump_line(line: &str) -> Option <Vec <u8>> {
+  let mut columns: Vec <&str> = line.trim().split("   ")
+      .map(|c| c.trim())
+      .filter(|c| !c.is_empty())
+      .collect();
+  if columns.is_empty() {
+    return None;
+  }
+
+  //Offset column, e.g. " 00     70 00 05 ..."
+  if columns.len() > 1 && columns[0].split_whitespace().count() == 1 &&
+      columns[0].chars().all(|c| c.is_ascii_hexdigit()) &&
+      columns[1].split_whitespace().all(|t| parse_hex_byte(t).is_some()) {
+    columns.remove(0);
+  }
+
+  //sg3_utils splits 16 bytes into two groups of 8 with a double space, so
+  //join byte columns back together until something that is not hex appears
+  let mut bytes = Vec::new();
+  for (i, column) in columns.iter().enumerate() {
+    let tokens: Vec <&str> = column.split_whitespace().collect();
+    let parsed: Vec <Option <u8>> = tokens.iter().map(|t| parse_hex_byte(t)).collect();
+    if parsed.iter().all(|b| b.is_some()) {
+      bytes.extend(parsed.into_iter().flatten());
+    } else if i == 0 {
+      return None;
+    } else {
+      //ASCII column
+      break;
     }
   }
 
-  let sense: Sense = Sense::from_buf(&buffer).expect("Invalid sense data!");
-  println!("== Debug Print ==");
-  println!("{:#?}", sense);
-  println!("");
-  println!("== Sense Print ==");
-  println!("{}", sense);
+  Some(bytes)
+}
+
+fn parse_hex_input(name: &str, text: &str) -> Result <Vec <Record>, String> {
+  let mut records: Vec <Record> = Vec::new();
+  let mut current: Vec <u8> = Vec::new();
+  let mut start_line = 0;
+  let mut pending_cdb: Option <Vec <u8>> = None;
+
+  let mut finish = |current: &mut Vec <u8>, start_line: usize, cdb: &mut Option <Vec <u8>>| {
+    if !current.is_empty() {
+      records.push(Record {
+        source: format!("{}:{}", name, start_line),
+        sense_bytes: std::mem::take(current),
+        cdb_bytes: cdb.take(),
+      });
+    }
+  };
+
+  for (i, line) in text.lines().enumerate() {
+    let line_no = i + 1;
+    if let Some(pos) = cdb_label_end(line) {
+      let cdb = parse_hex_tokens(skip_to_hex(&line[pos..]))
+          .map_err(|e| format!("{}:{}: {}", name, line_no, e))?;
+      if cdb.is_empty() {
+        return Err(format!("{}:{}: empty CDB", name, line_no));
+      }
+      pending_cdb = Some(cdb);
+      continue;
+    }
+
+    match hex_dump_line(line) {
+      Some(bytes) => {
+        if current.is_empty() {
+          start_line = line_no;
+        }
+        current.extend(bytes);
+      },
+      None => { finish(&mut current, start_line, &mut pending_cdb); },
+    }
+  }
+  finish(&mut current, start_line, &mut pending_cdb);
+
+  Ok(records)
+}
+
+/// Find a "cdb:" label as a word of its own, in any case and with optional
+/// space before the colon, and return the offset just past the colon
+fn cdb_label_end(line: &str) -> Option <usize> {
+  let lower = line.to_ascii_lowercase();
+  let mut start = 0;
+  while let Some(found) = lower[start..].find("cdb") {
+    let pos = start + found;
+    start = pos + "cdb".len();
+    if lower[..pos].chars().next_back().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_') {
+      continue;
+    }
+    let rest = &lower[start..];
+    let trimmed = rest.trim_start();
+    if trimmed.starts_with(':') {
+      return Some(start + (rest.len() - trimmed.len()) + 1);
+    }
+  }
+  None
+}
+
+/// Skip a leading command name such as "Read(6)" in front of CDB bytes
+fn skip_to_hex(text: &str) -> &str {
+  let text = text.trim();
+  match text.split_whitespace().next() {
+    Some(first) if parse_hex_byte(first).is_none() => {
+      text[first.len()..].trim_start()
+    },
+    _ => { text },
+  }
+}
+
+fn parse_kernel_input(name: &str, text: &str) -> Result <Vec <Record>, String> {
+  struct Pending {
+    line: usize,
+    descriptor: bool,
+    deferred: bool,
+    key: u8,
+    asc: Option <(u8, u8)>,
+    info: Option <u64>,
+    cdb: Option <Vec <u8>>,
+  }
+
+  fn finish(name: &str, pending: Pending) -> Result <Record, String> {
+    let (asc, ascq) = pending.asc.ok_or_else(|| format!(
+        "{}:{}: sense key without an additional sense line", name, pending.line))?;
+
+    //The fixed format information field only holds 32 bits
+    let descriptor = pending.descriptor || pending.info.is_some_and(|t| t > u32::MAX as u64);
+    let sense_bytes = if descriptor {
+      let mut buf = vec![
+        if pending.deferred { 0x73 } else { 0x72 },
+        pending.key, asc, ascq, 0, 0, 0, 0,
+      ];
+      if let Some(info) = pending.info {
+        buf.extend_from_slice(&[0x00, 0x0A, 0x80, 0x00]);
+        buf.extend_from_slice(&info.to_be_bytes());
+      }
+      buf[7] = (buf.len() - 8) as u8;
+      buf
+    } else {
+      let mut buf = vec![0; 18];
+      buf[0] = if pending.deferred { 0x71 } else { 0x70 };
+      if let Some(info) = pending.info {
+        buf[0] |= 0x80;
+        buf[3..7].copy_from_slice(&(info as u32).to_be_bytes());
+      }
+      buf[2] = pending.key;
+      buf[7] = 10;
+      buf[12] = asc;
+      buf[13] = ascq;
+      buf
+    };
+
+    Ok(Record {
+      source: format!("{}:{}", name, pending.line),
+      sense_bytes,
+      cdb_bytes: pending.cdb,
+    })
+  }
+
+  let mut records: Vec <Record> = Vec::new();
+  let mut pending: Option <Pending> = None;
+
+  for (i, line) in text.lines().enumerate() {
+    let line_no = i + 1;
+    let err = |e: String| format!("{}:{}: {}", name, line_no, e);
+
+    if let Some(pos) = line.find("Sense Key") {
+      if let Some(p) = pending.take() {
+        records.push(finish(name, p)?);
+      }
+
+      let rest = line[pos + "Sense Key".len()..].trim_start().trim_start_matches(':');
+      let key_name = rest.split('[').next().unwrap_or("").trim();
+      let key = SenseKey::from_name(key_name)
+          .ok_or_else(|| err(format!("unknown sense key '{}'", key_name)))?;
+
+      pending = Some(Pending {
+        line: line_no,
+        descriptor: rest.contains("[descriptor]"),
+        deferred: rest.contains("[deferred]"),
+        key: key.to_u8(),
+        asc: None,
+        info: None,
+        cdb: None,
+      });
+    } else if let Some(p) = pending.as_mut() {
+      if let Some(pos) = line.find("Add. Sense:") {
+        let desc = line[pos + "Add. Sense:".len()..].trim();
+        p.asc = Some(parse_kernel_asc(desc).ok_or_else(|| {
+          err(format!("unknown additional sense '{}'", desc))
+        })?);
+      } else if let Some(pos) = line.find("ASC=") {
+        p.asc = Some(parse_kernel_asc(&line[pos..]).ok_or_else(|| {
+          err(format!("invalid ASC/ASCQ '{}'", line[pos..].trim()))
+        })?);
+      } else if let Some(pos) = line.find("Info fld=") {
+        let value = line[pos + "Info fld=".len()..].split_whitespace().next().unwrap_or("");
+        let value = value.trim_start_matches("0x");
+        p.info = Some(u64::from_str_radix(value, 16)
+            .map_err(|_| err(format!("invalid information field '{}'", value)))?);
+      } else if let Some(pos) = line.find("CDB:") {
+        let cdb = parse_hex_tokens(skip_to_hex(&line[pos + "CDB:".len()..]))
+            .map_err(err)?;
+        p.cdb = Some(cdb);
+      }
+    }
+  }
+
+  if let Some(p) = pending.take() {
+    records.push(finish(name, p)?);
+  }
+
+  Ok(records)
+}
+
+/// Either "ASC=0x11 ASCQ=0x0" or an additional sense description
+fn parse_kernel_asc(text: &str) -> Option <(u8, u8)> {
+  if let Some(rest) = text.strip_prefix("ASC=") {
+    let mut parts = rest.split_whitespace();
+    let asc = parts.next()?.trim_start_matches("0x");
+    let ascq = parts.next()?.strip_prefix("ASCQ=")?.trim_start_matches("0x");
+    return Some((u8::from_str_radix(asc, 16).ok()?, u8::from_str_radix(ascq, 16).ok()?));
+  }
+
+  scsi::find_additional_sense_code(text)
+}
+
+fn parse_base64_input(name: &str, text: &str) -> Result <Vec <Record>, String> {
+  let mut records: Vec <Record> = Vec::new();
+  for (i, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() {
+      continue;
+    }
+
+    let bytes = decode_base64(line).map_err(|e| format!("{}:{}: {}", name, i + 1, e))?;
+    records.push(Record {
+      source: format!("{}:{}", name, i + 1),
+      sense_bytes: bytes,
+      cdb_bytes: None,
+    });
+  }
+
+  Ok(records)
+}
+
+fn decode_base64(text: &str) -> Result <Vec <u8>, String> {
+  let data = text.trim_end_matches('=');
+  let padding = text.len() - data.len();
+  if padding > 2 || (padding > 0 && !text.len().is_multiple_of(4)) {
+    return Err("invalid base64 padding".to_string());
+  }
+  if data.len() % 4 == 1 {
+    return Err("invalid base64 length".to_string());
+  }
+
+  let mut rv: Vec <u8> = Vec::new();
+  let mut acc: u32 = 0;
+  let mut bits = 0;
+
+  for c in data.chars() {
+    let val = match c {
+      'A'..='Z' => { c as u32 - 'A' as u32 },
+      'a'..='z' => { c as u32 - 'a' as u32 + 26 },
+      '0'..='9' => { c as u32 - '0' as u32 + 52 },
+      '+' | '-' => { 62 },
+      '/' | '_' => { 63 },
+      '=' => { return Err("base64 padding must be at the end".to_string()); },
+      _ => { return Err(format!("invalid base64 character '{}'", c)); },
+    };
+
+    acc = (acc << 6) | val;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      rv.push((acc >> bits) as u8);
+      acc &= (1 << bits) - 1;
+    }
+  }
+
+  Ok(rv)
+}
+
+#[test]
+fn hex_dump_test() {
+  let text = concat!(
+    "Raw sense data (in hex), sb_len=18\n",
+    " 00     70 00 05 00 00 00 00 0a  00 00 00 00 24 00 00 00    p...........$...\n",
+    " 10     00 00\n",
+    "\n",
+    "cdb: Read(6) 08 00 00 00 50 00\n",
+    "72 03 11 00 00 00 00 00\n",
+  );
+
+  let records = parse_hex_input("test", text).unwrap();
+  assert_eq!(records.len(), 2);
+  assert_eq!(records[0].sense_bytes.len(), 18);
+  assert_eq!(records[0].sense_bytes[12], 0x24);
+  assert!(records[0].cdb_bytes.is_none());
+  assert_eq!(records[1].cdb_bytes, Some(vec![0x08, 0x00, 0x00, 0x00, 0x50, 0x00]));
+
+  //Only a "cdb:" label starts a CDB, not prose that mentions one
+  let text = concat!(
+    "Retried the cdb twice, giving up: 70 00 05 00\n",
+    "scsi_cdb_dump: raw\n",
+    "70 00 05 00\n",
+    "\n",
+    "[st0] CDB : Read(6) 08 00 00 00 50 00\n",
+    "70 00 03 00\n",
+  );
+  let records = parse_hex_input("test", text).unwrap();
+  assert_eq!(records.len(), 2);
+  assert!(records[0].cdb_bytes.is_none());
+  assert_eq!(records[1].cdb_bytes, Some(vec![0x08, 0x00, 0x00, 0x00, 0x50, 0x00]));
+
+  //Prose lines are skipped, even when the first word is valid hex
+  let text = "Add 12 bytes of padding\n70 00 05 00\nbe careful\n";
+  let records = parse_hex_input("test", text).unwrap();
+  assert_eq!(records.len(), 1);
+  assert_eq!(records[0].sense_bytes, vec![0x70, 0x00, 0x05, 0x00]);
+  assert!(parse_hex_input("test", "70 00 zz\n").unwrap().is_empty());
+}
+
+#[test]
+fn kernel_log_test() {
+  let text = concat!(
+    "st 0:0:0:0: [st0] Sense Key : Medium Error [current]\n",
+    "st 0:0:0:0: [st0] Add. Sense: Unrecovered read error\n",
+    "st 0:0:0:0: [st0] CDB: Read(6) 08 00 00 00 50 00\n",
+  );
+
+  let records = parse_kernel_input("test", text).unwrap();
+  assert_eq!(records.len(), 1);
+  let sense = Sense::from_buf(&records[0].sense_bytes).unwrap();
+  assert!(matches!(sense.sense_key, SenseKey::MediumError));
+  assert_eq!(sense.additional_sense_code, 0x11);
+  assert!(records[0].cdb_bytes.is_some());
+
+  //An information field wider than 32 bits needs descriptor format
+  let text = concat!(
+    "st 0:0:0:0: [st0] Sense Key : Medium Error [current]\n",
+    "st 0:0:0:0: [st0] Add. Sense: Unrecovered read error\n",
+    "st 0:0:0:0: [st0] Info fld=0x123456789\n",
+  );
+  let records = parse_kernel_input("test", text).unwrap();
+  assert_eq!(records[0].sense_bytes[0], 0x72);
+  let sense = Sense::from_buf(&records[0].sense_bytes).unwrap();
+  assert_eq!(sense.information_value(), Some(0x123456789));
+}
+
+#[test]
+fn base64_test() {
+  assert_eq!(decode_base64("cAAFAA==").unwrap(), vec![0x70, 0x00, 0x05, 0x00]);
+  assert!(decode_base64("cA*A").is_err());
+  assert!(decode_base64("cAAFA").is_err());
+  assert!(decode_base64("cA=AFA==").is_err());
+  assert!(decode_base64("cAAFAA===").is_err());
 }
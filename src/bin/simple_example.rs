@@ -62,7 +62,7 @@ fn main() {
     println!("{}", result.as_ref().unwrap_err());
   }
   if let Some(sense) = result.unwrap().sense {
-    println!("{}", read_cmd.parse_sense(&sense));
+    print!("{}", read_cmd.parse_sense(&sense));
   }
   println!("Buffer: {:x?}", buf);
 }
@@ -0,0 +1,146 @@
+use crate::commands::{*};
+use crate::sense::Sense;
+use crate::Command;
+use std::fmt;
+
+/// Sense interpretation returned by [`Cdb::parse_sense`]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CdbSenseInfo {
+  Read(ReadSenseInfo),
+  Write(WriteSenseInfo),
+  WriteFilemarks(WriteFilemarksSenseInfo),
+  Verify(VerifySenseInfo),
+  Space(SpaceSenseInfo),
+  Locate(LocateSenseInfo),
+  MediumOperation(MediumOperationSenseInfo),
+  Parameter(ParameterSenseInfo),
+}
+
+impl fmt::Display for CdbSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CdbSenseInfo::Read(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::Write(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::WriteFilemarks(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::Verify(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::Space(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::Locate(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::MediumOperation(t) => { write!(f, "{}", t) },
+      CdbSenseInfo::Parameter(t) => { write!(f, "{}", t) },
+    }
+  }
+}
+
+macro_rules! cdb_commands {
+  ( $( $name:ident => $info:ident ),* $(,)? ) => {
+    /// Any command in [`commands`](crate::commands), decoded from raw CDB
+    /// bytes by operation code
+    #[derive(Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Cdb {
+      $( $name($name), )*
+    }
+
+    impl Cdb {
+      /// Decode the CDB into the matching command structure. Returns None
+      /// if the operation code is not implemented or the CDB is too short.
+      pub fn from_bytes(buf: &[u8]) -> Option <Cdb> {
+        $(
+          if let Some(cmd) = $name::from_bytes(buf) {
+            return Some(Cdb::$name(cmd));
+          }
+        )*
+        None
+      }
+
+      /// Interpret sense data returned for this command
+      pub fn parse_sense(&self, sense: &Sense) -> CdbSenseInfo {
+        match self {
+          $( Cdb::$name(cmd) => { CdbSenseInfo::$info(cmd.parse_sense(sense)) }, )*
+        }
+      }
+
+      pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+        match self {
+          $( Cdb::$name(cmd) => { cmd.to_bytes() }, )*
+        }
+      }
+    }
+  };
+}
+
+cdb_commands! {
+  AllowOverwrite => Locate,
+  Erase6 => MediumOperation,
+  Erase16 => MediumOperation,
+  FormatMedium => MediumOperation,
+  Inquiry => Parameter,
+  LoadUnload => MediumOperation,
+  Locate10 => Locate,
+  Locate16 => Locate,
+  LogSelect => Parameter,
+  LogSense => Parameter,
+  ModeSelect6 => Parameter,
+  ModeSelect10 => Parameter,
+  ModeSense6 => Parameter,
+  ModeSense10 => Parameter,
+  PersistentReserveIn => Parameter,
+  PersistentReserveOut => Parameter,
+  PreventAllowMediumRemoval => Parameter,
+  ReadAttribute => Parameter,
+  ReadBlockLimits => Parameter,
+  ReadBuffer => Parameter,
+  ReadMediaSerialNumber => Parameter,
+  ReadPosition => Parameter,
+  ReadReverse6 => Read,
+  ReadReverse16 => Read,
+  Read6 => Read,
+  Read16 => Read,
+  ReceiveDiagnosticResults => Parameter,
+  RecoverBufferedData => Read,
+  Release6 => Parameter,
+  ReportDensitySupport => Parameter,
+  ReportIdentifyingInformation => Parameter,
+  ReportLuns => Parameter,
+  ReportSupportedOperationCodes => Parameter,
+  ReportTimestamp => Parameter,
+  RequestSense => Parameter,
+  Reserve6 => Parameter,
+  Rewind => MediumOperation,
+  SecurityProtocolIn => Parameter,
+  SecurityProtocolOut => Parameter,
+  SendDiagnostic => Parameter,
+  SetCapacity => MediumOperation,
+  SetIdentifyingInformation => Parameter,
+  SetTimestamp => Parameter,
+  Space6 => Space,
+  Space16 => Space,
+  TestUnitReady => MediumOperation,
+  Verify6 => Verify,
+  Verify16 => Verify,
+  WriteAttribute => Parameter,
+  WriteBuffer => Parameter,
+  WriteFilemarks6 => WriteFilemarks,
+  WriteFilemarks16 => WriteFilemarks,
+  Write6 => Write,
+  Write16 => Write,
+}
+
+#[test]
+fn cdb_round_trip_test() {
+  let bytes = vec![0x08_u8, 0x03, 0x00, 0x01, 0x2C, 0x00];
+  let cdb = Cdb::from_bytes(&bytes).unwrap();
+  match &cdb {
+    Cdb::Read6(cmd) => {
+      assert!(cmd.sili);
+      assert!(cmd.fixed);
+      assert_eq!(cmd.transfer_length, 300);
+    },
+    _ => { panic!("Decoded as {:?}", cdb); },
+  }
+  assert_eq!(cdb.to_bytes(), Ok(bytes));
+
+  assert!(Cdb::from_bytes(&[0x08, 0x00]).is_none());
+  assert!(Cdb::from_bytes(&[0xFF, 0x00, 0x00, 0x00, 0x00, 0x00]).is_none());
+}
@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::LocateSenseInfo;
 
 /// SSC-4 Section 7.1
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllowOverwrite {
   pub allow_overwrite: u8,
   pub partition: u8,
@@ -17,12 +20,27 @@ impl AllowOverwrite {
   pub fn new() -> AllowOverwrite {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <AllowOverwrite> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(AllowOverwrite {
+      allow_overwrite: buf[2] & 0x0F,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+    })
+  }
 }
 
 impl crate::NoIO for AllowOverwrite {
 }
 
 impl crate::Command for AllowOverwrite {
+  type SenseInfo = LocateSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -38,7 +56,7 @@ impl crate::Command for AllowOverwrite {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> LocateSenseInfo {
+    LocateSenseInfo::from_sense(sense)
   }
 }
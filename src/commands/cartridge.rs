@@ -0,0 +1,178 @@
+use crate::commands::ReadAttribute;
+use crate::commands::ReadMediaSerialNumber;
+use crate::commands::mam::{self, Attribute};
+use crate::commands::mode::read_mode_parameter_header;
+use crate::device::{Device, DeviceStatus, UnitReadiness, check_good};
+use crate::sense::SenseKey;
+use std::fmt;
+
+/// Identity of the loaded cartridge as reported by the medium itself,
+/// rather than by a library barcode reader. Sources the drive or medium
+/// does not support are left as None.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CartridgeIdentity {
+  /// READ MEDIA SERIAL NUMBER
+  pub media_serial_number: Option <String>,
+  /// MAM Medium Serial Number attribute
+  pub medium_serial_number: Option <String>,
+  /// MAM Barcode attribute
+  pub barcode: Option <String>,
+  /// MAM Volume Identifier attribute
+  pub volume_identifier: Option <String>,
+  /// Medium type from the mode parameter header
+  pub medium_type: u8,
+}
+
+impl CartridgeIdentity {
+  fn from_parts(media_serial_number: Option <Vec <u8>>, attributes: &[Attribute], medium_type: u8)
+      -> CartridgeIdentity {
+    let attribute = |id| {
+      attributes.iter()
+          .find(|t| t.id == id)
+          .and_then(|t| t.as_string())
+          .filter(|t| !t.is_empty())
+    };
+
+    CartridgeIdentity {
+      media_serial_number: media_serial_number
+          .map(|t| String::from_utf8_lossy(&t)
+              .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+              .to_string())
+          .filter(|t| !t.is_empty()),
+      medium_serial_number: attribute(mam::MEDIUM_SERIAL_NUMBER),
+      barcode: attribute(mam::BARCODE),
+      volume_identifier: attribute(mam::VOLUME_IDENTIFIER),
+      medium_type,
+    }
+  }
+
+  /// Whether a label, such as the one a library reported, agrees with the
+  /// barcode or volume identifier recorded on the medium. None if the
+  /// medium records neither.
+  pub fn matches_label(&self, label: &str) -> Option <bool> {
+    let label = label.trim();
+    let recorded: Vec <&String> = self.barcode.iter().chain(self.volume_identifier.iter()).collect();
+    if recorded.is_empty() {
+      return None;
+    }
+
+    Some(recorded.iter().any(|t| t.trim() == label))
+  }
+
+  /// The most specific serial number available
+  pub fn serial_number(&self) -> Option <&str> {
+    self.media_serial_number.as_deref().or(self.medium_serial_number.as_deref())
+  }
+}
+
+impl fmt::Display for CartridgeIdentity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let unknown = "unknown".to_string();
+    writeln!(f, "Media Serial Number: {}", self.media_serial_number.as_ref().unwrap_or(&unknown))?;
+    writeln!(f, "Medium Serial Number: {}",
+        self.medium_serial_number.as_ref().unwrap_or(&unknown))?;
+    writeln!(f, "Barcode: {}", self.barcode.as_ref().unwrap_or(&unknown))?;
+    writeln!(f, "Volume Identifier: {}", self.volume_identifier.as_ref().unwrap_or(&unknown))?;
+    writeln!(f, "Medium Type: {:#04x}", self.medium_type)
+  }
+}
+
+pub fn read_media_serial_number(device: &Device) -> Result <Vec <u8>, String> {
+  let cmd = ReadMediaSerialNumber::new();
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("READ MEDIA SERIAL NUMBER", status)?;
+
+  cmd.parse_buffer(&buf).ok_or_else(|| "Could not parse media serial number".to_string())
+}
+
+/// Status of a command for an optional identity source, None if the drive
+/// or medium rejects it with ILLEGAL REQUEST as not supported
+fn optional_source(name: &str, status: DeviceStatus) -> Result <Option <DeviceStatus>, String> {
+  let unsupported = status.sense.as_ref()
+      .is_some_and(|t| matches!(t.sense_key, SenseKey::IllegalRequest));
+  if unsupported {
+    return Ok(None);
+  }
+
+  check_good(name, status).map(Some)
+}
+
+fn read_optional_media_serial_number(device: &Device) -> Result <Option <Vec <u8>>, String> {
+  let cmd = ReadMediaSerialNumber::new();
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  if optional_source("READ MEDIA SERIAL NUMBER", status)?.is_none() {
+    return Ok(None);
+  }
+
+  cmd.parse_buffer(&buf).map(Some).ok_or_else(|| "Could not parse media serial number".to_string())
+}
+
+fn read_optional_attributes(device: &Device) -> Result <Vec <Attribute>, String> {
+  let cmd = ReadAttribute {
+    service_action: ReadAttribute::ATTRIBUTE_VALUES,
+    allocation_length: 0x10000,
+    ..Default::default()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  if optional_source("READ ATTRIBUTE", status)?.is_none() {
+    return Ok(Vec::new());
+  }
+
+  mam::parse_values(&buf).ok_or_else(|| "Could not parse READ ATTRIBUTE data".to_string())
+}
+
+/// Collect the identity of the loaded cartridge. Fails unless a medium is
+/// loaded and ready, see [`Device::wait_until_ready`]. A drive or medium
+/// that does not support READ MEDIA SERIAL NUMBER or MAM still returns the
+/// sources it does have; any other failure of those is returned.
+pub fn read_cartridge_identity(device: &Device) -> Result <CartridgeIdentity, String> {
+  //MODE SENSE succeeds without a medium, and the other sources fail while
+  //one is loading. Unit attentions and deferred errors clear once reported.
+  const READY_ATTEMPTS: usize = 4;
+  let mut ready = false;
+  for _ in 0..READY_ATTEMPTS {
+    match device.test_unit_ready()? {
+      UnitReadiness::Ready => { ready = true; break; },
+      UnitReadiness::UnitAttention | UnitReadiness::DeferredError => {},
+      UnitReadiness::MediumNotPresent => { return Err("No medium is loaded".to_string()); },
+      UnitReadiness::BecomingReady(_) => { return Err("Medium is not ready".to_string()); },
+    }
+  }
+  if !ready {
+    return Err("Medium is not ready".to_string());
+  }
+
+  let header = read_mode_parameter_header(device)?;
+  let media_serial_number = read_optional_media_serial_number(device)?;
+  let attributes = read_optional_attributes(device)?;
+
+  Ok(CartridgeIdentity::from_parts(media_serial_number, &attributes, header.medium_type))
+}
+
+#[test]
+fn cartridge_test() {
+  let attributes = vec![
+    Attribute::from_str(mam::BARCODE, "ABC123L8"),
+    Attribute::from_str(mam::VOLUME_IDENTIFIER, ""),
+    Attribute::from_str(mam::MEDIUM_SERIAL_NUMBER, "SN0001"),
+  ];
+  let identity = CartridgeIdentity::from_parts(Some(b"  XYZ789\0\0".to_vec()), &attributes, 0x00);
+  assert_eq!(identity.media_serial_number, Some("XYZ789".to_string()));
+  assert_eq!(identity.medium_serial_number, Some("SN0001".to_string()));
+  assert_eq!(identity.barcode, Some("ABC123L8".to_string()));
+  assert_eq!(identity.volume_identifier, None);
+  assert_eq!(identity.serial_number(), Some("XYZ789"));
+  assert_eq!(identity.matches_label("ABC123L8 "), Some(true));
+  assert_eq!(identity.matches_label("ABC124L8"), Some(false));
+
+  let identity = CartridgeIdentity::from_parts(None, &[], 0x00);
+  assert_eq!(identity.matches_label("ABC123L8"), None);
+  assert_eq!(identity.serial_number(), None);
+}
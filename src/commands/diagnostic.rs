@@ -0,0 +1,175 @@
+use crate::commands::ReceiveDiagnosticResults;
+use crate::commands::RequestSense;
+use crate::commands::SendDiagnostic;
+use crate::commands::log::read_typed_log_page;
+use crate::commands::log_pages::SelfTestResults;
+use crate::device::{Device, DeviceStatus, check_good};
+use crate::sense::SenseKeySpecific;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const SUPPORTED_DIAGNOSTIC_PAGES: u8 = 0x00;
+
+/// SPC-4 Section 7.2.1 diagnostic page, used as SEND DIAGNOSTIC parameter
+/// data and RECEIVE DIAGNOSTIC RESULTS parameter data
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagnosticPage {
+  pub page_code: u8,
+  pub page_specific: u8,
+  pub data: Vec <u8>,
+}
+
+impl DiagnosticPage {
+  pub const HEADER_LEN: usize = 4;
+
+  /// Parse a page including its header. The buffer may be shorter than the
+  /// page length if the allocation length was.
+  pub fn from_bytes(buf: &[u8]) -> Option <DiagnosticPage> {
+    if buf.len() < Self::HEADER_LEN {
+      return None;
+    }
+
+    let length = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+    Some(DiagnosticPage {
+      page_code: buf[0],
+      page_specific: buf[1],
+      data: buf[Self::HEADER_LEN..buf.len().min(Self::HEADER_LEN + length)].to_vec(),
+    })
+  }
+
+  pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    if self.data.len() > u16::MAX as usize - Self::HEADER_LEN {
+      return Err("Page length must be in the range 0..2^16");
+    }
+
+    let mut data = Vec::with_capacity(Self::HEADER_LEN + self.data.len());
+    data.push(self.page_code);
+    data.push(self.page_specific);
+    data.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
+    data.extend_from_slice(&self.data);
+
+    Ok(data)
+  }
+}
+
+pub fn receive_diagnostic_page(device: &Device, page_code: u8) -> Result <DiagnosticPage, String> {
+  let cmd = ReceiveDiagnosticResults {
+    pcv: true,
+    page_code,
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf = vec![0; cmd.allocation_length.into()];
+  check_good("RECEIVE DIAGNOSTIC RESULTS", device.issue_cmd_with_output(&cmd, &mut buf)?)?;
+
+  cmd.parse_buffer(&buf)
+      .ok_or_else(|| format!("Could not parse diagnostic page {:#04x}", page_code))
+}
+
+/// Page codes listed in the Supported Diagnostic Pages page
+pub fn supported_diagnostic_pages(device: &Device) -> Result <Vec <u8>, String> {
+  Ok(receive_diagnostic_page(device, SUPPORTED_DIAGNOSTIC_PAGES)?.data)
+}
+
+/// Send a diagnostic page with the PF bit set. Results, if the page has any,
+/// are read back with [`receive_diagnostic_page`].
+pub fn send_diagnostic_page(device: &Device, page: &DiagnosticPage)
+    -> Result <DeviceStatus, String> {
+  let buf = page.to_bytes().map_err(|e| e.to_string())?;
+  let cmd = SendDiagnostic {
+    pf: true,
+    parameter_list_length: buf.len() as u16,
+    ..Default::default()
+  };
+  check_good("SEND DIAGNOSTIC", device.issue_cmd_with_input(&cmd, &buf)?)
+}
+
+/// Progress of a background self-test from the sense key specific field of
+/// REQUEST SENSE, out of 65536
+fn self_test_progress(device: &Device) -> Result <Option <u16>, String> {
+  let cmd = RequestSense::new();
+  let mut buf = vec![0; cmd.allocation_length.into()];
+  check_good("REQUEST SENSE", device.issue_cmd_with_output(&cmd, &mut buf)?)?;
+
+  Ok(match cmd.parse_buffer(&buf).map(|t| t.sense_key_specific) {
+    Some(SenseKeySpecific::NotReady(t)) => { Some(t.progress) },
+    _ => { None },
+  })
+}
+
+/// Run a self-test and return the Self-Test Results log page once it is
+/// done. See [`run_self_test_with_progress`].
+pub fn run_self_test(device: &Device, self_test_code: u8, timeout: Duration)
+    -> Result <SelfTestResults, String> {
+  run_self_test_with_progress(device, self_test_code, timeout, |_| {})
+}
+
+/// Run a self-test with SEND DIAGNOSTIC. A self_test_code of NO_SELF_TEST
+/// runs the default self-test with the SELFTEST bit. Foreground and default
+/// self-tests complete before the command does, so `timeout` is the timeout
+/// of the SEND DIAGNOSTIC itself. Background self-tests are polled until the
+/// most recent result is no longer in progress or `timeout` has passed,
+/// calling `progress` with the progress indication (out of 65536) each time.
+pub fn run_self_test_with_progress <F>
+    (device: &Device, self_test_code: u8, timeout: Duration, mut progress: F)
+    -> Result <SelfTestResults, String>
+    where F: FnMut(Option <u16>) {
+  const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+  let cmd = SendDiagnostic {
+    self_test_code,
+    self_test: self_test_code == SendDiagnostic::NO_SELF_TEST,
+    ..Default::default()
+  };
+  let background = matches!(self_test_code,
+      SendDiagnostic::BACKGROUND_SHORT | SendDiagnostic::BACKGROUND_EXTENDED);
+  if !background {
+    let status = device.issue_cmd_with_input_and_timeout(&cmd, &[], timeout)?;
+    check_good("SEND DIAGNOSTIC", status)?;
+    return read_typed_log_page(device);
+  }
+
+  check_good("SEND DIAGNOSTIC", device.issue_cmd_with_input(&cmd, &[])?)?;
+
+  let start = Instant::now();
+  loop {
+    thread::sleep(POLL_INTERVAL);
+
+    let results: SelfTestResults = read_typed_log_page(device)?;
+    if !results.most_recent().is_some_and(|t| t.in_progress()) {
+      return Ok(results);
+    }
+    progress(self_test_progress(device)?);
+
+    if start.elapsed() >= timeout {
+      return Err(format!("Self-test still in progress after {:?}", timeout));
+    }
+  }
+}
+
+#[test]
+fn diagnostic_test() {
+  use crate::Command;
+
+  let page = DiagnosticPage::from_bytes(&[0x00, 0x00, 0x00, 0x03, 0x00, 0x3F, 0x80, 0xFF]).unwrap();
+  assert_eq!(page.page_code, SUPPORTED_DIAGNOSTIC_PAGES);
+  assert_eq!(page.data, vec![0x00, 0x3F, 0x80]);
+  assert_eq!(page.to_bytes().unwrap(), vec![0x00, 0x00, 0x00, 0x03, 0x00, 0x3F, 0x80]);
+
+  let cmd = SendDiagnostic {
+    self_test_code: SendDiagnostic::BACKGROUND_EXTENDED,
+    ..Default::default()
+  };
+  assert_eq!(cmd.to_bytes().unwrap(), vec![0x1D, 0x40, 0x00, 0x00, 0x00, 0x00]);
+  let cmd = SendDiagnostic {
+    self_test_code: SendDiagnostic::FOREGROUND_SHORT,
+    self_test: true,
+    ..Default::default()
+  };
+  assert!(cmd.to_bytes().is_err());
+  let cmd = SendDiagnostic::from_bytes(&[0x1D, 0x14, 0x00, 0x01, 0x00, 0x00]).unwrap();
+  assert!(cmd.pf);
+  assert!(cmd.self_test);
+  assert_eq!(cmd.parameter_list_length, 0x100);
+}
@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 5.2
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Erase16 {
   pub fcs: bool,
   pub lcs: bool,
@@ -20,12 +23,33 @@ impl Erase16 {
   pub fn new() -> Erase16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Erase16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Erase16 {
+      fcs: buf[1] & 0x8 == 0x8,
+      lcs: buf[1] & 0x4 == 0x4,
+      immed: buf[1] & 0x2 == 0x2,
+      long: buf[1] & 0x1 == 0x1,
+      method: (buf[2] >> 4) & 0x3,
+      smd: buf[2] & 0x2 == 0x2,
+      vcm: buf[2] & 0x1 == 0x1,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+    })
+  }
 }
 
 impl crate::NoIO for Erase16 {
 }
 
 impl crate::Command for Erase16 {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -49,7 +73,7 @@ impl crate::Command for Erase16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 6.2
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Erase6 {
   pub immed: bool,
   pub long: bool,
@@ -16,12 +18,29 @@ impl Erase6 {
   pub fn new() -> Erase6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Erase6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Erase6 {
+      immed: buf[1] & 0x2 == 0x2,
+      long: buf[1] & 0x1 == 0x1,
+      method: (buf[2] >> 4) & 0x3,
+      smd: buf[2] & 0x2 == 0x2,
+      vcm: buf[2] & 0x1 == 0x1,
+    })
+  }
 }
 
 impl crate::NoIO for Erase6 {
 }
 
 impl crate::Command for Erase6 {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -41,7 +60,7 @@ impl crate::Command for Erase6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
@@ -0,0 +1,406 @@
+use crate::commands::ReadBuffer;
+use crate::commands::WriteBuffer;
+use crate::device::{Device, DeviceStatus, check_good};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+/// Largest data transfer issued by the helpers in this module
+pub const MAX_TRANSFER_LENGTH: u32 = 0x10000;
+
+/// Timeout for microcode WRITE BUFFER commands when the device does not
+/// report a recommended one. Saving and activating microcode can take
+/// minutes, well past the driver default.
+pub const MICROCODE_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+
+// SPC-4 Section 6.17.9 error history buffer IDs
+pub const ERROR_HISTORY_DIRECTORY: u8 = 0x00;
+pub const ERROR_HISTORY_DIRECTORY_NEW_SNAPSHOT: u8 = 0x01;
+pub const ERROR_HISTORY_DIRECTORY_NEW_I_T_NEXUS: u8 = 0x02;
+pub const ERROR_HISTORY_DIRECTORY_NEW_I_T_NEXUS_AND_SNAPSHOT: u8 = 0x03;
+pub const ERROR_HISTORY_CLEAR_I_T_NEXUS: u8 = 0xFE;
+pub const ERROR_HISTORY_RELEASE_SNAPSHOT: u8 = 0xFF;
+
+/// SPC-4 Section 6.17.5 READ BUFFER descriptor
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BufferDescriptor {
+  /// Buffer offsets must be a multiple of 2^offset_boundary, 0xFF means only
+  /// an offset of zero is supported
+  pub offset_boundary: u8,
+  pub buffer_capacity: u32,
+}
+
+impl BufferDescriptor {
+  pub fn from_bytes(buf: &[u8]) -> Option <BufferDescriptor> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    Some(BufferDescriptor {
+      offset_boundary: buf[0],
+      buffer_capacity: u32::from_be_bytes([0, buf[1], buf[2], buf[3]]),
+    })
+  }
+
+  /// Required alignment of buffer offsets, None if offsets are not supported
+  pub fn alignment(&self) -> Option <u32> {
+    match self.offset_boundary {
+      0xFF => { None },
+      t if t < 24 => { Some(1 << t) },
+      _ => { Some(1 << 24) },
+    }
+  }
+}
+
+/// SPC-4 Section 6.17.7 echo buffer descriptor
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EchoBufferDescriptor {
+  /// Echo buffer overwritten supported
+  pub ebos: bool,
+  pub buffer_capacity: u16,
+}
+
+impl EchoBufferDescriptor {
+  pub fn from_bytes(buf: &[u8]) -> Option <EchoBufferDescriptor> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    Some(EchoBufferDescriptor {
+      ebos: buf[0] & 0x01 == 0x01,
+      buffer_capacity: u16::from_be_bytes([buf[2], buf[3]]) & 0x1FFF,
+    })
+  }
+}
+
+/// SPC-4 Section 6.17.9.2 error history directory entry
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorHistoryDirectoryEntry {
+  pub buffer_id: u8,
+  pub buffer_format: u8,
+  pub buffer_source: u8,
+  pub maximum_available_length: u32,
+}
+
+/// SPC-4 Section 6.17.9.2 error history directory
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorHistoryDirectory {
+  pub vendor_identification: String,
+  pub version: u8,
+  /// Whether the current snapshot has been retrieved
+  pub ehs_retrieved: u8,
+  /// How the current snapshot was created
+  pub ehs_source: u8,
+  /// CLEAR support, the snapshot may be released
+  pub clr_sup: bool,
+  pub entries: Vec <ErrorHistoryDirectoryEntry>,
+}
+
+impl ErrorHistoryDirectory {
+  pub fn from_bytes(buf: &[u8]) -> Option <ErrorHistoryDirectory> {
+    if buf.len() < 32 {
+      return None;
+    }
+
+    let length = u16::from_be_bytes([buf[30], buf[31]]) as usize;
+    let entries = buf[32..buf.len().min(32 + length)]
+        .chunks_exact(8)
+        .map(|t| ErrorHistoryDirectoryEntry {
+          buffer_id: t[0],
+          buffer_format: t[1],
+          buffer_source: t[2] & 0x0F,
+          maximum_available_length: u32::from_be_bytes(t[4..8].try_into().unwrap()),
+        })
+        .collect();
+
+    Some(ErrorHistoryDirectory {
+      vendor_identification: String::from_utf8_lossy(&buf[0..8]).trim_end().to_string(),
+      version: buf[8],
+      ehs_retrieved: (buf[9] >> 3) & 0x03,
+      ehs_source: (buf[9] >> 1) & 0x03,
+      clr_sup: buf[9] & 0x01 == 0x01,
+      entries,
+    })
+  }
+
+  /// Entries holding error history data, as opposed to reserved buffer IDs
+  pub fn data_entries(&self) -> impl Iterator <Item = &ErrorHistoryDirectoryEntry> {
+    self.entries.iter().filter(|t| (0x10..=0xEF).contains(&t.buffer_id))
+  }
+}
+
+/// READ BUFFER parameter data, by mode
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadBufferOutput {
+  Descriptor(BufferDescriptor),
+  EchoBufferDescriptor(EchoBufferDescriptor),
+  ErrorHistoryDirectory(ErrorHistoryDirectory),
+}
+
+/// Split an image into (offset, length) segments that respect the offset
+/// boundary and capacity of the buffer, and the transfer limit
+pub fn firmware_segments(image_len: usize, descriptor: &BufferDescriptor, max_transfer: u32)
+    -> Result <Vec <(u32, u32)>, String> {
+  if image_len == 0 {
+    return Err("Firmware image is empty".to_string());
+  }
+  if image_len >= 1 << 24 {
+    return Err(format!("Firmware image of {} bytes is too large for WRITE BUFFER", image_len));
+  }
+
+  let limit = match descriptor.buffer_capacity {
+    0 => { max_transfer },
+    t => { t.min(max_transfer) },
+  };
+
+  let segment_len = match descriptor.alignment() {
+    Some(alignment) => { limit / alignment * alignment },
+    None if image_len as u32 <= limit => { image_len as u32 },
+    None => {
+      return Err(format!(
+          "Buffer does not support offsets and the {} byte image exceeds the {} byte limit",
+          image_len, limit));
+    },
+  };
+  if segment_len == 0 {
+    return Err(format!(
+        "Offset boundary {} leaves no room in a {} byte transfer",
+        descriptor.offset_boundary, limit));
+  }
+
+  let image_len = image_len as u32;
+  Ok((0..image_len)
+      .step_by(segment_len as usize)
+      .map(|t| (t, segment_len.min(image_len - t)))
+      .collect())
+}
+
+pub fn read_buffer_descriptor(device: &Device, buffer_id: u8) -> Result <BufferDescriptor, String> {
+  let cmd = ReadBuffer {
+    mode: ReadBuffer::DESCRIPTOR,
+    buffer_id,
+    buffer_offset: 0,
+    allocation_length: 4,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("READ BUFFER", status)?;
+
+  BufferDescriptor::from_bytes(&buf).ok_or_else(|| "Could not parse buffer descriptor".to_string())
+}
+
+/// Download a microcode image with a mode that takes buffer offsets, and
+/// activate it. See [`update_firmware_with_progress`].
+pub fn update_firmware(device: &Device, image: &[u8], buffer_id: u8)
+    -> Result <DeviceStatus, String> {
+  update_firmware_with_progress(device, image, buffer_id,
+      WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_SAVE_AND_DEFER_ACTIVATE, |_, _| {})
+}
+
+/// Recommended timeout for WRITE BUFFER, or [`MICROCODE_TIMEOUT`]
+fn microcode_timeout(device: &Device) -> Duration {
+  device.recommended_timeout::<WriteBuffer>().ok().flatten().unwrap_or(MICROCODE_TIMEOUT)
+}
+
+/// Download a microcode image in segments sized by the buffer descriptor.
+/// The progress callback gets the bytes sent so far and the image length.
+/// When the mode defers activation an ACTIVATE DEFERRED MICROCODE is sent
+/// once the image is saved. Each WRITE BUFFER uses the recommended timeout
+/// the device reports for it, or [`MICROCODE_TIMEOUT`].
+pub fn update_firmware_with_progress <F>
+    (device: &Device, image: &[u8], buffer_id: u8, mode: u8, mut progress: F)
+    -> Result <DeviceStatus, String>
+    where F: FnMut(usize, usize) {
+  let deferred = match mode {
+    WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_ACTIVATE |
+    WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_SAVE => { false },
+    WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_SELECT_ACTIVATION_SAVE_AND_DEFER_ACTIVATE |
+    WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_SAVE_AND_DEFER_ACTIVATE => { true },
+    _ => { return Err(format!("Mode {:#04x} does not download microcode with offsets", mode)); },
+  };
+
+  let descriptor = read_buffer_descriptor(device, buffer_id)?;
+  let segments = firmware_segments(image.len(), &descriptor, MAX_TRANSFER_LENGTH)?;
+  let timeout = microcode_timeout(device);
+
+  let mut status = None;
+  for (offset, length) in segments {
+    let cmd = WriteBuffer {
+      mode,
+      buffer_id,
+      buffer_offset: offset,
+      parameter_list_length: length,
+      ..Default::default()
+    };
+
+    let data = &image[offset as usize..(offset + length) as usize];
+    let result = device.issue_cmd_with_input_and_timeout(&cmd, data, timeout);
+    status = Some(check_good("WRITE BUFFER", result?)?);
+    progress((offset + length) as usize, image.len());
+  }
+
+  match (deferred, status) {
+    (true, _) => { activate_deferred_microcode(device) },
+    (false, Some(t)) => { Ok(t) },
+    (false, None) => { Err("Firmware image is empty".to_string()) },
+  }
+}
+
+/// Activate microcode saved by a mode that defers activation
+pub fn activate_deferred_microcode(device: &Device) -> Result <DeviceStatus, String> {
+  let cmd = WriteBuffer {
+    mode: WriteBuffer::ACTIVATE_DEFERRED_MICROCODE,
+    ..Default::default()
+  };
+
+  let status = device.issue_cmd_with_input_and_timeout(&cmd, &[], microcode_timeout(device))?;
+  check_good("WRITE BUFFER", status)
+}
+
+/// Read length bytes from a buffer, MAX_TRANSFER_LENGTH at a time
+fn read_buffer_data(device: &Device, mode: u8, buffer_id: u8, length: u32, alignment: u32)
+    -> Result <Vec <u8>, String> {
+  let segment_len = (MAX_TRANSFER_LENGTH / alignment * alignment).max(alignment);
+  let mut data = Vec::with_capacity(length as usize);
+
+  while (data.len() as u32) < length {
+    let cmd = ReadBuffer {
+      mode,
+      buffer_id,
+      buffer_offset: data.len() as u32,
+      allocation_length: segment_len.min(length - data.len() as u32),
+    };
+
+    let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+    let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+    check_good("READ BUFFER", status)?;
+    data.extend_from_slice(&buf);
+  }
+
+  Ok(data)
+}
+
+/// Read the whole of a data mode buffer, sized by its descriptor
+pub fn read_buffer(device: &Device, buffer_id: u8) -> Result <Vec <u8>, String> {
+  let descriptor = read_buffer_descriptor(device, buffer_id)?;
+  match descriptor.alignment() {
+    Some(alignment) => {
+      read_buffer_data(device, ReadBuffer::DATA, buffer_id, descriptor.buffer_capacity, alignment)
+    },
+    None => {
+      read_buffer_data(device, ReadBuffer::DATA, buffer_id, descriptor.buffer_capacity,
+          descriptor.buffer_capacity.max(1))
+    },
+  }
+}
+
+pub fn read_error_history_directory(device: &Device, buffer_id: u8)
+    -> Result <ErrorHistoryDirectory, String> {
+  let cmd = ReadBuffer {
+    mode: ReadBuffer::ERROR_HISTORY,
+    buffer_id,
+    buffer_offset: 0,
+    allocation_length: 0x2000,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("READ BUFFER", status)?;
+
+  ErrorHistoryDirectory::from_bytes(&buf)
+      .ok_or_else(|| "Could not parse error history directory".to_string())
+}
+
+pub fn read_error_history(device: &Device, entry: &ErrorHistoryDirectoryEntry)
+    -> Result <Vec <u8>, String> {
+  read_buffer_data(device, ReadBuffer::ERROR_HISTORY, entry.buffer_id,
+      entry.maximum_available_length.min((1 << 24) - 1), 1)
+}
+
+/// Release the error history snapshot so the device can discard it
+pub fn release_error_history(device: &Device) -> Result <DeviceStatus, String> {
+  let cmd = ReadBuffer {
+    mode: ReadBuffer::ERROR_HISTORY,
+    buffer_id: ERROR_HISTORY_RELEASE_SNAPSHOT,
+    ..Default::default()
+  };
+
+  let status = device.issue_cmd_with_output(&cmd, &mut [])?;
+  check_good("READ BUFFER", status)
+}
+
+/// Take a new error history snapshot, which holds the vendor diagnostic
+/// dump, and write every buffer in it to path. Returns the number of bytes
+/// written.
+pub fn save_dump(device: &Device, path: &str) -> Result <usize, String> {
+  let directory = read_error_history_directory(device, ERROR_HISTORY_DIRECTORY_NEW_SNAPSHOT)?;
+
+  let result = (|| {
+    let mut file = File::create(path).map_err(|e| format!("Could not create {}: {}", path, e))?;
+    let mut total = 0;
+    for entry in directory.data_entries() {
+      let data = read_error_history(device, entry)?;
+      file.write_all(&data).map_err(|e| format!("Could not write {}: {}", path, e))?;
+      total += data.len();
+    }
+    Ok(total)
+  })();
+
+  if directory.clr_sup {
+    let released = release_error_history(device);
+    if result.is_ok() {
+      released?;
+    }
+  }
+
+  result
+}
+
+/// Write the whole of a data mode buffer to path, for drives that expose
+/// their dump as a vendor buffer rather than error history
+pub fn save_buffer(device: &Device, buffer_id: u8, path: &str) -> Result <usize, String> {
+  let data = read_buffer(device, buffer_id)?;
+  let mut file = File::create(path).map_err(|e| format!("Could not create {}: {}", path, e))?;
+  file.write_all(&data).map_err(|e| format!("Could not write {}: {}", path, e))?;
+  Ok(data.len())
+}
+
+#[test]
+fn firmware_test() {
+  let descriptor = BufferDescriptor::from_bytes(&[0x09, 0x00, 0x80, 0x10]).unwrap();
+  assert_eq!(descriptor.alignment(), Some(512));
+  assert_eq!(descriptor.buffer_capacity, 0x8010);
+
+  let segments = firmware_segments(0x18000, &descriptor, MAX_TRANSFER_LENGTH).unwrap();
+  assert_eq!(segments, vec![(0, 0x8000), (0x8000, 0x8000), (0x10000, 0x8000)]);
+  let segments = firmware_segments(0x100, &descriptor, 0x40).unwrap_err();
+  assert!(segments.contains("no room"));
+
+  let descriptor = BufferDescriptor { offset_boundary: 0xFF, buffer_capacity: 0x1000 };
+  assert_eq!(firmware_segments(0x800, &descriptor, MAX_TRANSFER_LENGTH).unwrap(), vec![(0, 0x800)]);
+  assert!(firmware_segments(0x2000, &descriptor, MAX_TRANSFER_LENGTH).is_err());
+
+  let mut buf = b"VENDOR  ".to_vec();
+  buf.extend_from_slice(&[0x01, 0x0B]);
+  buf.extend_from_slice(&[0; 20]);
+  buf.extend_from_slice(&[0x00, 0x10]);
+  buf.extend_from_slice(&[0x10, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00]);
+  buf.extend_from_slice(&[ERROR_HISTORY_RELEASE_SNAPSHOT, 0, 0, 0, 0, 0, 0, 0]);
+  let directory = ErrorHistoryDirectory::from_bytes(&buf).unwrap();
+  assert_eq!(directory.vendor_identification, "VENDOR");
+  assert_eq!(directory.ehs_retrieved, 1);
+  assert_eq!(directory.ehs_source, 1);
+  assert!(directory.clr_sup);
+  assert_eq!(directory.entries.len(), 2);
+  let entries: Vec <_> = directory.data_entries().collect();
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].buffer_source, 1);
+  assert_eq!(entries[0].maximum_available_length, 0x10000);
+}
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 7.2
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FormatMedium {
   pub verify: bool,
   pub immed: bool,
@@ -18,12 +20,28 @@ impl FormatMedium {
   pub fn new() -> FormatMedium {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <FormatMedium> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(FormatMedium {
+      verify: buf[1] & 0x2 == 0x2,
+      immed: buf[1] & 0x1 == 0x1,
+      format: buf[2] & 0x0F,
+      transfer_length: u16::from_be_bytes([buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::Input for FormatMedium {
 }
 
 impl crate::Command for FormatMedium {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -41,7 +59,7 @@ impl crate::Command for FormatMedium {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
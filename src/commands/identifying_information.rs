@@ -0,0 +1,121 @@
+use crate::commands::ReportIdentifyingInformation;
+use crate::commands::SetIdentifyingInformation;
+use crate::device::{Device, DeviceStatus, check_good};
+
+// SPC-4 Section 6.26 information types
+/// Persists across power cycles and resets, commonly used as a device alias
+pub const PERIPHERAL_DEVICE: u8 = 0x00;
+pub const SUPPORTED_INFORMATION_TYPES: u8 = 0x7F;
+
+/// Largest identifying information of any type
+pub const MAX_INFORMATION_LENGTH: u32 = 512;
+
+/// SPC-4 Section 6.26.3 identifying information supported descriptor
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupportedInformationType {
+  pub information_type: u8,
+  pub maximum_information_length: u16,
+}
+
+/// REPORT IDENTIFYING INFORMATION parameter data
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IdentifyingInformationOutput {
+  Information(Vec <u8>),
+  Supported(Vec <SupportedInformationType>),
+}
+
+/// Contents after the 4 byte header, limited to the returned length
+fn contents(buf: &[u8]) -> Option <&[u8]> {
+  if buf.len() < 4 {
+    return None;
+  }
+
+  let length = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+  Some(&buf[4..buf.len().min(4 + length)])
+}
+
+pub(crate) fn parse_information(buf: &[u8]) -> Option <Vec <u8>> {
+  contents(buf).map(|t| t.to_vec())
+}
+
+pub(crate) fn parse_supported(buf: &[u8]) -> Option <Vec <SupportedInformationType>> {
+  Some(contents(buf)?
+      .chunks_exact(4)
+      .map(|t| SupportedInformationType {
+        information_type: t[0] >> 1,
+        maximum_information_length: u16::from_be_bytes([t[2], t[3]]),
+      })
+      .collect())
+}
+
+fn report(device: &Device, information_type: u8) -> Result <IdentifyingInformationOutput, String> {
+  let cmd = ReportIdentifyingInformation {
+    information_type,
+    ..ReportIdentifyingInformation::new()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("REPORT IDENTIFYING INFORMATION", status)?;
+
+  cmd.parse_buffer(&buf).ok_or_else(|| "Could not parse identifying information".to_string())
+}
+
+pub fn read_identifying_information(device: &Device, information_type: u8)
+    -> Result <Vec <u8>, String> {
+  match report(device, information_type)? {
+    IdentifyingInformationOutput::Information(t) => { Ok(t) },
+    IdentifyingInformationOutput::Supported(_) => {
+      Err("Use supported_information_types for the supported types".to_string())
+    },
+  }
+}
+
+pub fn supported_information_types(device: &Device)
+    -> Result <Vec <SupportedInformationType>, String> {
+  match report(device, SUPPORTED_INFORMATION_TYPES)? {
+    IdentifyingInformationOutput::Supported(t) => { Ok(t) },
+    IdentifyingInformationOutput::Information(_) => {
+      Err("Could not parse supported information types".to_string())
+    },
+  }
+}
+
+/// Replace the identifying information, an empty slice clears it
+pub fn set_identifying_information(device: &Device, information_type: u8, information: &[u8])
+    -> Result <DeviceStatus, String> {
+  let cmd = SetIdentifyingInformation {
+    information_type,
+    parameter_list_length: information.len() as u32,
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, information)?;
+  check_good("SET IDENTIFYING INFORMATION", status)
+}
+
+/// The peripheral device identifying information as text, with trailing
+/// NULs and spaces removed
+pub fn read_alias(device: &Device) -> Result <String, String> {
+  let information = read_identifying_information(device, PERIPHERAL_DEVICE)?;
+  Ok(String::from_utf8_lossy(&information)
+      .trim_end_matches(|c: char| c == '\0' || c.is_whitespace())
+      .to_string())
+}
+
+pub fn set_alias(device: &Device, alias: &str) -> Result <DeviceStatus, String> {
+  set_identifying_information(device, PERIPHERAL_DEVICE, alias.as_bytes())
+}
+
+#[test]
+fn identifying_information_test() {
+  let buf = [0x00, 0x00, 0x00, 0x06, b'd', b'r', b'v', b'0', 0x00, 0x00, 0xFF];
+  assert_eq!(parse_information(&buf), Some(b"drv0\0\0".to_vec()));
+
+  let buf = [0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x02, 0x00, 0x04, 0x00, 0x01, 0x00];
+  assert_eq!(parse_supported(&buf), Some(vec![
+    SupportedInformationType { information_type: 0, maximum_information_length: 0x200 },
+    SupportedInformationType { information_type: 2, maximum_information_length: 0x100 },
+  ]));
+}
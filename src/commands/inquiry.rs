@@ -0,0 +1,313 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::VpdPage;
+use crate::commands::vpd::ascii_field;
+use std::fmt;
+
+/// SPC-3 Section 6.4
+#[repr(C)]
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inquiry {
+  pub evpd: bool,
+  pub page_code: u8,
+  pub allocation_length: u16,
+}
+
+/// SPC-3 Section 6.4.2 standard INQUIRY data
+#[repr(C)]
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InquiryOutput {
+  pub peripheral_qualifier: u8,
+  pub peripheral_device_type: u8,
+  pub rmb: bool,
+  pub version: u8,
+  pub normaca: bool,
+  pub hisup: bool,
+  pub response_data_format: u8,
+  pub additional_length: u8,
+  pub sccs: bool,
+  pub acc: bool,
+  pub tpgs: u8,
+  pub three_pc: bool,
+  pub protect: bool,
+  pub encserv: bool,
+  pub multip: bool,
+  pub cmdque: bool,
+  pub vendor_identification: [u8; 8],
+  pub product_identification: [u8; 16],
+  pub product_revision_level: [u8; 4],
+  pub version_descriptors: [u16; 8],
+}
+
+impl Inquiry {
+  pub const STANDARD_LEN: usize = 36;
+  pub const FULL_LEN: usize = 96;
+  pub const VPD_LEN: usize = 1024;
+  const OP_CODE: u8 = 0x12;
+
+  pub fn new() -> Inquiry {
+    Default::default()
+  }
+
+  /// Request the vital product data page `page_code`
+  pub fn vpd(page_code: u8) -> Inquiry {
+    Inquiry {
+      evpd: true,
+      page_code,
+      allocation_length: Self::VPD_LEN as u16,
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Inquiry> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Inquiry {
+      evpd: buf[1] & 0x1 == 0x1,
+      page_code: buf[2],
+      allocation_length: u16::from_be_bytes([buf[3], buf[4]]),
+    })
+  }
+
+  /// Parse standard INQUIRY data. Returns None if EVPD is set or the buffer
+  /// is shorter than the 36 mandatory bytes.
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <InquiryOutput> {
+    if self.evpd || buf.len() < Self::STANDARD_LEN {
+      return None;
+    }
+
+    let mut rv = InquiryOutput {
+      peripheral_qualifier: buf[0] >> 5,
+      peripheral_device_type: buf[0] & 0x1F,
+      rmb: buf[1] & 0x80 == 0x80,
+      version: buf[2],
+      normaca: buf[3] & 0x20 == 0x20,
+      hisup: buf[3] & 0x10 == 0x10,
+      response_data_format: buf[3] & 0x0F,
+      additional_length: buf[4],
+      sccs: buf[5] & 0x80 == 0x80,
+      acc: buf[5] & 0x40 == 0x40,
+      tpgs: (buf[5] >> 4) & 0x3,
+      three_pc: buf[5] & 0x08 == 0x08,
+      protect: buf[5] & 0x01 == 0x01,
+      encserv: buf[6] & 0x40 == 0x40,
+      multip: buf[6] & 0x10 == 0x10,
+      cmdque: buf[7] & 0x02 == 0x02,
+      ..Default::default()
+    };
+    rv.vendor_identification.copy_from_slice(&buf[8..16]);
+    rv.product_identification.copy_from_slice(&buf[16..32]);
+    rv.product_revision_level.copy_from_slice(&buf[32..36]);
+
+    let end = std::cmp::min(buf.len(), rv.additional_length as usize + 5);
+    for (i, descriptor) in rv.version_descriptors.iter_mut().enumerate() {
+      let offset = 58 + i * 2;
+      if offset + 2 > end {
+        break;
+      }
+      *descriptor = u16::from_be_bytes([buf[offset], buf[offset + 1]]);
+    }
+
+    Some(rv)
+  }
+
+  /// Parse the vital product data page requested with EVPD set. Returns None
+  /// if EVPD is not set or the device returned a different page.
+  pub fn parse_vpd(&self, buf: &[u8]) -> Option <VpdPage> {
+    if !self.evpd || buf.len() < 4 || buf[1] != self.page_code {
+      return None;
+    }
+
+    VpdPage::from_bytes(buf)
+  }
+}
+
+impl InquiryOutput {
+  pub fn vendor(&self) -> String {
+    ascii_field(&self.vendor_identification)
+  }
+
+  pub fn product(&self) -> String {
+    ascii_field(&self.product_identification)
+  }
+
+  pub fn revision(&self) -> String {
+    ascii_field(&self.product_revision_level)
+  }
+}
+
+impl fmt::Display for InquiryOutput {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, concat!(
+        "Peripheral Qualifier: {}\n",
+        "Peripheral Device Type: {:#04x}\n",
+        "RMB: {}\n",
+        "Version: {:#04x}\n",
+        "Response Data Format: {}\n",
+        "Flags:{}{}{}{}{}{}{}{}{}\n",
+        "TPGS: {}\n",
+        "Vendor: {}\n",
+        "Product: {}\n",
+        "Revision: {}"),
+        self.peripheral_qualifier,
+        self.peripheral_device_type,
+        self.rmb,
+        self.version,
+        self.response_data_format,
+        if self.normaca { " NORMACA" } else { "" },
+        if self.hisup { " HISUP" } else { "" },
+        if self.sccs { " SCCS" } else { "" },
+        if self.acc { " ACC" } else { "" },
+        if self.three_pc { " 3PC" } else { "" },
+        if self.protect { " PROTECT" } else { "" },
+        if self.encserv { " ENCSERV" } else { "" },
+        if self.multip { " MULTIP" } else { "" },
+        if self.cmdque { " CMDQUE" } else { "" },
+        self.tpgs,
+        self.vendor(),
+        self.product(),
+        self.revision(),
+    )?;
+
+    let descriptors: Vec <String> = self.version_descriptors.iter()
+        .filter(|d| **d != 0)
+        .map(|d| format!("{:#06x}", d))
+        .collect();
+    if !descriptors.is_empty() {
+      writeln!(f, "Version Descriptors: {}", descriptors.join(" "))?;
+    }
+
+    Ok(())
+  }
+}
+
+impl crate::Output for Inquiry {
+}
+
+impl crate::Command for Inquiry {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    if !self.evpd && self.page_code != 0 {
+      return Err("Page code must be 0 when EVPD is not set");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.evpd { 0x1 } else { 0x0 };
+    data[2] = self.page_code;
+    data[3..5].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
+
+#[test]
+fn parse_standard_test() {
+  let mut buf = vec![0_u8; 74];
+  buf[0] = 0x01;
+  buf[1] = 0x80;
+  buf[2] = 0x06;
+  buf[3] = 0x12;
+  buf[4] = 69;
+  buf[5] = 0x09;
+  buf[6] = 0x10;
+  buf[7] = 0x02;
+  buf[8..16].copy_from_slice(b"IBM     ");
+  buf[16..32].copy_from_slice(b"ULT3580-TD8     ");
+  buf[32..36].copy_from_slice(b"MB00");
+  buf[58..60].copy_from_slice(&0x0460_u16.to_be_bytes());
+
+  let cmd = Inquiry { allocation_length: 74, ..Default::default() };
+  let out = cmd.parse_buffer(&buf).unwrap();
+  assert_eq!(out.peripheral_device_type, 0x01);
+  assert!(out.rmb);
+  assert!(out.hisup);
+  assert_eq!(out.response_data_format, 2);
+  assert!(out.three_pc);
+  assert!(out.protect);
+  assert!(out.multip);
+  assert!(out.cmdque);
+  assert_eq!(out.vendor(), "IBM");
+  assert_eq!(out.product(), "ULT3580-TD8");
+  assert_eq!(out.revision(), "MB00");
+  assert_eq!(out.version_descriptors[0], 0x0460);
+  assert_eq!(out.version_descriptors[1], 0);
+
+  assert!(cmd.parse_buffer(&buf[..20]).is_none());
+}
+
+
+// C Functions
+use crate::device::Device;
+use crate::device::DeviceStatus;
+
+#[no_mangle]
+pub extern "C" fn device_issue_inquiry(
+    device: *mut Device, cdb: *const Inquiry, buf: *mut u8, size: usize) -> *mut DeviceStatus {
+  let device = unsafe { &mut *device };
+  let cdb = unsafe { &*cdb };
+  let buf = unsafe { std::slice::from_raw_parts_mut(buf, size) };
+
+  let result = device.issue_cmd_with_output(cdb, buf);
+  match result {
+    Ok(rv) => {
+      let boxed_rv: Box <DeviceStatus> = Box::new(rv);
+      Box::into_raw(boxed_rv)
+    },
+    Err(e) => {
+      eprintln!("device_issue_inquiry: {}", e);
+      std::ptr::null_mut()
+    },
+  }
+}
+
+use crate::Command;
+
+#[no_mangle]
+pub extern "C" fn inquiry_status_to_stdout(cmd: *const Inquiry, status: *mut DeviceStatus) {
+  let cmd = unsafe { &*cmd };
+  let status = unsafe { &mut *status };
+  if let Some(sense) = &status.sense {
+    print!("{}", cmd.parse_sense(sense));
+  }
+}
+
+/// Fills in `output` from standard INQUIRY data. Returns false if the data
+/// could not be parsed.
+#[no_mangle]
+pub extern "C" fn inquiry_parse(
+    cmd: *const Inquiry, buf: *const u8, size: usize, output: *mut InquiryOutput) -> bool {
+  let cmd = unsafe { &*cmd };
+  let buf = unsafe { std::slice::from_raw_parts(buf, size) };
+  let output = unsafe { &mut *output };
+
+  match cmd.parse_buffer(buf) {
+    Some(rv) => { *output = rv; true },
+    None => { false },
+  }
+}
+
+#[no_mangle]
+pub extern "C" fn inquiry_output_to_stdout(output: *const InquiryOutput) {
+  let output = unsafe { &*output };
+  print!("{}", output);
+}
+
+#[no_mangle]
+pub extern "C" fn inquiry_vpd_to_stdout(cmd: *const Inquiry, buf: *const u8, size: usize) {
+  let cmd = unsafe { &*cmd };
+  let buf = unsafe { std::slice::from_raw_parts(buf, size) };
+  if let Some(page) = cmd.parse_vpd(buf) {
+    print!("{}", page);
+  }
+}
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 7.3
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LoadUnload {
   pub immed: bool,
   pub hold: bool,
@@ -16,12 +18,29 @@ impl LoadUnload {
   pub fn new() -> LoadUnload {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <LoadUnload> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(LoadUnload {
+      immed: buf[1] & 0x1 == 0x1,
+      hold: buf[4] & 0x8 == 0x8,
+      eot: buf[4] & 0x4 == 0x4,
+      reten: buf[4] & 0x2 == 0x2,
+      load: buf[4] & 0x1 == 0x1,
+    })
+  }
 }
 
 impl crate::NoIO for LoadUnload {
 }
 
 impl crate::Command for LoadUnload {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -36,7 +55,7 @@ impl crate::Command for LoadUnload {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::LocateSenseInfo;
 
 /// SSC-4 Section 6.3
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Locate10 {
   pub bt: bool,
   pub cp: bool,
@@ -16,12 +19,29 @@ impl Locate10 {
   pub fn new() -> Locate10 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Locate10> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Locate10 {
+      bt: buf[1] & 0x4 == 0x4,
+      cp: buf[1] & 0x2 == 0x2,
+      immed: buf[1] & 0x1 == 0x1,
+      logical_object_identifier: u32::from_be_bytes(buf[3..7].try_into().unwrap()),
+      partition: buf[8],
+    })
+  }
 }
 
 impl crate::NoIO for Locate10 {
 }
 
 impl crate::Command for Locate10 {
+  type SenseInfo = LocateSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 10];
 
@@ -36,7 +56,7 @@ impl crate::Command for Locate10 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> LocateSenseInfo {
+    LocateSenseInfo::from_sense(sense)
   }
 }
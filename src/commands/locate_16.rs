@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::LocateSenseInfo;
 
 /// SSC-4 Section 7.4
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Locate16 {
   pub dest_type: u8,
   pub rsvd: bool,
@@ -21,12 +24,31 @@ impl Locate16 {
   pub fn new() -> Locate16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Locate16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Locate16 {
+      dest_type: (buf[1] >> 3) & 0x7,
+      rsvd: buf[1] & 0x4 == 0x4,
+      cp: buf[1] & 0x2 == 0x2,
+      immed: buf[1] & 0x1 == 0x1,
+      bam: buf[2] & 0x1 == 0x1,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+    })
+  }
 }
 
 impl crate::NoIO for Locate16 {
 }
 
 impl crate::Command for Locate16 {
+  type SenseInfo = LocateSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -48,7 +70,7 @@ impl crate::Command for Locate16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> LocateSenseInfo {
+    LocateSenseInfo::from_sense(sense)
   }
 }
@@ -0,0 +1,348 @@
+use crate::commands::LogSense;
+use crate::commands::LogSelect;
+use crate::commands::TapeAlertLog;
+use crate::commands::log_pages::{*};
+use crate::device::{Device, DeviceStatus, check_good};
+use std::fmt;
+
+/// SPC-3 Section 7.2.2 log parameter
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogParameter {
+  pub parameter_code: u16,
+  pub du: bool,
+  pub tsd: bool,
+  pub etc: bool,
+  pub tmc: u8,
+  pub format_and_linking: u8,
+  pub value: Vec <u8>,
+}
+
+/// Parameter value decoded according to its FORMAT AND LINKING field
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LogValue {
+  Counter(u64),
+  Ascii(String),
+  Binary(Vec <u8>),
+}
+
+impl LogParameter {
+  pub const BOUNDED_DATA_COUNTER: u8 = 0x0;
+  pub const ASCII_FORMAT_LIST: u8 = 0x1;
+  pub const DATA_COUNTER: u8 = 0x2;
+  pub const BINARY_FORMAT_LIST: u8 = 0x3;
+
+  /// Parse one parameter from the front of `buf`, returning it and its length
+  pub fn from_bytes(buf: &[u8]) -> Option <(LogParameter, usize)> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let end = 4 + buf[3] as usize;
+    if end > buf.len() {
+      return None;
+    }
+
+    Some((LogParameter {
+      parameter_code: u16::from_be_bytes([buf[0], buf[1]]),
+      du: buf[2] & 0x80 == 0x80,
+      tsd: buf[2] & 0x20 == 0x20,
+      etc: buf[2] & 0x10 == 0x10,
+      tmc: (buf[2] >> 2) & 0x3,
+      format_and_linking: buf[2] & 0x3,
+      value: buf[4..end].to_vec(),
+    }, end))
+  }
+
+  pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    if self.value.len() > u8::MAX.into() {
+      return Err("Parameter length must be in the range 0..2^8");
+    }
+
+    let mut data = Vec::with_capacity(self.value.len() + 4);
+    data.extend_from_slice(&self.parameter_code.to_be_bytes());
+    data.push(if self.du { 0x80 } else { 0x00 }
+        | if self.tsd { 0x20 } else { 0x00 }
+        | if self.etc { 0x10 } else { 0x00 }
+        | ((self.tmc & 0x3) << 2)
+        | (self.format_and_linking & 0x3));
+    data.push(self.value.len() as u8);
+    data.extend_from_slice(&self.value);
+
+    Ok(data)
+  }
+
+  /// The value as a big-endian unsigned integer, if it fits in 64 bits
+  pub fn counter(&self) -> Option <u64> {
+    if self.value.is_empty() || self.value.len() > 8 {
+      return None;
+    }
+
+    Some(self.value.iter().fold(0, |acc, b| (acc << 8) | u64::from(*b)))
+  }
+
+  pub fn decode(&self) -> LogValue {
+    match self.format_and_linking {
+      Self::ASCII_FORMAT_LIST => {
+        LogValue::Ascii(String::from_utf8_lossy(&self.value).trim_end().to_string())
+      },
+      Self::BINARY_FORMAT_LIST => { LogValue::Binary(self.value.clone()) },
+      _ => {
+        match self.counter() {
+          Some(t) => { LogValue::Counter(t) },
+          None => { LogValue::Binary(self.value.clone()) },
+        }
+      },
+    }
+  }
+}
+
+impl fmt::Display for LogParameter {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:#06x}: ", self.parameter_code)?;
+    match self.decode() {
+      LogValue::Counter(t) => { write!(f, "{}", t) },
+      LogValue::Ascii(t) => { write!(f, "{}", t) },
+      LogValue::Binary(t) => { write!(f, "{:02x?}", t) },
+    }
+  }
+}
+
+/// SPC-3 Section 7.2.1 log page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogPage {
+  pub ds: bool,
+  pub spf: bool,
+  pub page_code: u8,
+  pub subpage_code: u8,
+  pub parameters: Vec <LogParameter>,
+}
+
+impl LogPage {
+  pub const HEADER_LEN: usize = 4;
+
+  /// Parse a page including its header. The page length field is honoured,
+  /// but the buffer may be shorter if the allocation length was.
+  pub fn from_bytes(buf: &[u8]) -> Option <LogPage> {
+    if buf.len() < Self::HEADER_LEN {
+      return None;
+    }
+
+    let page_length = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+    let end = std::cmp::min(buf.len(), Self::HEADER_LEN + page_length);
+
+    let mut parameters = Vec::new();
+    let mut offset = Self::HEADER_LEN;
+    while offset < end {
+      match LogParameter::from_bytes(&buf[offset..end]) {
+        Some((parameter, len)) => { parameters.push(parameter); offset += len; },
+        None => { break; },
+      }
+    }
+
+    Some(LogPage {
+      ds: buf[0] & 0x80 == 0x80,
+      spf: buf[0] & 0x40 == 0x40,
+      page_code: buf[0] & 0x3F,
+      subpage_code: buf[1],
+      parameters,
+    })
+  }
+
+  /// Encode the page for LOG SELECT
+  pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; Self::HEADER_LEN];
+    data[0] = (self.page_code & 0x3F)
+        | if self.ds { 0x80 } else { 0x00 }
+        | if self.spf { 0x40 } else { 0x00 };
+    data[1] = self.subpage_code;
+
+    for parameter in &self.parameters {
+      data.extend(parameter.to_bytes()?);
+    }
+
+    let page_length = data.len() - Self::HEADER_LEN;
+    if page_length > u16::MAX.into() {
+      return Err("Page length must be in the range 0..2^16");
+    }
+    data[2..4].copy_from_slice(&(page_length as u16).to_be_bytes());
+
+    Ok(data)
+  }
+
+  pub fn parameter(&self, parameter_code: u16) -> Option <&LogParameter> {
+    self.parameters.iter().find(|p| p.parameter_code == parameter_code)
+  }
+
+  /// The counter value of a parameter, if present
+  pub fn counter(&self, parameter_code: u16) -> Option <u64> {
+    self.parameter(parameter_code).and_then(|p| p.counter())
+  }
+
+  /// The value of a parameter as trimmed ASCII, if present
+  pub fn ascii(&self, parameter_code: u16) -> Option <String> {
+    self.parameter(parameter_code).map(|p| {
+      String::from_utf8_lossy(&p.value).trim_matches(|c: char| c == '\0' || c.is_whitespace())
+          .to_string()
+    })
+  }
+
+  /// Decode with the typed parser registered for this page, if any
+  pub fn decode(&self) -> TypedLogPage {
+    TypedLogPage::from_page(self)
+  }
+}
+
+impl fmt::Display for LogPage {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Log Page {:#04x}/{:#04x}:", self.page_code, self.subpage_code)?;
+    for parameter in &self.parameters {
+      writeln!(f, "  {}", parameter)?;
+    }
+    Ok(())
+  }
+}
+
+/// Typed view of a log page. Implementors are listed in the `log_pages!`
+/// registry so [`LogPage::decode`] can find them.
+pub trait LogPageType: Sized + fmt::Debug + fmt::Display {
+  const PAGE_CODE: u8;
+  const SUBPAGE_CODE: u8 = 0;
+
+  /// Whether this type parses `page`; override for a family of subpages
+  fn matches(page: &LogPage) -> bool {
+    page.page_code == Self::PAGE_CODE && page.subpage_code == Self::SUBPAGE_CODE
+  }
+
+  fn from_page(page: &LogPage) -> Option <Self>;
+}
+
+macro_rules! log_pages {
+  ( $( $name:ident ),* $(,)? ) => {
+    /// A log page decoded by its registered typed parser
+    #[derive(Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum TypedLogPage {
+      $( $name($name), )*
+      Unknown(LogPage),
+    }
+
+    impl TypedLogPage {
+      pub fn from_page(page: &LogPage) -> TypedLogPage {
+        $(
+          if $name::matches(page) {
+            if let Some(t) = $name::from_page(page) {
+              return TypedLogPage::$name(t);
+            }
+          }
+        )*
+        TypedLogPage::Unknown(page.clone())
+      }
+    }
+
+    impl fmt::Display for TypedLogPage {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+          $( TypedLogPage::$name(t) => { write!(f, "{}", t) }, )*
+          TypedLogPage::Unknown(t) => { write!(f, "{}", t) },
+        }
+      }
+    }
+  };
+}
+
+log_pages! {
+  WriteErrorCounters,
+  ReadErrorCounters,
+  SequentialAccessDevice,
+  SelfTestResults,
+  DeviceStatistics,
+  VolumeStatistics,
+  DataCompressionLog,
+  TapeCapacity,
+  TapeAlertLog,
+}
+
+/// Read one page with LOG SENSE and the given page control
+pub fn read_log_page(device: &Device, pc: u8, page_code: u8, subpage_code: u8)
+    -> Result <LogPage, String> {
+  let cmd = LogSense {
+    pc,
+    page_code,
+    subpage_code,
+    allocation_length: u16::MAX,
+    ..Default::default()
+  };
+
+  let mut buf = vec![0; cmd.allocation_length.into()];
+  check_good("LOG SENSE", device.issue_cmd_with_output(&cmd, &mut buf)?)?;
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { Ok(t) },
+    None => { Err("Could not parse LOG SENSE data".to_string()) },
+  }
+}
+
+/// Typed form of [`read_log_page`] for the current cumulative values
+pub fn read_typed_log_page <T: LogPageType> (device: &Device) -> Result <T, String> {
+  let page = read_log_page(device, LogSense::CUMULATIVE, T::PAGE_CODE, T::SUBPAGE_CODE)?;
+  match T::from_page(&page) {
+    Some(t) => { Ok(t) },
+    None => { Err(format!("Could not parse log page {:#04x}", T::PAGE_CODE)) },
+  }
+}
+
+/// Reset the parameters selected by `pc` to their defaults with the PCR bit.
+/// A page code of 0 resets every page.
+pub fn reset_log_parameters(device: &Device, pc: u8, page_code: u8, subpage_code: u8)
+    -> Result <DeviceStatus, String> {
+  let cmd = LogSelect {
+    pcr: true,
+    pc,
+    page_code,
+    subpage_code,
+    ..Default::default()
+  };
+  check_good("LOG SELECT", device.issue_cmd_with_input(&cmd, &[])?)
+}
+
+/// Write the threshold values in `page` with LOG SELECT. If `save` is set
+/// the savable parameters are also saved.
+pub fn write_log_thresholds(device: &Device, page: &LogPage, save: bool)
+    -> Result <DeviceStatus, String> {
+  let buf = page.to_bytes().map_err(|e| e.to_string())?;
+  let cmd = LogSelect {
+    sp: save,
+    pc: LogSelect::THRESHOLD,
+    parameter_list_length: buf.len() as u16,
+    ..Default::default()
+  };
+  check_good("LOG SELECT", device.issue_cmd_with_input(&cmd, &buf)?)
+}
+
+#[test]
+fn log_page_test() {
+  let buf = vec![
+    0x0C, 0x00, 0x00, 0x1A,
+    // Bounded data counter
+    0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+    // ASCII format list
+    0x00, 0x01, 0x01, 0x04, b'A', b'B', b'C', b' ',
+    // Binary format list with DU, ETC and TMC set
+    0x01, 0x00, 0x9F, 0x02, 0x12, 0x34,
+  ];
+
+  let page = LogPage::from_bytes(&buf).unwrap();
+  assert_eq!(page.page_code, 0x0C);
+  assert_eq!(page.parameters.len(), 3);
+  assert_eq!(page.counter(0x0000), Some(0x10000));
+  assert_eq!(page.parameter(0x0001).unwrap().decode(), LogValue::Ascii("ABC".to_string()));
+  let binary = page.parameter(0x0100).unwrap();
+  assert!(binary.du);
+  assert!(binary.etc);
+  assert_eq!(binary.tmc, 3);
+  assert_eq!(binary.decode(), LogValue::Binary(vec![0x12, 0x34]));
+  assert_eq!(page.to_bytes(), Ok(buf));
+}
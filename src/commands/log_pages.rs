@@ -0,0 +1,755 @@
+use crate::commands::LogPage;
+use crate::commands::LogPageType;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+fn write_counter(f: &mut fmt::Formatter, name: &str, value: Option <u64>) -> fmt::Result {
+  match value {
+    Some(t) => { writeln!(f, "  {}: {}", name, t) },
+    None => { Ok(()) },
+  }
+}
+
+/// SPC-3 Section 7.2.4 error counter parameters, shared by the write, read
+/// and verify error counter pages
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorCounters {
+  pub corrected_without_substantial_delay: Option <u64>,
+  pub corrected_with_possible_delays: Option <u64>,
+  pub total_rewrites_or_rereads: Option <u64>,
+  pub total_errors_corrected: Option <u64>,
+  pub total_times_correction_algorithm_processed: Option <u64>,
+  pub total_bytes_processed: Option <u64>,
+  pub total_uncorrected_errors: Option <u64>,
+}
+
+impl ErrorCounters {
+  fn from_page(page: &LogPage) -> ErrorCounters {
+    ErrorCounters {
+      corrected_without_substantial_delay: page.counter(0x0000),
+      corrected_with_possible_delays: page.counter(0x0001),
+      total_rewrites_or_rereads: page.counter(0x0002),
+      total_errors_corrected: page.counter(0x0003),
+      total_times_correction_algorithm_processed: page.counter(0x0004),
+      total_bytes_processed: page.counter(0x0005),
+      total_uncorrected_errors: page.counter(0x0006),
+    }
+  }
+}
+
+impl fmt::Display for ErrorCounters {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write_counter(f, "Corrected Without Substantial Delay",
+        self.corrected_without_substantial_delay)?;
+    write_counter(f, "Corrected With Possible Delays", self.corrected_with_possible_delays)?;
+    write_counter(f, "Total Rewrites or Rereads", self.total_rewrites_or_rereads)?;
+    write_counter(f, "Total Errors Corrected", self.total_errors_corrected)?;
+    write_counter(f, "Total Times Correction Algorithm Processed",
+        self.total_times_correction_algorithm_processed)?;
+    write_counter(f, "Total Bytes Processed", self.total_bytes_processed)?;
+    write_counter(f, "Total Uncorrected Errors", self.total_uncorrected_errors)
+  }
+}
+
+/// SPC-3 Section 7.2.4 Write Error Counters log page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteErrorCounters(pub ErrorCounters);
+
+impl LogPageType for WriteErrorCounters {
+  const PAGE_CODE: u8 = 0x02;
+
+  fn from_page(page: &LogPage) -> Option <WriteErrorCounters> {
+    Some(WriteErrorCounters(ErrorCounters::from_page(page)))
+  }
+}
+
+impl fmt::Display for WriteErrorCounters {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Write Error Counters:")?;
+    write!(f, "{}", self.0)
+  }
+}
+
+/// SPC-3 Section 7.2.4 Read Error Counters log page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadErrorCounters(pub ErrorCounters);
+
+impl LogPageType for ReadErrorCounters {
+  const PAGE_CODE: u8 = 0x03;
+
+  fn from_page(page: &LogPage) -> Option <ReadErrorCounters> {
+    Some(ReadErrorCounters(ErrorCounters::from_page(page)))
+  }
+}
+
+impl fmt::Display for ReadErrorCounters {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Read Error Counters:")?;
+    write!(f, "{}", self.0)
+  }
+}
+
+/// SSC-4 Section 8.2.2 Sequential-Access Device log page. Capacities are in
+/// megabytes.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequentialAccessDevice {
+  /// Before compression
+  pub data_bytes_received: Option <u64>,
+  /// After compression
+  pub data_bytes_written: Option <u64>,
+  /// Before decompression
+  pub data_bytes_read: Option <u64>,
+  /// After decompression
+  pub data_bytes_transferred: Option <u64>,
+  pub native_capacity_bop_to_eod: Option <u64>,
+  pub native_capacity_bop_to_ew: Option <u64>,
+  pub minimum_native_capacity_ew_to_eop: Option <u64>,
+  pub native_capacity_bop_to_current_position: Option <u64>,
+  pub maximum_native_capacity_in_object_buffer: Option <u64>,
+  pub cleaning_required: bool,
+}
+
+impl LogPageType for SequentialAccessDevice {
+  const PAGE_CODE: u8 = 0x0C;
+
+  fn from_page(page: &LogPage) -> Option <SequentialAccessDevice> {
+    Some(SequentialAccessDevice {
+      data_bytes_received: page.counter(0x0000),
+      data_bytes_written: page.counter(0x0001),
+      data_bytes_read: page.counter(0x0002),
+      data_bytes_transferred: page.counter(0x0003),
+      native_capacity_bop_to_eod: page.counter(0x0004),
+      native_capacity_bop_to_ew: page.counter(0x0005),
+      minimum_native_capacity_ew_to_eop: page.counter(0x0006),
+      native_capacity_bop_to_current_position: page.counter(0x0007),
+      maximum_native_capacity_in_object_buffer: page.counter(0x0008),
+      cleaning_required: page.counter(0x0100).is_some_and(|t| t != 0),
+    })
+  }
+}
+
+impl fmt::Display for SequentialAccessDevice {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Sequential-Access Device:")?;
+    write_counter(f, "Data Bytes Received", self.data_bytes_received)?;
+    write_counter(f, "Data Bytes Written", self.data_bytes_written)?;
+    write_counter(f, "Data Bytes Read", self.data_bytes_read)?;
+    write_counter(f, "Data Bytes Transferred", self.data_bytes_transferred)?;
+    write_counter(f, "Native Capacity BOP to EOD (MB)", self.native_capacity_bop_to_eod)?;
+    write_counter(f, "Native Capacity BOP to EW (MB)", self.native_capacity_bop_to_ew)?;
+    write_counter(f, "Minimum Native Capacity EW to EOP (MB)",
+        self.minimum_native_capacity_ew_to_eop)?;
+    write_counter(f, "Native Capacity BOP to Current Position (MB)",
+        self.native_capacity_bop_to_current_position)?;
+    write_counter(f, "Maximum Native Capacity in Object Buffer (MB)",
+        self.maximum_native_capacity_in_object_buffer)?;
+    writeln!(f, "  Cleaning Required: {}", self.cleaning_required)
+  }
+}
+
+/// SPC-4 Section 7.3.16.2 self-test results log parameter
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestResult {
+  /// 1 for the most recent self-test, up to 20
+  pub parameter_code: u16,
+  pub self_test_code: u8,
+  pub self_test_results: u8,
+  pub self_test_segment_number: u8,
+  pub accumulated_power_on_hours: u16,
+  pub address_of_first_failure: u64,
+  pub sense_key: u8,
+  pub additional_sense_code: u8,
+  pub additional_sense_code_qualifier: u8,
+  pub vendor_specific: u8,
+}
+
+impl SelfTestResult {
+  pub const COMPLETED: u8 = 0x0;
+  pub const ABORTED_BY_SEND_DIAGNOSTIC: u8 = 0x1;
+  pub const ABORTED: u8 = 0x2;
+  pub const UNKNOWN_ERROR: u8 = 0x3;
+  pub const FAILED_UNKNOWN_SEGMENT: u8 = 0x4;
+  pub const FAILED_FIRST_SEGMENT: u8 = 0x5;
+  pub const FAILED_SECOND_SEGMENT: u8 = 0x6;
+  pub const FAILED_SEGMENT: u8 = 0x7;
+  pub const IN_PROGRESS: u8 = 0xF;
+
+  fn from_bytes(parameter_code: u16, buf: &[u8]) -> Option <SelfTestResult> {
+    if buf.len() < 16 {
+      return None;
+    }
+
+    Some(SelfTestResult {
+      parameter_code,
+      self_test_code: buf[0] >> 5,
+      self_test_results: buf[0] & 0x0F,
+      self_test_segment_number: buf[1],
+      accumulated_power_on_hours: u16::from_be_bytes([buf[2], buf[3]]),
+      address_of_first_failure: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      sense_key: buf[12] & 0x0F,
+      additional_sense_code: buf[13],
+      additional_sense_code_qualifier: buf[14],
+      vendor_specific: buf[15],
+    })
+  }
+
+  /// An unused parameter is all zeros
+  pub fn is_empty(&self) -> bool {
+    *self == SelfTestResult { parameter_code: self.parameter_code, ..Default::default() }
+  }
+
+  pub fn passed(&self) -> bool {
+    self.self_test_results == Self::COMPLETED
+  }
+
+  pub fn in_progress(&self) -> bool {
+    self.self_test_results == Self::IN_PROGRESS
+  }
+
+  pub fn results_description(&self) -> &'static str {
+    match self.self_test_results {
+      Self::COMPLETED => { "Completed without error" },
+      Self::ABORTED_BY_SEND_DIAGNOSTIC => { "Aborted by SEND DIAGNOSTIC" },
+      Self::ABORTED => { "Aborted" },
+      Self::UNKNOWN_ERROR => { "Unknown error" },
+      Self::FAILED_UNKNOWN_SEGMENT => { "Failed in an unknown segment" },
+      Self::FAILED_FIRST_SEGMENT => { "Failed in the first segment" },
+      Self::FAILED_SECOND_SEGMENT => { "Failed in the second segment" },
+      Self::FAILED_SEGMENT => { "Failed in the segment given by the segment number" },
+      Self::IN_PROGRESS => { "In progress" },
+      _ => { "Reserved" },
+    }
+  }
+}
+
+impl fmt::Display for SelfTestResult {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "  {}: code {} at {} hours: {}", self.parameter_code, self.self_test_code,
+        self.accumulated_power_on_hours, self.results_description())?;
+    if self.self_test_results == Self::FAILED_SEGMENT {
+      write!(f, " {}", self.self_test_segment_number)?;
+    }
+    if self.sense_key != 0 {
+      write!(f, " (sense key {:#x}, ASC/ASCQ {:02X}/{:02X})", self.sense_key,
+          self.additional_sense_code, self.additional_sense_code_qualifier)?;
+    }
+    writeln!(f)
+  }
+}
+
+/// SPC-4 Section 7.3.16 Self-Test Results log page. Empty parameters are
+/// left out, the most recent self-test comes first.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestResults {
+  pub results: Vec <SelfTestResult>,
+}
+
+impl SelfTestResults {
+  pub fn most_recent(&self) -> Option <&SelfTestResult> {
+    self.results.first()
+  }
+}
+
+impl LogPageType for SelfTestResults {
+  const PAGE_CODE: u8 = 0x10;
+
+  fn from_page(page: &LogPage) -> Option <SelfTestResults> {
+    let mut results: Vec <SelfTestResult> = page.parameters.iter()
+        .filter(|t| (0x0001..=0x0014).contains(&t.parameter_code))
+        .filter_map(|t| SelfTestResult::from_bytes(t.parameter_code, &t.value))
+        .filter(|t| !t.is_empty())
+        .collect();
+    results.sort_by_key(|t| t.parameter_code);
+
+    Some(SelfTestResults { results })
+  }
+}
+
+impl fmt::Display for SelfTestResults {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Self-Test Results:")?;
+    for result in &self.results {
+      write!(f, "{}", result)?;
+    }
+    Ok(())
+  }
+}
+
+/// SSC-4 Section 8.2.4 Device Statistics log page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceStatistics {
+  pub lifetime_volume_loads: Option <u64>,
+  pub lifetime_cleaning_operations: Option <u64>,
+  pub lifetime_power_on_hours: Option <u64>,
+  pub lifetime_medium_motion_hours: Option <u64>,
+  pub lifetime_meters_of_tape_processed: Option <u64>,
+  pub medium_motion_hours_since_last_cleaning: Option <u64>,
+  pub lifetime_power_cycles: Option <u64>,
+  pub volume_loads_since_last_reset: Option <u64>,
+  pub hard_write_errors: Option <u64>,
+  pub hard_read_errors: Option <u64>,
+}
+
+impl LogPageType for DeviceStatistics {
+  const PAGE_CODE: u8 = 0x14;
+
+  fn from_page(page: &LogPage) -> Option <DeviceStatistics> {
+    Some(DeviceStatistics {
+      lifetime_volume_loads: page.counter(0x0000),
+      lifetime_cleaning_operations: page.counter(0x0001),
+      lifetime_power_on_hours: page.counter(0x0002),
+      lifetime_medium_motion_hours: page.counter(0x0003),
+      lifetime_meters_of_tape_processed: page.counter(0x0004),
+      medium_motion_hours_since_last_cleaning: page.counter(0x0008),
+      lifetime_power_cycles: page.counter(0x000C),
+      volume_loads_since_last_reset: page.counter(0x000D),
+      hard_write_errors: page.counter(0x000E),
+      hard_read_errors: page.counter(0x000F),
+    })
+  }
+}
+
+impl fmt::Display for DeviceStatistics {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Device Statistics:")?;
+    write_counter(f, "Lifetime Volume Loads", self.lifetime_volume_loads)?;
+    write_counter(f, "Lifetime Cleaning Operations", self.lifetime_cleaning_operations)?;
+    write_counter(f, "Lifetime Power On Hours", self.lifetime_power_on_hours)?;
+    write_counter(f, "Lifetime Medium Motion Hours", self.lifetime_medium_motion_hours)?;
+    write_counter(f, "Lifetime Meters of Tape Processed",
+        self.lifetime_meters_of_tape_processed)?;
+    write_counter(f, "Medium Motion Hours Since Last Cleaning",
+        self.medium_motion_hours_since_last_cleaning)?;
+    write_counter(f, "Lifetime Power Cycles", self.lifetime_power_cycles)?;
+    write_counter(f, "Volume Loads Since Last Reset", self.volume_loads_since_last_reset)?;
+    write_counter(f, "Hard Write Errors", self.hard_write_errors)?;
+    write_counter(f, "Hard Read Errors", self.hard_read_errors)
+  }
+}
+
+/// Per-partition value from a Volume Statistics partition record list
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionValue {
+  pub partition_number: u16,
+  pub value: u64,
+}
+
+fn partition_records(page: &LogPage, parameter_code: u16) -> Vec <PartitionValue> {
+  let mut rv = Vec::new();
+  let buf = match page.parameter(parameter_code) {
+    Some(t) => { &t.value },
+    None => { return rv; },
+  };
+
+  let mut offset = 0;
+  while offset < buf.len() {
+    let end = offset + 1 + buf[offset] as usize;
+    if end > buf.len() || end < offset + 4 {
+      break;
+    }
+
+    rv.push(PartitionValue {
+      partition_number: u16::from_be_bytes([buf[offset + 2], buf[offset + 3]]),
+      value: buf[offset + 4..end].iter().fold(0, |acc, b| (acc << 8) | u64::from(*b)),
+    });
+    offset = end;
+  }
+
+  rv
+}
+
+/// SSC-4 Section 8.2.6 Volume Statistics log page. Capacities are in
+/// megabytes.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VolumeStatistics {
+  pub subpage_code: u8,
+  pub page_valid: bool,
+  pub thread_count: Option <u64>,
+  pub total_data_sets_written: Option <u64>,
+  pub total_write_retries: Option <u64>,
+  pub total_unrecovered_write_errors: Option <u64>,
+  pub total_data_sets_read: Option <u64>,
+  pub total_read_retries: Option <u64>,
+  pub total_unrecovered_read_errors: Option <u64>,
+  pub last_mount_megabytes_written: Option <u64>,
+  pub last_mount_megabytes_read: Option <u64>,
+  pub lifetime_megabytes_written: Option <u64>,
+  pub lifetime_megabytes_read: Option <u64>,
+  pub total_native_capacity: Option <u64>,
+  pub total_used_native_capacity: Option <u64>,
+  pub volume_serial_number: Option <String>,
+  pub volume_barcode: Option <String>,
+  pub volume_manufacturer: Option <String>,
+  pub write_protect: Option <bool>,
+  pub worm: Option <bool>,
+  pub partition_native_capacity: Vec <PartitionValue>,
+  pub partition_used_native_capacity: Vec <PartitionValue>,
+  pub partition_remaining_native_capacity: Vec <PartitionValue>,
+}
+
+impl LogPageType for VolumeStatistics {
+  const PAGE_CODE: u8 = 0x17;
+
+  /// Every subpage shares the same parameter layout
+  fn matches(page: &LogPage) -> bool {
+    page.page_code == Self::PAGE_CODE && page.subpage_code <= 0x0F
+  }
+
+  fn from_page(page: &LogPage) -> Option <VolumeStatistics> {
+    Some(VolumeStatistics {
+      subpage_code: page.subpage_code,
+      page_valid: page.counter(0x0000).is_some_and(|t| t != 0),
+      thread_count: page.counter(0x0001),
+      total_data_sets_written: page.counter(0x0002),
+      total_write_retries: page.counter(0x0003),
+      total_unrecovered_write_errors: page.counter(0x0004),
+      total_data_sets_read: page.counter(0x0007),
+      total_read_retries: page.counter(0x0008),
+      total_unrecovered_read_errors: page.counter(0x0009),
+      last_mount_megabytes_written: page.counter(0x000E),
+      last_mount_megabytes_read: page.counter(0x000F),
+      lifetime_megabytes_written: page.counter(0x0010),
+      lifetime_megabytes_read: page.counter(0x0011),
+      total_native_capacity: page.counter(0x0016),
+      total_used_native_capacity: page.counter(0x0017),
+      volume_serial_number: page.ascii(0x0040),
+      volume_barcode: page.ascii(0x0042),
+      volume_manufacturer: page.ascii(0x0043),
+      write_protect: page.counter(0x0080).map(|t| t != 0),
+      worm: page.counter(0x0081).map(|t| t != 0),
+      partition_native_capacity: partition_records(page, 0x0202),
+      partition_used_native_capacity: partition_records(page, 0x0203),
+      partition_remaining_native_capacity: partition_records(page, 0x0204),
+    })
+  }
+}
+
+impl fmt::Display for VolumeStatistics {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Volume Statistics (subpage {:#04x}):", self.subpage_code)?;
+    writeln!(f, "  Page Valid: {}", self.page_valid)?;
+    write_counter(f, "Thread Count", self.thread_count)?;
+    write_counter(f, "Total Data Sets Written", self.total_data_sets_written)?;
+    write_counter(f, "Total Write Retries", self.total_write_retries)?;
+    write_counter(f, "Total Unrecovered Write Errors", self.total_unrecovered_write_errors)?;
+    write_counter(f, "Total Data Sets Read", self.total_data_sets_read)?;
+    write_counter(f, "Total Read Retries", self.total_read_retries)?;
+    write_counter(f, "Total Unrecovered Read Errors", self.total_unrecovered_read_errors)?;
+    write_counter(f, "Last Mount Megabytes Written", self.last_mount_megabytes_written)?;
+    write_counter(f, "Last Mount Megabytes Read", self.last_mount_megabytes_read)?;
+    write_counter(f, "Lifetime Megabytes Written", self.lifetime_megabytes_written)?;
+    write_counter(f, "Lifetime Megabytes Read", self.lifetime_megabytes_read)?;
+    write_counter(f, "Total Native Capacity (MB)", self.total_native_capacity)?;
+    write_counter(f, "Total Used Native Capacity (MB)", self.total_used_native_capacity)?;
+    if let Some(t) = &self.volume_serial_number {
+      writeln!(f, "  Volume Serial Number: {}", t)?;
+    }
+    if let Some(t) = &self.volume_barcode {
+      writeln!(f, "  Volume Barcode: {}", t)?;
+    }
+    if let Some(t) = &self.volume_manufacturer {
+      writeln!(f, "  Volume Manufacturer: {}", t)?;
+    }
+    for t in &self.partition_native_capacity {
+      writeln!(f, "  Partition {} Native Capacity (MB): {}", t.partition_number, t.value)?;
+    }
+    for t in &self.partition_used_native_capacity {
+      writeln!(f, "  Partition {} Used Native Capacity (MB): {}", t.partition_number, t.value)?;
+    }
+    for t in &self.partition_remaining_native_capacity {
+      writeln!(f, "  Partition {} Remaining Native Capacity (MB): {}",
+          t.partition_number, t.value)?;
+    }
+    Ok(())
+  }
+}
+
+/// SSC-4 Section 8.2.3 Data Compression log page. Each amount is split into
+/// a megabyte count and a signed byte residue.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataCompressionLog {
+  /// Ratio multiplied by 100
+  pub read_compression_ratio: Option <u64>,
+  /// Ratio multiplied by 100
+  pub write_compression_ratio: Option <u64>,
+  pub megabytes_transferred_to_application_client: Option <u64>,
+  pub bytes_transferred_to_application_client: Option <i64>,
+  pub megabytes_read_from_medium: Option <u64>,
+  pub bytes_read_from_medium: Option <i64>,
+  pub megabytes_transferred_from_application_client: Option <u64>,
+  pub bytes_transferred_from_application_client: Option <i64>,
+  pub megabytes_written_to_medium: Option <u64>,
+  pub bytes_written_to_medium: Option <i64>,
+}
+
+fn signed_counter(page: &LogPage, parameter_code: u16) -> Option <i64> {
+  let value = &page.parameter(parameter_code)?.value;
+  if value.is_empty() || value.len() > 8 {
+    return None;
+  }
+
+  let mut bytes = if value[0] & 0x80 == 0x80 { [0xFF; 8] } else { [0; 8] };
+  bytes[8 - value.len()..].copy_from_slice(value);
+  Some(i64::from_be_bytes(bytes))
+}
+
+fn total_bytes(megabytes: Option <u64>, bytes: Option <i64>) -> Option <i64> {
+  let megabytes = i64::try_from(megabytes?).ok()?;
+  megabytes.checked_mul(1_000_000)?.checked_add(bytes.unwrap_or(0))
+}
+
+impl DataCompressionLog {
+  pub fn total_bytes_written_to_medium(&self) -> Option <i64> {
+    total_bytes(self.megabytes_written_to_medium, self.bytes_written_to_medium)
+  }
+
+  pub fn total_bytes_transferred_from_application_client(&self) -> Option <i64> {
+    total_bytes(self.megabytes_transferred_from_application_client,
+        self.bytes_transferred_from_application_client)
+  }
+}
+
+impl LogPageType for DataCompressionLog {
+  const PAGE_CODE: u8 = 0x1B;
+
+  fn from_page(page: &LogPage) -> Option <DataCompressionLog> {
+    Some(DataCompressionLog {
+      read_compression_ratio: page.counter(0x0000),
+      write_compression_ratio: page.counter(0x0001),
+      megabytes_transferred_to_application_client: page.counter(0x0002),
+      bytes_transferred_to_application_client: signed_counter(page, 0x0003),
+      megabytes_read_from_medium: page.counter(0x0004),
+      bytes_read_from_medium: signed_counter(page, 0x0005),
+      megabytes_transferred_from_application_client: page.counter(0x0006),
+      bytes_transferred_from_application_client: signed_counter(page, 0x0007),
+      megabytes_written_to_medium: page.counter(0x0008),
+      bytes_written_to_medium: signed_counter(page, 0x0009),
+    })
+  }
+}
+
+impl fmt::Display for DataCompressionLog {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Data Compression:")?;
+    write_counter(f, "Read Compression Ratio (x100)", self.read_compression_ratio)?;
+    write_counter(f, "Write Compression Ratio (x100)", self.write_compression_ratio)?;
+    write_counter(f, "Megabytes Transferred to Application Client",
+        self.megabytes_transferred_to_application_client)?;
+    write_counter(f, "Megabytes Read from Medium", self.megabytes_read_from_medium)?;
+    write_counter(f, "Megabytes Transferred from Application Client",
+        self.megabytes_transferred_from_application_client)?;
+    write_counter(f, "Megabytes Written to Medium", self.megabytes_written_to_medium)
+  }
+}
+
+/// Tape Capacity log page. This page is vendor specific but implemented
+/// the same way by the common LTO drives. Capacities are in megabytes.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeCapacity {
+  pub main_partition_remaining_capacity: Option <u64>,
+  pub alternate_partition_remaining_capacity: Option <u64>,
+  pub main_partition_maximum_capacity: Option <u64>,
+  pub alternate_partition_maximum_capacity: Option <u64>,
+}
+
+impl TapeCapacity {
+  /// Remaining capacity of partition 0 (main) or 1 (alternate)
+  pub fn remaining(&self, partition: u8) -> Option <u64> {
+    match partition {
+      0 => { self.main_partition_remaining_capacity },
+      1 => { self.alternate_partition_remaining_capacity },
+      _ => { None },
+    }
+  }
+
+  /// Maximum capacity of partition 0 (main) or 1 (alternate)
+  pub fn maximum(&self, partition: u8) -> Option <u64> {
+    match partition {
+      0 => { self.main_partition_maximum_capacity },
+      1 => { self.alternate_partition_maximum_capacity },
+      _ => { None },
+    }
+  }
+}
+
+impl LogPageType for TapeCapacity {
+  const PAGE_CODE: u8 = 0x31;
+
+  fn from_page(page: &LogPage) -> Option <TapeCapacity> {
+    Some(TapeCapacity {
+      main_partition_remaining_capacity: page.counter(0x0001),
+      alternate_partition_remaining_capacity: page.counter(0x0002),
+      main_partition_maximum_capacity: page.counter(0x0003),
+      alternate_partition_maximum_capacity: page.counter(0x0004),
+    })
+  }
+}
+
+impl fmt::Display for TapeCapacity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Tape Capacity:")?;
+    write_counter(f, "Main Partition Remaining Capacity (MB)",
+        self.main_partition_remaining_capacity)?;
+    write_counter(f, "Alternate Partition Remaining Capacity (MB)",
+        self.alternate_partition_remaining_capacity)?;
+    write_counter(f, "Main Partition Maximum Capacity (MB)",
+        self.main_partition_maximum_capacity)?;
+    write_counter(f, "Alternate Partition Maximum Capacity (MB)",
+        self.alternate_partition_maximum_capacity)
+  }
+}
+
+#[test]
+fn tape_log_pages_test() {
+  use crate::commands::log::TypedLogPage;
+
+  let buf = [
+    0x31, 0x00, 0x00, 0x18,
+    0x00, 0x01, 0x00, 0x04, 0x00, 0x5B, 0x8D, 0x80,
+    0x00, 0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x25,
+    0x00, 0x03, 0x00, 0x04, 0x00, 0x5B, 0x8D, 0x80,
+  ];
+  let capacity = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::TapeCapacity(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(capacity.remaining(0), Some(6_000_000));
+  assert_eq!(capacity.remaining(1), Some(37));
+  assert_eq!(capacity.maximum(1), None);
+
+  let buf = [
+    0x57, 0x01, 0x00, 0x25,
+    0x00, 0x00, 0x00, 0x01, 0x01,
+    0x00, 0x40, 0x01, 0x08, b'A', b'B', b'C', b'1', b'2', b'3', b' ', b' ',
+    // Two partition records with 4 byte values
+    0x02, 0x02, 0x03, 0x10,
+    0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+    0x07, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x25,
+  ];
+  let volume = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::VolumeStatistics(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert!(volume.page_valid);
+  assert_eq!(volume.subpage_code, 0x01);
+  assert_eq!(volume.volume_serial_number, Some("ABC123".to_string()));
+  assert_eq!(volume.partition_native_capacity, vec![
+    PartitionValue { partition_number: 0, value: 0x1000 },
+    PartitionValue { partition_number: 1, value: 0x25 },
+  ]);
+}
+
+#[test]
+fn self_test_results_test() {
+  use crate::commands::log::TypedLogPage;
+
+  let buf = [
+    0x10, 0x00, 0x00, 0x3C,
+    0x00, 0x02, 0x03, 0x10,
+    0xC7, 0x03, 0x01, 0x2C, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x04, 0x44, 0x00, 0x00,
+    0x00, 0x01, 0x03, 0x10,
+    0x4F, 0x00, 0x01, 0x2D, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // Unused
+    0x00, 0x03, 0x03, 0x10,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+  let self_test = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::SelfTestResults(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(self_test.results.len(), 2);
+  let recent = self_test.most_recent().unwrap();
+  assert_eq!(recent.self_test_code, 2);
+  assert!(recent.in_progress());
+  assert_eq!(recent.accumulated_power_on_hours, 301);
+  let previous = &self_test.results[1];
+  assert_eq!(previous.self_test_code, 6);
+  assert_eq!(previous.self_test_results, SelfTestResult::FAILED_SEGMENT);
+  assert_eq!(previous.self_test_segment_number, 3);
+  assert_eq!(previous.sense_key, 0x4);
+  assert_eq!(previous.additional_sense_code, 0x44);
+}
+
+#[test]
+fn tape_usage_log_pages_test() {
+  use crate::commands::log::TypedLogPage;
+
+  let buf = [
+    0x0C, 0x00, 0x00, 0x16,
+    0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x10, 0x00,
+    0x00, 0x04, 0x00, 0x04, 0x00, 0x5B, 0x8D, 0x80,
+    0x01, 0x00, 0x00, 0x02, 0x00, 0x01,
+  ];
+  let sequential = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::SequentialAccessDevice(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(sequential.data_bytes_received, Some(0x1000));
+  assert_eq!(sequential.data_bytes_written, None);
+  assert_eq!(sequential.native_capacity_bop_to_eod, Some(6_000_000));
+  assert!(sequential.cleaning_required);
+
+  let buf = [
+    0x14, 0x00, 0x00, 0x18,
+    0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x0C,
+    0x00, 0x02, 0x00, 0x04, 0x00, 0x00, 0x01, 0xF4,
+    0x00, 0x0F, 0x00, 0x04, 0x00, 0x00, 0x00, 0x03,
+  ];
+  let statistics = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::DeviceStatistics(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(statistics.lifetime_volume_loads, Some(12));
+  assert_eq!(statistics.lifetime_power_on_hours, Some(500));
+  assert_eq!(statistics.lifetime_cleaning_operations, None);
+  assert_eq!(statistics.hard_read_errors, Some(3));
+
+  let buf = [
+    0x1B, 0x00, 0x00, 0x1F,
+    0x00, 0x06, 0x00, 0x04, 0x00, 0x00, 0x00, 0x0A,
+    // -1000 bytes
+    0x00, 0x07, 0x00, 0x02, 0xFC, 0x18,
+    0x00, 0x08, 0x00, 0x08, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0x00, 0x09, 0x00, 0x01, 0x05,
+  ];
+  let compression = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::DataCompressionLog(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(compression.bytes_transferred_from_application_client, Some(-1000));
+  assert_eq!(compression.bytes_written_to_medium, Some(5));
+  assert_eq!(compression.total_bytes_transferred_from_application_client(), Some(9_999_000));
+  //Too large for an i64
+  assert_eq!(compression.total_bytes_written_to_medium(), None);
+  assert_eq!(total_bytes(Some(9_223_372_036_854), Some(775_807)), Some(i64::MAX));
+  assert_eq!(total_bytes(Some(9_223_372_036_854), Some(775_808)), None);
+  assert_eq!(total_bytes(Some(9_223_372_036_855), None), None);
+  assert_eq!(total_bytes(None, Some(5)), None);
+
+  let buf = [
+    0x57, 0x01, 0x00, 0x0C,
+    0x02, 0x04, 0x03, 0x08,
+    0x07, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x30,
+  ];
+  let volume = match LogPage::from_bytes(&buf).unwrap().decode() {
+    TypedLogPage::VolumeStatistics(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(volume.partition_remaining_native_capacity, vec![
+    PartitionValue { partition_number: 1, value: 0x30 },
+  ]);
+}
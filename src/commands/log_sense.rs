@@ -0,0 +1,83 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::LogPage;
+
+/// SPC-3 Section 6.6
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogSense {
+  pub ppc: bool,
+  pub sp: bool,
+  pub pc: u8,
+  pub page_code: u8,
+  pub subpage_code: u8,
+  pub parameter_pointer: u16,
+  pub allocation_length: u16,
+}
+
+impl LogSense {
+  pub const THRESHOLD: u8 = 0x0;
+  pub const CUMULATIVE: u8 = 0x1;
+  pub const DEFAULT_THRESHOLD: u8 = 0x2;
+  pub const DEFAULT_CUMULATIVE: u8 = 0x3;
+  pub const SUPPORTED_PAGES: u8 = 0x00;
+  const OP_CODE: u8 = 0x4D;
+
+  pub fn new() -> LogSense {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <LogSense> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(LogSense {
+      ppc: buf[1] & 0x02 == 0x02,
+      sp: buf[1] & 0x01 == 0x01,
+      pc: buf[2] >> 6,
+      page_code: buf[2] & 0x3F,
+      subpage_code: buf[3],
+      parameter_pointer: u16::from_be_bytes([buf[5], buf[6]]),
+      allocation_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <LogPage> {
+    LogPage::from_bytes(buf)
+  }
+}
+
+impl crate::Output for LogSense {
+}
+
+impl crate::Command for LogSense {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    if self.pc > Self::DEFAULT_CUMULATIVE {
+      return Err("Page control must be in the range 0..4");
+    }
+
+    if self.page_code > 0x3F {
+      return Err("Page code must be in the range 0..64");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.ppc { 0x02 } else { 0x00 };
+    data[1] |= if self.sp { 0x01 } else { 0x00 };
+    data[2] = (self.pc << 6) | self.page_code;
+    data[3] = self.subpage_code;
+    data[5..7].copy_from_slice(&self.parameter_pointer.to_be_bytes());
+    data[7..9].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
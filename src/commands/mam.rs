@@ -0,0 +1,372 @@
+use crate::commands::ReadAttribute;
+use crate::commands::WriteAttribute;
+use crate::device::{Device, DeviceStatus, check_good};
+use std::convert::TryInto;
+use std::fmt;
+
+// SPC-4 Section 7.3.2 attribute identifiers that are commonly used. See
+// MAM_ATTRIBUTES for the full catalogue.
+pub const REMAINING_CAPACITY_IN_PARTITION: u16 = 0x0000;
+pub const MAXIMUM_CAPACITY_IN_PARTITION: u16 = 0x0001;
+pub const TAPEALERT_FLAGS: u16 = 0x0002;
+pub const LOAD_COUNT: u16 = 0x0003;
+pub const MAM_SPACE_REMAINING: u16 = 0x0004;
+pub const VOLUME_IDENTIFIER: u16 = 0x0008;
+pub const TOTAL_MBYTES_WRITTEN_IN_MEDIUM_LIFE: u16 = 0x0220;
+pub const TOTAL_MBYTES_READ_IN_MEDIUM_LIFE: u16 = 0x0221;
+pub const TOTAL_MBYTES_WRITTEN_IN_CURRENT_LOAD: u16 = 0x0222;
+pub const TOTAL_MBYTES_READ_IN_CURRENT_LOAD: u16 = 0x0223;
+pub const MEDIUM_MANUFACTURER: u16 = 0x0400;
+pub const MEDIUM_SERIAL_NUMBER: u16 = 0x0401;
+pub const MEDIUM_MANUFACTURE_DATE: u16 = 0x0406;
+pub const MEDIUM_TYPE: u16 = 0x0408;
+pub const APPLICATION_VENDOR: u16 = 0x0800;
+pub const APPLICATION_NAME: u16 = 0x0801;
+pub const APPLICATION_VERSION: u16 = 0x0802;
+pub const USER_MEDIUM_TEXT_LABEL: u16 = 0x0803;
+pub const DATE_AND_TIME_LAST_WRITTEN: u16 = 0x0804;
+pub const BARCODE: u16 = 0x0806;
+pub const OWNING_HOST_TEXTUAL_NAME: u16 = 0x0807;
+pub const MEDIA_POOL: u16 = 0x0808;
+
+/// SPC-4 Section 7.3.1 attribute format
+pub const FORMAT_BINARY: u8 = 0x0;
+pub const FORMAT_ASCII: u8 = 0x1;
+pub const FORMAT_TEXT: u8 = 0x2;
+
+/// Which part of the device owns an attribute, by identifier range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AttributeType {
+  /// Maintained by the device server, read only
+  Device,
+  /// Set when the medium is manufactured, read only
+  Medium,
+  /// Written by the application client
+  Host,
+  VendorSpecific,
+  Reserved,
+}
+
+impl AttributeType {
+  pub fn from_id(id: u16) -> AttributeType {
+    match id {
+      0x0000..=0x03FF => { AttributeType::Device },
+      0x0400..=0x07FF => { AttributeType::Medium },
+      0x0800..=0x0BFF => { AttributeType::Host },
+      0x0C00..=0x0FFF | 0x1400..=0x17FF => { AttributeType::VendorSpecific },
+      _ => { AttributeType::Reserved },
+    }
+  }
+}
+
+/// Catalogue entry for a standard attribute
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AttributeInfo {
+  pub id: u16,
+  pub name: &'static str,
+  pub format: u8,
+  /// Length in bytes, zero if the length is not fixed
+  pub length: u16,
+}
+
+macro_rules! attribute {
+  ( $id:expr, $name:expr, $format:expr, $length:expr ) => {
+    AttributeInfo { id: $id, name: $name, format: $format, length: $length }
+  };
+}
+
+/// SPC-4 Tables 334, 336 and 338 standard device, medium and host
+/// attributes
+pub static MAM_ATTRIBUTES: [AttributeInfo; 47] = [
+  attribute!(0x0000, "Remaining Capacity In Partition", FORMAT_BINARY, 8),
+  attribute!(0x0001, "Maximum Capacity In Partition", FORMAT_BINARY, 8),
+  attribute!(0x0002, "TapeAlert Flags", FORMAT_BINARY, 8),
+  attribute!(0x0003, "Load Count", FORMAT_BINARY, 8),
+  attribute!(0x0004, "MAM Space Remaining", FORMAT_BINARY, 8),
+  attribute!(0x0005, "Assigning Organization", FORMAT_ASCII, 8),
+  attribute!(0x0006, "Formatted Density Code", FORMAT_BINARY, 1),
+  attribute!(0x0007, "Initialization Count", FORMAT_BINARY, 2),
+  attribute!(0x0008, "Volume Identifier", FORMAT_ASCII, 32),
+  attribute!(0x0009, "Volume Change Reference", FORMAT_BINARY, 4),
+  attribute!(0x020A, "Device Vendor/Serial Number at Last Load", FORMAT_ASCII, 40),
+  attribute!(0x020B, "Device Vendor/Serial Number at Load-1", FORMAT_ASCII, 40),
+  attribute!(0x020C, "Device Vendor/Serial Number at Load-2", FORMAT_ASCII, 40),
+  attribute!(0x020D, "Device Vendor/Serial Number at Load-3", FORMAT_ASCII, 40),
+  attribute!(0x0220, "Total MBytes Written in Medium Life", FORMAT_BINARY, 8),
+  attribute!(0x0221, "Total MBytes Read in Medium Life", FORMAT_BINARY, 8),
+  attribute!(0x0222, "Total MBytes Written in Current/Last Load", FORMAT_BINARY, 8),
+  attribute!(0x0223, "Total MBytes Read in Current/Last Load", FORMAT_BINARY, 8),
+  attribute!(0x0224, "Logical Position of First Encrypted Block", FORMAT_BINARY, 8),
+  attribute!(0x0225, "Logical Position of First Unencrypted Block after the First Encrypted Block",
+      FORMAT_BINARY, 8),
+  attribute!(0x0340, "Medium Usage History", FORMAT_BINARY, 90),
+  attribute!(0x0341, "Partition Usage History", FORMAT_BINARY, 60),
+  attribute!(0x0400, "Medium Manufacturer", FORMAT_ASCII, 8),
+  attribute!(0x0401, "Medium Serial Number", FORMAT_ASCII, 32),
+  attribute!(0x0402, "Medium Length", FORMAT_BINARY, 4),
+  attribute!(0x0403, "Medium Width", FORMAT_BINARY, 4),
+  attribute!(0x0404, "Assigning Organization", FORMAT_ASCII, 8),
+  attribute!(0x0405, "Medium Density Code", FORMAT_BINARY, 1),
+  attribute!(0x0406, "Medium Manufacture Date", FORMAT_ASCII, 8),
+  attribute!(0x0407, "MAM Capacity", FORMAT_BINARY, 8),
+  attribute!(0x0408, "Medium Type", FORMAT_BINARY, 1),
+  attribute!(0x0409, "Medium Type Information", FORMAT_BINARY, 2),
+  attribute!(0x040A, "Numeric Medium Serial Number", FORMAT_BINARY, 0),
+  attribute!(0x0800, "Application Vendor", FORMAT_ASCII, 8),
+  attribute!(0x0801, "Application Name", FORMAT_ASCII, 32),
+  attribute!(0x0802, "Application Version", FORMAT_ASCII, 8),
+  attribute!(0x0803, "User Medium Text Label", FORMAT_TEXT, 160),
+  attribute!(0x0804, "Date and Time Last Written", FORMAT_ASCII, 12),
+  attribute!(0x0805, "Text Localization Identifier", FORMAT_BINARY, 1),
+  attribute!(0x0806, "Barcode", FORMAT_ASCII, 32),
+  attribute!(0x0807, "Owning Host Textual Name", FORMAT_TEXT, 80),
+  attribute!(0x0808, "Media Pool", FORMAT_TEXT, 160),
+  attribute!(0x0809, "Partition User Text Label", FORMAT_ASCII, 16),
+  attribute!(0x080A, "Load/Unload at Partition", FORMAT_BINARY, 1),
+  attribute!(0x080B, "Application Format Version", FORMAT_ASCII, 16),
+  attribute!(0x0820, "Medium Globally Unique Identifier", FORMAT_BINARY, 36),
+  attribute!(0x0821, "Media Pool Globally Unique Identifier", FORMAT_BINARY, 36),
+];
+
+pub fn attribute_info(id: u16) -> Option <&'static AttributeInfo> {
+  MAM_ATTRIBUTES.iter().find(|t| t.id == id)
+}
+
+/// Decoded attribute value
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttributeValue {
+  /// Binary attributes of up to 8 bytes
+  Number(u64),
+  Binary(Vec <u8>),
+  Ascii(String),
+  Text(String),
+}
+
+impl fmt::Display for AttributeValue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      AttributeValue::Number(t) => { write!(f, "{}", t) },
+      AttributeValue::Binary(t) => { write!(f, "{:02X?}", t) },
+      AttributeValue::Ascii(t) => { write!(f, "{}", t) },
+      AttributeValue::Text(t) => { write!(f, "{}", t) },
+    }
+  }
+}
+
+/// SPC-4 Section 7.3.1 MAM attribute
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+  pub id: u16,
+  pub read_only: bool,
+  pub format: u8,
+  pub value: Vec <u8>,
+}
+
+impl Attribute {
+  /// Decode one attribute, returning it and its length
+  pub fn from_bytes(buf: &[u8]) -> Option <(Attribute, usize)> {
+    if buf.len() < 5 {
+      return None;
+    }
+
+    let length = u16::from_be_bytes([buf[3], buf[4]]) as usize;
+    let attribute = Attribute {
+      id: u16::from_be_bytes([buf[0], buf[1]]),
+      read_only: buf[2] & 0x80 == 0x80,
+      format: buf[2] & 0x03,
+      value: buf.get(5..(5 + length))?.to_vec(),
+    };
+
+    Some((attribute, 5 + length))
+  }
+
+  /// Decode a list of attributes, stopping at one that is truncated
+  pub fn list_from_bytes(buf: &[u8]) -> Vec <Attribute> {
+    let mut rv = Vec::new();
+    let mut offset = 0;
+    while let Some((attribute, length)) = Attribute::from_bytes(&buf[offset..]) {
+      rv.push(attribute);
+      offset += length;
+    }
+    rv
+  }
+
+  /// READ ONLY is ignored by WRITE ATTRIBUTE
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let mut rv = self.id.to_be_bytes().to_vec();
+    rv.push(self.format & 0x03);
+    rv.extend_from_slice(&(self.value.len() as u16).to_be_bytes());
+    rv.extend_from_slice(&self.value);
+    rv
+  }
+
+  /// Host attribute with ASCII or text format. The value is padded with
+  /// spaces or truncated to the catalogue length.
+  pub fn from_str(id: u16, value: &str) -> Attribute {
+    let info = attribute_info(id);
+    let format = info.map(|t| t.format).unwrap_or(FORMAT_ASCII);
+    let mut value = value.as_bytes().to_vec();
+    if let Some(length) = info.map(|t| t.length as usize).filter(|t| *t > 0) {
+      // Text attributes are null padded, ASCII attributes space padded
+      let pad = if format == FORMAT_TEXT { 0x00 } else { b' ' };
+      value.resize(length, pad);
+    }
+
+    Attribute {
+      id,
+      read_only: false,
+      format,
+      value,
+    }
+  }
+
+  /// Empty value, which deletes a host attribute when written
+  pub fn delete(id: u16) -> Attribute {
+    Attribute {
+      id,
+      format: attribute_info(id).map(|t| t.format).unwrap_or(FORMAT_BINARY),
+      ..Default::default()
+    }
+  }
+
+  pub fn info(&self) -> Option <&'static AttributeInfo> {
+    attribute_info(self.id)
+  }
+
+  pub fn attribute_type(&self) -> AttributeType {
+    AttributeType::from_id(self.id)
+  }
+
+  pub fn decode(&self) -> AttributeValue {
+    match self.format {
+      FORMAT_BINARY if self.value.len() <= 8 => {
+        AttributeValue::Number(self.value.iter().fold(0, |acc, b| (acc << 8) | u64::from(*b)))
+      },
+      FORMAT_ASCII => {
+        AttributeValue::Ascii(String::from_utf8_lossy(&self.value).trim_end().to_string())
+      },
+      FORMAT_TEXT => {
+        AttributeValue::Text(String::from_utf8_lossy(&self.value).trim_end_matches('\0').to_string())
+      },
+      _ => { AttributeValue::Binary(self.value.clone()) },
+    }
+  }
+
+  /// Numeric value of a binary attribute
+  pub fn as_u64(&self) -> Option <u64> {
+    match self.decode() {
+      AttributeValue::Number(t) => { Some(t) },
+      _ => { None },
+    }
+  }
+
+  /// Value of an ASCII or text attribute
+  pub fn as_string(&self) -> Option <String> {
+    match self.decode() {
+      AttributeValue::Ascii(t) | AttributeValue::Text(t) => { Some(t) },
+      _ => { None },
+    }
+  }
+}
+
+impl fmt::Display for Attribute {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.info() {
+      Some(info) => { write!(f, "{:04X}h {}: {}", self.id, info.name, self.decode()) },
+      None => { write!(f, "{:04X}h: {}", self.id, self.decode()) },
+    }
+  }
+}
+
+/// READ ATTRIBUTE parameter data, by service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadAttributeOutput {
+  Values(Vec <Attribute>),
+  AttributeList(Vec <u16>),
+  /// First volume or partition number and the number available
+  VolumeList { first: u8, count: u8 },
+  PartitionList { first: u8, count: u8 },
+}
+
+pub(crate) fn parse_values(buf: &[u8]) -> Option <Vec <Attribute>> {
+  let length = u32::from_be_bytes(buf.get(0..4)?.try_into().unwrap()) as usize;
+  Some(Attribute::list_from_bytes(&buf[4..buf.len().min(4 + length)]))
+}
+
+pub(crate) fn parse_attribute_list(buf: &[u8]) -> Option <Vec <u16>> {
+  let length = u32::from_be_bytes(buf.get(0..4)?.try_into().unwrap()) as usize;
+  Some(buf[4..buf.len().min(4 + length)].chunks_exact(2)
+      .map(|t| u16::from_be_bytes([t[0], t[1]]))
+      .collect())
+}
+
+/// Read every attribute from `first_id` onwards for a partition
+pub fn read_attributes(device: &Device, partition_number: u8, first_id: u16)
+    -> Result <Vec <Attribute>, String> {
+  let cmd = ReadAttribute {
+    service_action: ReadAttribute::ATTRIBUTE_VALUES,
+    partition_number,
+    first_attribute_identifier: first_id,
+    allocation_length: 0x10000,
+    ..Default::default()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("READ ATTRIBUTE", status)?;
+
+  parse_values(&buf).ok_or_else(|| "Could not parse READ ATTRIBUTE data".to_string())
+}
+
+/// Read one attribute, None if the medium does not hold it
+pub fn read_attribute(device: &Device, partition_number: u8, id: u16)
+    -> Result <Option <Attribute>, String> {
+  Ok(read_attributes(device, partition_number, id)?.into_iter().find(|t| t.id == id))
+}
+
+/// Write host attributes for a partition
+pub fn write_attributes(device: &Device, partition_number: u8, attributes: &[Attribute])
+    -> Result <DeviceStatus, String> {
+  let mut buf = vec![0; 4];
+  for t in attributes {
+    buf.extend_from_slice(&t.to_bytes());
+  }
+  let length = (buf.len() - 4) as u32;
+  buf[0..4].copy_from_slice(&length.to_be_bytes());
+
+  let cmd = WriteAttribute {
+    partition_number,
+    parameter_list_length: buf.len() as u32,
+    ..Default::default()
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, &buf)?;
+  check_good("WRITE ATTRIBUTE", status)
+}
+
+#[test]
+fn mam_test() {
+  let buf = [
+    0x00, 0x00, 0x00, 0x27,
+    0x00, 0x03, 0x80, 0x00, 0x08, 0, 0, 0, 0, 0, 0, 0x01, 0x2C,
+    0x04, 0x06, 0x81, 0x00, 0x08, b'2', b'0', b'2', b'4', b'0', b'1', b'3', b'1',
+    0x08, 0x06, 0x01, 0x00, 0x08, b'A', b'B', b'C', b'1', b'2', b'3', b'L', b'9',
+  ];
+  let attributes = parse_values(&buf).unwrap();
+  assert_eq!(attributes.len(), 3);
+  assert!(attributes[0].read_only);
+  assert_eq!(attributes[0].as_u64(), Some(300));
+  assert_eq!(attributes[1].to_string(), "0406h Medium Manufacture Date: 20240131");
+  assert_eq!(attributes[2].attribute_type(), AttributeType::Host);
+  assert_eq!(attributes[2].as_string(), Some("ABC123L9".to_string()));
+
+  let barcode = Attribute::from_str(BARCODE, "ABC123L9");
+  assert_eq!(barcode.value.len(), 32);
+  assert_eq!(&barcode.to_bytes()[0..5], &[0x08, 0x06, 0x01, 0x00, 0x20]);
+  assert_eq!(barcode.as_string(), Some("ABC123L9".to_string()));
+}
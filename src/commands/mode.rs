@@ -0,0 +1,596 @@
+use crate::commands::ModeSense10;
+use crate::commands::ModeSelect10;
+use crate::commands::mode_pages::{*};
+use crate::device::{Device, DeviceStatus, check_good};
+use std::fmt;
+
+/// SPC-3 Section 7.4.3 mode parameter header, with the SSC-4 Section 8.3.3
+/// device-specific parameter split into its fields
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeParameterHeader {
+  pub mode_data_length: u16,
+  pub medium_type: u8,
+  pub wp: bool,
+  pub buffered_mode: u8,
+  pub speed: u8,
+  pub longlba: bool,
+  pub block_descriptor_length: u16,
+}
+
+impl ModeParameterHeader {
+  pub const LEN_6: usize = 4;
+  pub const LEN_10: usize = 8;
+
+  /// Decode only the MODE SENSE(10) header, ignoring whatever follows
+  pub fn from_bytes_10(buf: &[u8]) -> Option <ModeParameterHeader> {
+    if buf.len() < Self::LEN_10 {
+      return None;
+    }
+
+    let mut header = ModeParameterHeader {
+      mode_data_length: u16::from_be_bytes([buf[0], buf[1]]),
+      medium_type: buf[2],
+      longlba: buf[4] & 0x01 == 0x01,
+      block_descriptor_length: u16::from_be_bytes([buf[6], buf[7]]),
+      ..Default::default()
+    };
+    header.set_device_specific_parameter(buf[3]);
+    Some(header)
+  }
+
+  fn device_specific_parameter(&self) -> u8 {
+    // WP is reserved for MODE SELECT
+    ((self.buffered_mode & 0x7) << 4) | (self.speed & 0x0F)
+  }
+
+  fn set_device_specific_parameter(&mut self, val: u8) {
+    self.wp = val & 0x80 == 0x80;
+    self.buffered_mode = (val >> 4) & 0x7;
+    self.speed = val & 0x0F;
+  }
+}
+
+/// SPC-3 Section 7.4.4 block descriptor. The short form carries a density
+/// code, the long LBA form does not.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockDescriptor {
+  pub density_code: u8,
+  pub number_of_blocks: u64,
+  pub block_length: u32,
+}
+
+impl BlockDescriptor {
+  pub const SHORT_LEN: usize = 8;
+  pub const LONG_LEN: usize = 16;
+
+  fn from_bytes(buf: &[u8], longlba: bool) -> BlockDescriptor {
+    if longlba {
+      let mut blocks = [0; 8];
+      blocks.copy_from_slice(&buf[0..8]);
+      BlockDescriptor {
+        density_code: 0,
+        number_of_blocks: u64::from_be_bytes(blocks),
+        block_length: u32::from_be_bytes([buf[12], buf[13], buf[14], buf[15]]),
+      }
+    } else {
+      BlockDescriptor {
+        density_code: buf[0],
+        number_of_blocks: u32::from_be_bytes([0, buf[1], buf[2], buf[3]]).into(),
+        block_length: u32::from_be_bytes([0, buf[5], buf[6], buf[7]]),
+      }
+    }
+  }
+
+  fn to_bytes(&self, longlba: bool) -> Result <Vec <u8>, &'static str> {
+    if longlba {
+      let mut data = vec![0; Self::LONG_LEN];
+      data[0..8].copy_from_slice(&self.number_of_blocks.to_be_bytes());
+      data[12..16].copy_from_slice(&self.block_length.to_be_bytes());
+      Ok(data)
+    } else {
+      if self.number_of_blocks >= 2_u64.pow(24) {
+        return Err("Number of blocks must be in the range 0..2^24");
+      }
+      if self.block_length >= 2_u32.pow(24) {
+        return Err("Block length must be in the range 0..2^24");
+      }
+
+      let mut data = vec![0; Self::SHORT_LEN];
+      data[0] = self.density_code;
+      data[1..4].copy_from_slice(&self.number_of_blocks.to_be_bytes()[5..]);
+      data[5..8].copy_from_slice(&self.block_length.to_be_bytes()[1..]);
+      Ok(data)
+    }
+  }
+}
+
+/// SPC-3 Section 7.4.5 mode page in either the page_0 or sub_page format.
+/// `data` holds the mode parameters that follow the page header.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModePage {
+  pub ps: bool,
+  pub spf: bool,
+  pub page_code: u8,
+  pub subpage_code: u8,
+  pub data: Vec <u8>,
+}
+
+impl ModePage {
+  /// Parse one page from the front of `buf`, returning it and its length
+  pub fn from_bytes(buf: &[u8]) -> Option <(ModePage, usize)> {
+    if buf.len() < 2 {
+      return None;
+    }
+
+    let spf = buf[0] & 0x40 == 0x40;
+    let (subpage_code, page_length, header_len) = if spf {
+      if buf.len() < 4 {
+        return None;
+      }
+      (buf[1], u16::from_be_bytes([buf[2], buf[3]]) as usize, 4)
+    } else {
+      (0, buf[1] as usize, 2)
+    };
+
+    let end = header_len + page_length;
+    if end > buf.len() {
+      return None;
+    }
+
+    Some((ModePage {
+      ps: buf[0] & 0x80 == 0x80,
+      spf,
+      page_code: buf[0] & 0x3F,
+      subpage_code,
+      data: buf[header_len..end].to_vec(),
+    }, end))
+  }
+
+  /// Encode the page for MODE SELECT; the PS bit is always cleared
+  pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = Vec::with_capacity(self.data.len() + 4);
+    let byte0 = (self.page_code & 0x3F) | if self.spf { 0x40 } else { 0x00 };
+
+    if self.spf {
+      if self.data.len() > u16::MAX.into() {
+        return Err("Subpage length must be in the range 0..2^16");
+      }
+      data.push(byte0);
+      data.push(self.subpage_code);
+      data.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
+    } else {
+      if self.data.len() > u8::MAX.into() {
+        return Err("Page length must be in the range 0..2^8");
+      }
+      data.push(byte0);
+      data.push(self.data.len() as u8);
+    }
+    data.extend_from_slice(&self.data);
+
+    Ok(data)
+  }
+
+  /// Take the bits of `changed` that are set in the `changeable` mask and
+  /// keep the rest from this (current) page. Bytes of `changed` past the end
+  /// of the current page are taken as they are, since pages such as Medium
+  /// Partition grow when more parameters are defined.
+  pub fn apply_change(&self, changed: &ModePage, changeable: &ModePage) -> ModePage {
+    let len = self.data.len().max(changed.data.len());
+    let data = (0..len).map(|i| {
+      match (self.data.get(i), changed.data.get(i)) {
+        (Some(current), Some(new)) => {
+          let mask = changeable.data.get(i).copied().unwrap_or(0);
+          (new & mask) | (current & !mask)
+        },
+        (Some(current), None) => { *current },
+        (None, Some(new)) => { *new },
+        (None, None) => { unreachable!() },
+      }
+    }).collect();
+
+    ModePage {
+      ps: false,
+      data,
+      ..self.clone()
+    }
+  }
+
+  /// Decode with the typed parser registered for this page, if any
+  pub fn decode(&self) -> TypedModePage {
+    TypedModePage::from_page(self)
+  }
+}
+
+/// Typed view of a mode page. Implementors are listed in the `mode_pages!`
+/// registry so [`ModePage::decode`] can find them.
+pub trait ModePageType: Sized + fmt::Debug + fmt::Display {
+  const PAGE_CODE: u8;
+  const SUBPAGE_CODE: u8 = 0;
+
+  /// Parse the mode parameters of `page`. Returns None if they are too short.
+  fn from_page(page: &ModePage) -> Option <Self>;
+
+  /// Encode the mode parameters, without the page header
+  fn to_data(&self) -> Vec <u8>;
+
+  fn to_page(&self) -> ModePage {
+    ModePage {
+      ps: false,
+      spf: Self::SUBPAGE_CODE != 0,
+      page_code: Self::PAGE_CODE,
+      subpage_code: Self::SUBPAGE_CODE,
+      data: self.to_data(),
+    }
+  }
+}
+
+macro_rules! mode_pages {
+  ( $( $name:ident ),* $(,)? ) => {
+    /// A mode page decoded by its registered typed parser
+    #[derive(Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum TypedModePage {
+      $( $name($name), )*
+      Unknown(ModePage),
+    }
+
+    impl TypedModePage {
+      pub fn from_page(page: &ModePage) -> TypedModePage {
+        $(
+          if page.page_code == $name::PAGE_CODE && page.subpage_code == $name::SUBPAGE_CODE {
+            if let Some(t) = $name::from_page(page) {
+              return TypedModePage::$name(t);
+            }
+          }
+        )*
+        TypedModePage::Unknown(page.clone())
+      }
+
+      pub fn to_page(&self) -> ModePage {
+        match self {
+          $( TypedModePage::$name(t) => { t.to_page() }, )*
+          TypedModePage::Unknown(t) => { t.clone() },
+        }
+      }
+    }
+
+    impl fmt::Display for TypedModePage {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+          $( TypedModePage::$name(t) => { write!(f, "{}", t) }, )*
+          TypedModePage::Unknown(t) => {
+            writeln!(f, "Mode Page {:#04x}/{:#04x}: {:02x?}", t.page_code, t.subpage_code, t.data)
+          },
+        }
+      }
+    }
+  };
+}
+
+mode_pages! {
+  DataCompression,
+  DeviceConfiguration,
+  DeviceConfigurationExtension,
+  MediumPartition,
+  Control,
+  ReadWriteErrorRecovery,
+  InformationalExceptionsControl,
+}
+
+/// Mode parameter list returned by MODE SENSE or sent with MODE SELECT
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeParameters {
+  pub header: ModeParameterHeader,
+  pub block_descriptors: Vec <BlockDescriptor>,
+  pub pages: Vec <ModePage>,
+}
+
+impl ModeParameters {
+  /// Parse the parameter list of MODE SENSE(6)
+  pub fn from_bytes_6(buf: &[u8]) -> Option <ModeParameters> {
+    if buf.len() < ModeParameterHeader::LEN_6 {
+      return None;
+    }
+
+    let mut header = ModeParameterHeader {
+      mode_data_length: buf[0].into(),
+      medium_type: buf[1],
+      block_descriptor_length: buf[3].into(),
+      ..Default::default()
+    };
+    header.set_device_specific_parameter(buf[2]);
+
+    let end = std::cmp::min(buf.len(), header.mode_data_length as usize + 1);
+    Self::from_parts(header, buf, ModeParameterHeader::LEN_6, end)
+  }
+
+  /// Parse the parameter list of MODE SENSE(10)
+  pub fn from_bytes_10(buf: &[u8]) -> Option <ModeParameters> {
+    let header = ModeParameterHeader::from_bytes_10(buf)?;
+    let end = std::cmp::min(buf.len(), header.mode_data_length as usize + 2);
+    Self::from_parts(header, buf, ModeParameterHeader::LEN_10, end)
+  }
+
+  fn from_parts(header: ModeParameterHeader, buf: &[u8], header_len: usize, end: usize)
+      -> Option <ModeParameters> {
+    let descriptors_end = header_len + header.block_descriptor_length as usize;
+    if descriptors_end > end {
+      return None;
+    }
+
+    let descriptor_len = if header.longlba {
+      BlockDescriptor::LONG_LEN
+    } else {
+      BlockDescriptor::SHORT_LEN
+    };
+    let block_descriptors = buf[header_len..descriptors_end].chunks_exact(descriptor_len)
+        .map(|d| BlockDescriptor::from_bytes(d, header.longlba))
+        .collect();
+
+    let mut pages = Vec::new();
+    let mut offset = descriptors_end;
+    while offset < end {
+      match ModePage::from_bytes(&buf[offset..end]) {
+        Some((page, len)) => { pages.push(page); offset += len; },
+        None => { break; },
+      }
+    }
+
+    Some(ModeParameters { header, block_descriptors, pages })
+  }
+
+  fn descriptor_and_page_bytes(&self) -> Result <(Vec <u8>, Vec <u8>), &'static str> {
+    let mut descriptors = Vec::new();
+    for descriptor in &self.block_descriptors {
+      descriptors.extend(descriptor.to_bytes(self.header.longlba)?);
+    }
+
+    let mut pages = Vec::new();
+    for page in &self.pages {
+      pages.extend(page.to_bytes()?);
+    }
+
+    Ok((descriptors, pages))
+  }
+
+  /// Encode as the parameter list of MODE SELECT(6)
+  pub fn to_bytes_6(&self) -> Result <Vec <u8>, &'static str> {
+    if self.header.longlba {
+      return Err("Long LBA block descriptors need MODE SELECT(10)");
+    }
+
+    let (descriptors, pages) = self.descriptor_and_page_bytes()?;
+    if descriptors.len() > u8::MAX.into() {
+      return Err("Block descriptor length must be in the range 0..2^8");
+    }
+
+    let mut data = vec![0; ModeParameterHeader::LEN_6];
+    data[1] = self.header.medium_type;
+    data[2] = self.header.device_specific_parameter();
+    data[3] = descriptors.len() as u8;
+    data.extend(descriptors);
+    data.extend(pages);
+
+    if data.len() > u8::MAX.into() {
+      return Err("Parameter list length must be in the range 0..2^8");
+    }
+
+    Ok(data)
+  }
+
+  /// Encode as the parameter list of MODE SELECT(10)
+  pub fn to_bytes_10(&self) -> Result <Vec <u8>, &'static str> {
+    let (descriptors, pages) = self.descriptor_and_page_bytes()?;
+
+    let mut data = vec![0; ModeParameterHeader::LEN_10];
+    data[2] = self.header.medium_type;
+    data[3] = self.header.device_specific_parameter();
+    data[4] = if self.header.longlba { 0x1 } else { 0x0 };
+    data[6..8].copy_from_slice(&(descriptors.len() as u16).to_be_bytes());
+    data.extend(descriptors);
+    data.extend(pages);
+
+    if data.len() > u16::MAX.into() {
+      return Err("Parameter list length must be in the range 0..2^16");
+    }
+
+    Ok(data)
+  }
+
+  pub fn page(&self, page_code: u8, subpage_code: u8) -> Option <&ModePage> {
+    self.pages.iter().find(|p| p.page_code == page_code && p.subpage_code == subpage_code)
+  }
+
+  /// The page registered for `T`, decoded
+  pub fn typed_page <T: ModePageType> (&self) -> Option <T> {
+    self.page(T::PAGE_CODE, T::SUBPAGE_CODE).and_then(T::from_page)
+  }
+}
+
+impl fmt::Display for ModeParameters {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, concat!(
+        "Medium Type: {:#04x}\n",
+        "WP: {}\n",
+        "Buffered Mode: {}\n",
+        "Speed: {}"),
+        self.header.medium_type,
+        self.header.wp,
+        self.header.buffered_mode,
+        self.header.speed,
+    )?;
+
+    for descriptor in &self.block_descriptors {
+      writeln!(f, "Block Descriptor: density {:#04x}, {} blocks, block length {}",
+          descriptor.density_code, descriptor.number_of_blocks, descriptor.block_length)?;
+    }
+
+    for page in &self.pages {
+      write!(f, "{}", page.decode())?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Read one page with MODE SENSE(10) and the given page control
+pub fn read_mode_page(device: &Device, pc: u8, page_code: u8, subpage_code: u8)
+    -> Result <(ModeParameterHeader, ModePage), String> {
+  let cmd = ModeSense10 {
+    dbd: true,
+    pc,
+    page_code,
+    subpage_code,
+    allocation_length: u16::MAX,
+    ..Default::default()
+  };
+
+  let mut buf = vec![0; cmd.allocation_length.into()];
+  check_good("MODE SENSE(10)", device.issue_cmd_with_output(&cmd, &mut buf)?)?;
+
+  let params = match cmd.parse_buffer(&buf) {
+    Some(t) => { t },
+    None => { return Err("Could not parse MODE SENSE(10) data".to_string()); },
+  };
+
+  match params.page(page_code, subpage_code) {
+    Some(page) => { Ok((params.header.clone(), page.clone())) },
+    None => { Err(format!("Mode page {:#04x}/{:#04x} not returned", page_code, subpage_code)) },
+  }
+}
+
+/// Read only the mode parameter header with MODE SENSE(10), for the medium
+/// type and write protection of the loaded medium
+pub fn read_mode_parameter_header(device: &Device) -> Result <ModeParameterHeader, String> {
+  let cmd = ModeSense10 {
+    dbd: true,
+    page_code: 0x3F,
+    allocation_length: ModeParameterHeader::LEN_10 as u16,
+    ..Default::default()
+  };
+
+  let mut buf = vec![0; cmd.allocation_length.into()];
+  check_good("MODE SENSE(10)", device.issue_cmd_with_output(&cmd, &mut buf)?)?;
+
+  //Only the header fits, so a block descriptor returned by a drive that
+  //ignores DBD is cut off rather than parsed
+  ModeParameterHeader::from_bytes_10(&buf)
+      .ok_or_else(|| "Could not parse MODE SENSE(10) data".to_string())
+}
+
+/// Read the current and changeable values of a page, let `change` modify the
+/// current values, and write back only the changeable bits with MODE
+/// SELECT(10). If `save` is set the page is also saved.
+pub fn apply_mode_page_change <F> (device: &Device, page_code: u8, subpage_code: u8,
+    save: bool, change: F) -> Result <DeviceStatus, String>
+  where F: FnOnce(&mut ModePage) -> Result <(), String>,
+{
+  let (header, current) = read_mode_page(device, ModeSense10::CURRENT, page_code, subpage_code)?;
+  let (_, changeable) = read_mode_page(device, ModeSense10::CHANGEABLE, page_code, subpage_code)?;
+
+  let mut changed = current.clone();
+  change(&mut changed)?;
+
+  let params = ModeParameters {
+    header: ModeParameterHeader {
+      buffered_mode: header.buffered_mode,
+      speed: header.speed,
+      ..Default::default()
+    },
+    block_descriptors: Vec::new(),
+    pages: vec![current.apply_change(&changed, &changeable)],
+  };
+
+  let buf = params.to_bytes_10().map_err(|e| e.to_string())?;
+  let cmd = ModeSelect10 {
+    pf: true,
+    sp: save,
+    parameter_list_length: buf.len() as u16,
+  };
+  check_good("MODE SELECT(10)", device.issue_cmd_with_input(&cmd, &buf)?)
+}
+
+/// Typed form of [`apply_mode_page_change`]
+pub fn apply_mode_page <T, F> (device: &Device, save: bool, change: F)
+    -> Result <DeviceStatus, String>
+  where T: ModePageType, F: FnOnce(&mut T),
+{
+  apply_mode_page_change(device, T::PAGE_CODE, T::SUBPAGE_CODE, save, |page| {
+    let mut typed = match T::from_page(page) {
+      Some(t) => { t },
+      None => { return Err(format!("Could not parse mode page {:#04x}", T::PAGE_CODE)); },
+    };
+    change(&mut typed);
+    page.data = typed.to_data();
+    Ok(())
+  })
+}
+
+#[test]
+fn mode_parameters_test() {
+  let buf = vec![
+    // MODE SENSE(6) header: buffered mode 1, WP set
+    0x1B, 0x00, 0x90, 0x08,
+    // Short block descriptor: density 0x5A, block length 0x40000
+    0x5A, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00,
+    // Page 0x0F page_0 format
+    0x8F, 0x0E, 0xC0, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00,
+  ];
+
+  let params = ModeParameters::from_bytes_6(&buf).unwrap();
+  assert!(params.header.wp);
+  assert_eq!(params.header.buffered_mode, 1);
+  assert_eq!(params.block_descriptors.len(), 1);
+  assert_eq!(params.block_descriptors[0].density_code, 0x5A);
+  assert_eq!(params.block_descriptors[0].block_length, 0x40000);
+  assert_eq!(params.pages.len(), 1);
+  assert!(params.pages[0].ps);
+  assert_eq!(params.pages[0].page_code, 0x0F);
+  assert_eq!(params.pages[0].data.len(), 14);
+
+  let encoded = params.to_bytes_6().unwrap();
+  assert_eq!(encoded[0], 0);
+  assert_eq!(encoded[2], 0x10);
+  assert_eq!(encoded[4..12], buf[4..12]);
+  assert_eq!(encoded[12], 0x0F);
+  assert_eq!(encoded[13..], buf[13..]);
+
+  let current = &params.pages[0];
+  let mut changed = current.clone();
+  changed.data[0] = 0x00;
+  changed.data[1] = 0x00;
+  let changeable = ModePage { data: vec![0x80], ..Default::default() };
+  let applied = current.apply_change(&changed, &changeable);
+  assert!(!applied.ps);
+  assert_eq!(applied.data[0], 0x40);
+  assert_eq!(applied.data[1], 0x80);
+}
+
+#[test]
+fn mode_parameter_header_test() {
+  // Only the header of a reply that carries a block descriptor despite DBD
+  let buf = [0x00, 0x3E, 0x58, 0x10, 0x00, 0x00, 0x00, 0x08];
+  assert!(ModeParameters::from_bytes_10(&buf).is_none());
+
+  let header = ModeParameterHeader::from_bytes_10(&buf).unwrap();
+  assert_eq!(header.medium_type, 0x58);
+  assert_eq!(header.buffered_mode, 1);
+  assert_eq!(header.block_descriptor_length, 8);
+  assert!(ModeParameterHeader::from_bytes_10(&buf[..4]).is_none());
+}
+
+#[test]
+fn subpage_test() {
+  let buf = [0x50, 0x01, 0x00, 0x02, 0xAA, 0xBB];
+  let (page, len) = ModePage::from_bytes(&buf).unwrap();
+  assert_eq!(len, 6);
+  assert!(page.spf);
+  assert_eq!(page.page_code, 0x10);
+  assert_eq!(page.subpage_code, 0x01);
+  assert_eq!(page.to_bytes(), Ok(buf.to_vec()));
+}
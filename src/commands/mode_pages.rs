@@ -0,0 +1,649 @@
+use crate::commands::ModePage;
+use crate::commands::ModePageType;
+use crate::commands::mode::apply_mode_page;
+use crate::device::{Device, DeviceStatus};
+use std::fmt;
+
+fn bit(byte: u8, mask: u8) -> bool {
+  byte & mask == mask
+}
+
+fn flag(val: bool, mask: u8) -> u8 {
+  if val { mask } else { 0x00 }
+}
+
+/// SSC-4 Section 8.3.2
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataCompression {
+  pub dce: bool,
+  pub dcc: bool,
+  pub dde: bool,
+  pub red: u8,
+  pub compression_algorithm: u32,
+  pub decompression_algorithm: u32,
+}
+
+impl DataCompression {
+  pub const LEN: usize = 14;
+}
+
+impl ModePageType for DataCompression {
+  const PAGE_CODE: u8 = 0x0F;
+
+  fn from_page(page: &ModePage) -> Option <DataCompression> {
+    let d = &page.data;
+    if d.len() < 10 {
+      return None;
+    }
+
+    Some(DataCompression {
+      dce: bit(d[0], 0x80),
+      dcc: bit(d[0], 0x40),
+      dde: bit(d[1], 0x80),
+      red: (d[1] >> 5) & 0x3,
+      compression_algorithm: u32::from_be_bytes([d[2], d[3], d[4], d[5]]),
+      decompression_algorithm: u32::from_be_bytes([d[6], d[7], d[8], d[9]]),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = flag(self.dce, 0x80) | flag(self.dcc, 0x40);
+    d[1] = flag(self.dde, 0x80) | ((self.red & 0x3) << 5);
+    d[2..6].copy_from_slice(&self.compression_algorithm.to_be_bytes());
+    d[6..10].copy_from_slice(&self.decompression_algorithm.to_be_bytes());
+    d
+  }
+}
+
+impl fmt::Display for DataCompression {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Data Compression:")?;
+    writeln!(f, "  DCE: {} DCC: {} DDE: {} RED: {}", self.dce, self.dcc, self.dde, self.red)?;
+    writeln!(f, "  Compression Algorithm: {:#x}", self.compression_algorithm)?;
+    writeln!(f, "  Decompression Algorithm: {:#x}", self.decompression_algorithm)
+  }
+}
+
+/// SSC-4 Section 8.3.3
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceConfiguration {
+  pub cap: bool,
+  pub caf: bool,
+  pub active_format: u8,
+  pub active_partition: u8,
+  pub write_object_buffer_full_ratio: u8,
+  pub read_object_buffer_empty_ratio: u8,
+  /// In units of 100 ms
+  pub write_delay_time: u16,
+  pub obr: bool,
+  /// LOIS in SSC-4
+  pub bis: bool,
+  pub rsmk: bool,
+  pub avc: u8,
+  pub socf: u8,
+  /// ROBO in SSC-4
+  pub rbo: bool,
+  pub gap_size: u8,
+  pub eod_defined: u8,
+  pub eeg: bool,
+  pub sew: bool,
+  pub swp: bool,
+  pub baml: bool,
+  pub bam: bool,
+  pub buffer_size_at_early_warning: u32,
+  pub select_data_compression_algorithm: u8,
+  pub oir: bool,
+  pub wtre: u8,
+  pub rew: bool,
+}
+
+impl DeviceConfiguration {
+  pub const LEN: usize = 14;
+}
+
+impl ModePageType for DeviceConfiguration {
+  const PAGE_CODE: u8 = 0x10;
+
+  fn from_page(page: &ModePage) -> Option <DeviceConfiguration> {
+    let d = &page.data;
+    if d.len() < Self::LEN {
+      return None;
+    }
+
+    Some(DeviceConfiguration {
+      cap: bit(d[0], 0x40),
+      caf: bit(d[0], 0x20),
+      active_format: d[0] & 0x1F,
+      active_partition: d[1],
+      write_object_buffer_full_ratio: d[2],
+      read_object_buffer_empty_ratio: d[3],
+      write_delay_time: u16::from_be_bytes([d[4], d[5]]),
+      obr: bit(d[6], 0x80),
+      bis: bit(d[6], 0x40),
+      rsmk: bit(d[6], 0x20),
+      avc: (d[6] >> 3) & 0x3,
+      socf: (d[6] >> 1) & 0x3,
+      rbo: bit(d[6], 0x01),
+      gap_size: d[7],
+      eod_defined: d[8] >> 5,
+      eeg: bit(d[8], 0x10),
+      sew: bit(d[8], 0x08),
+      swp: bit(d[8], 0x04),
+      baml: bit(d[8], 0x02),
+      bam: bit(d[8], 0x01),
+      buffer_size_at_early_warning: u32::from_be_bytes([0, d[9], d[10], d[11]]),
+      select_data_compression_algorithm: d[12],
+      oir: bit(d[13], 0x20),
+      wtre: (d[13] >> 3) & 0x3,
+      rew: bit(d[13], 0x01),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = flag(self.cap, 0x40) | flag(self.caf, 0x20) | (self.active_format & 0x1F);
+    d[1] = self.active_partition;
+    d[2] = self.write_object_buffer_full_ratio;
+    d[3] = self.read_object_buffer_empty_ratio;
+    d[4..6].copy_from_slice(&self.write_delay_time.to_be_bytes());
+    d[6] = flag(self.obr, 0x80) | flag(self.bis, 0x40) | flag(self.rsmk, 0x20)
+        | ((self.avc & 0x3) << 3) | ((self.socf & 0x3) << 1) | flag(self.rbo, 0x01);
+    d[7] = self.gap_size;
+    d[8] = ((self.eod_defined & 0x7) << 5) | flag(self.eeg, 0x10) | flag(self.sew, 0x08)
+        | flag(self.swp, 0x04) | flag(self.baml, 0x02) | flag(self.bam, 0x01);
+    d[9..12].copy_from_slice(&self.buffer_size_at_early_warning.to_be_bytes()[1..]);
+    d[12] = self.select_data_compression_algorithm;
+    d[13] = flag(self.oir, 0x20) | ((self.wtre & 0x3) << 3) | flag(self.rew, 0x01);
+    d
+  }
+}
+
+impl fmt::Display for DeviceConfiguration {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Device Configuration:")?;
+    writeln!(f, "  Active Format: {} Active Partition: {}",
+        self.active_format, self.active_partition)?;
+    writeln!(f, "  Write Delay Time: {} ms", u32::from(self.write_delay_time) * 100)?;
+    writeln!(f, "  OBR: {} BIS: {} RSMK: {} AVC: {} SOCF: {} RBO: {}",
+        self.obr, self.bis, self.rsmk, self.avc, self.socf, self.rbo)?;
+    writeln!(f, "  EOD Defined: {} EEG: {} SEW: {} SWP: {} REW: {}",
+        self.eod_defined, self.eeg, self.sew, self.swp, self.rew)?;
+    writeln!(f, "  Buffer Size at Early Warning: {}", self.buffer_size_at_early_warning)?;
+    writeln!(f, "  Select Data Compression Algorithm: {}",
+        self.select_data_compression_algorithm)
+  }
+}
+
+/// SSC-4 Section 8.3.4
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceConfigurationExtension {
+  pub tarpf: bool,
+  pub asocwp: bool,
+  pub perswp: bool,
+  pub prmwp: bool,
+  pub short_erase_mode: u8,
+  pub write_mode: u8,
+  /// In megabytes
+  pub pews: u16,
+}
+
+impl DeviceConfigurationExtension {
+  pub const LEN: usize = 28;
+}
+
+impl ModePageType for DeviceConfigurationExtension {
+  const PAGE_CODE: u8 = 0x10;
+  const SUBPAGE_CODE: u8 = 0x01;
+
+  fn from_page(page: &ModePage) -> Option <DeviceConfigurationExtension> {
+    let d = &page.data;
+    if d.len() < 5 {
+      return None;
+    }
+
+    Some(DeviceConfigurationExtension {
+      tarpf: bit(d[0], 0x08),
+      asocwp: bit(d[0], 0x04),
+      perswp: bit(d[0], 0x02),
+      prmwp: bit(d[0], 0x01),
+      short_erase_mode: d[1],
+      write_mode: d[2] & 0x0F,
+      pews: u16::from_be_bytes([d[3], d[4]]),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = flag(self.tarpf, 0x08) | flag(self.asocwp, 0x04) | flag(self.perswp, 0x02)
+        | flag(self.prmwp, 0x01);
+    d[1] = self.short_erase_mode;
+    d[2] = self.write_mode & 0x0F;
+    d[3..5].copy_from_slice(&self.pews.to_be_bytes());
+    d
+  }
+}
+
+impl fmt::Display for DeviceConfigurationExtension {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Device Configuration Extension:")?;
+    writeln!(f, "  TARPF: {} ASOCWP: {} PERSWP: {} PRMWP: {}",
+        self.tarpf, self.asocwp, self.perswp, self.prmwp)?;
+    writeln!(f, "  Short Erase Mode: {} Write Mode: {}", self.short_erase_mode, self.write_mode)?;
+    writeln!(f, "  PEWS: {} MB", self.pews)
+  }
+}
+
+/// SSC-4 Section 8.3.5
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediumPartition {
+  pub maximum_additional_partitions: u8,
+  pub additional_partitions_defined: u8,
+  pub fdp: bool,
+  pub sdp: bool,
+  pub idp: bool,
+  pub psum: u8,
+  pub pofm: bool,
+  pub clear: bool,
+  pub addp: bool,
+  pub medium_format_recognition: u8,
+  pub partition_units: u8,
+  pub partition_sizes: Vec <u16>,
+}
+
+impl MediumPartition {
+  pub const PSUM_BYTES: u8 = 0x0;
+  pub const PSUM_KILOBYTES: u8 = 0x1;
+  pub const PSUM_MEGABYTES: u8 = 0x2;
+  pub const PSUM_PARTITION_UNITS: u8 = 0x3;
+
+  /// Request initiator-defined partitions of the given sizes. The sizes are
+  /// in units of 10^`partition_units` bytes; 0xFFFF gives a partition the
+  /// remaining capacity. The partitions are created by a later FORMAT MEDIUM,
+  /// or straight away unless POFM is set.
+  pub fn set_partition_sizes(&mut self, partition_units: u8, sizes: &[u16]) {
+    self.fdp = false;
+    self.sdp = false;
+    self.idp = true;
+    self.psum = Self::PSUM_PARTITION_UNITS;
+    self.partition_units = partition_units;
+    self.additional_partitions_defined = sizes.len().saturating_sub(1) as u8;
+    self.partition_sizes = sizes.to_vec();
+  }
+}
+
+impl ModePageType for MediumPartition {
+  const PAGE_CODE: u8 = 0x11;
+
+  fn from_page(page: &ModePage) -> Option <MediumPartition> {
+    let d = &page.data;
+    if d.len() < 6 {
+      return None;
+    }
+
+    Some(MediumPartition {
+      maximum_additional_partitions: d[0],
+      additional_partitions_defined: d[1],
+      fdp: bit(d[2], 0x80),
+      sdp: bit(d[2], 0x40),
+      idp: bit(d[2], 0x20),
+      psum: (d[2] >> 3) & 0x3,
+      pofm: bit(d[2], 0x04),
+      clear: bit(d[2], 0x02),
+      addp: bit(d[2], 0x01),
+      medium_format_recognition: d[3],
+      partition_units: d[4] & 0x0F,
+      partition_sizes: d[6..].chunks_exact(2).map(|s| u16::from_be_bytes([s[0], s[1]])).collect(),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; 6];
+    d[0] = self.maximum_additional_partitions;
+    d[1] = self.additional_partitions_defined;
+    d[2] = flag(self.fdp, 0x80) | flag(self.sdp, 0x40) | flag(self.idp, 0x20)
+        | ((self.psum & 0x3) << 3) | flag(self.pofm, 0x04) | flag(self.clear, 0x02)
+        | flag(self.addp, 0x01);
+    d[3] = self.medium_format_recognition;
+    d[4] = self.partition_units & 0x0F;
+    for size in &self.partition_sizes {
+      d.extend_from_slice(&size.to_be_bytes());
+    }
+    d
+  }
+}
+
+impl fmt::Display for MediumPartition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Medium Partition:")?;
+    writeln!(f, "  Maximum Additional Partitions: {} Additional Partitions Defined: {}",
+        self.maximum_additional_partitions, self.additional_partitions_defined)?;
+    writeln!(f, "  FDP: {} SDP: {} IDP: {} PSUM: {} POFM: {} CLEAR: {} ADDP: {}",
+        self.fdp, self.sdp, self.idp, self.psum, self.pofm, self.clear, self.addp)?;
+    writeln!(f, "  Medium Format Recognition: {:#04x} Partition Units: {}",
+        self.medium_format_recognition, self.partition_units)?;
+    writeln!(f, "  Partition Sizes: {:?}", self.partition_sizes)
+  }
+}
+
+/// SPC-3 Section 7.4.6
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Control {
+  pub tst: u8,
+  pub tmf_only: bool,
+  pub d_sense: bool,
+  pub gltsd: bool,
+  pub rlec: bool,
+  pub queue_algorithm_modifier: u8,
+  pub qerr: u8,
+  pub rac: bool,
+  pub ua_intlck_ctrl: u8,
+  pub swp: bool,
+  pub ato: bool,
+  pub tas: bool,
+  pub autoload_mode: u8,
+  /// In units of 100 ms
+  pub busy_timeout_period: u16,
+  /// In seconds
+  pub extended_self_test_completion_time: u16,
+}
+
+impl Control {
+  pub const LEN: usize = 10;
+
+  /// Switch the device between descriptor (`enable`) and fixed format sense
+  /// data. If `save` is set the choice survives a power cycle.
+  pub fn set_descriptor_sense(device: &Device, enable: bool, save: bool)
+      -> Result <DeviceStatus, String> {
+    apply_mode_page(device, save, |page: &mut Control| { page.d_sense = enable; })
+  }
+}
+
+impl ModePageType for Control {
+  const PAGE_CODE: u8 = 0x0A;
+
+  fn from_page(page: &ModePage) -> Option <Control> {
+    let d = &page.data;
+    if d.len() < Self::LEN {
+      return None;
+    }
+
+    Some(Control {
+      tst: d[0] >> 5,
+      tmf_only: bit(d[0], 0x10),
+      d_sense: bit(d[0], 0x04),
+      gltsd: bit(d[0], 0x02),
+      rlec: bit(d[0], 0x01),
+      queue_algorithm_modifier: d[1] >> 4,
+      qerr: (d[1] >> 1) & 0x3,
+      rac: bit(d[2], 0x40),
+      ua_intlck_ctrl: (d[2] >> 4) & 0x3,
+      swp: bit(d[2], 0x08),
+      ato: bit(d[3], 0x80),
+      tas: bit(d[3], 0x40),
+      autoload_mode: d[3] & 0x7,
+      busy_timeout_period: u16::from_be_bytes([d[6], d[7]]),
+      extended_self_test_completion_time: u16::from_be_bytes([d[8], d[9]]),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = ((self.tst & 0x7) << 5) | flag(self.tmf_only, 0x10) | flag(self.d_sense, 0x04)
+        | flag(self.gltsd, 0x02) | flag(self.rlec, 0x01);
+    d[1] = ((self.queue_algorithm_modifier & 0x0F) << 4) | ((self.qerr & 0x3) << 1);
+    d[2] = flag(self.rac, 0x40) | ((self.ua_intlck_ctrl & 0x3) << 4) | flag(self.swp, 0x08);
+    d[3] = flag(self.ato, 0x80) | flag(self.tas, 0x40) | (self.autoload_mode & 0x7);
+    d[6..8].copy_from_slice(&self.busy_timeout_period.to_be_bytes());
+    d[8..10].copy_from_slice(&self.extended_self_test_completion_time.to_be_bytes());
+    d
+  }
+}
+
+impl fmt::Display for Control {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Control:")?;
+    writeln!(f, "  TST: {} TMF_ONLY: {} D_SENSE: {} GLTSD: {} RLEC: {}",
+        self.tst, self.tmf_only, self.d_sense, self.gltsd, self.rlec)?;
+    writeln!(f, "  Queue Algorithm Modifier: {} QERR: {}",
+        self.queue_algorithm_modifier, self.qerr)?;
+    writeln!(f, "  RAC: {} UA_INTLCK_CTRL: {} SWP: {} ATO: {} TAS: {} Autoload Mode: {}",
+        self.rac, self.ua_intlck_ctrl, self.swp, self.ato, self.tas, self.autoload_mode)?;
+    writeln!(f, "  Busy Timeout Period: {}", self.busy_timeout_period)?;
+    writeln!(f, "  Extended Self-Test Completion Time: {} s",
+        self.extended_self_test_completion_time)
+  }
+}
+
+/// SSC-4 Section 8.3.6
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadWriteErrorRecovery {
+  pub tb: bool,
+  pub eer: bool,
+  pub per: bool,
+  pub dte: bool,
+  pub dcr: bool,
+  pub read_retry_count: u8,
+  pub write_retry_count: u8,
+}
+
+impl ReadWriteErrorRecovery {
+  pub const LEN: usize = 10;
+}
+
+impl ModePageType for ReadWriteErrorRecovery {
+  const PAGE_CODE: u8 = 0x01;
+
+  fn from_page(page: &ModePage) -> Option <ReadWriteErrorRecovery> {
+    let d = &page.data;
+    if d.len() < 7 {
+      return None;
+    }
+
+    Some(ReadWriteErrorRecovery {
+      tb: bit(d[0], 0x20),
+      eer: bit(d[0], 0x08),
+      per: bit(d[0], 0x04),
+      dte: bit(d[0], 0x02),
+      dcr: bit(d[0], 0x01),
+      read_retry_count: d[1],
+      write_retry_count: d[6],
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = flag(self.tb, 0x20) | flag(self.eer, 0x08) | flag(self.per, 0x04)
+        | flag(self.dte, 0x02) | flag(self.dcr, 0x01);
+    d[1] = self.read_retry_count;
+    d[6] = self.write_retry_count;
+    d
+  }
+}
+
+impl fmt::Display for ReadWriteErrorRecovery {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Read-Write Error Recovery:")?;
+    writeln!(f, "  TB: {} EER: {} PER: {} DTE: {} DCR: {}",
+        self.tb, self.eer, self.per, self.dte, self.dcr)?;
+    writeln!(f, "  Read Retry Count: {} Write Retry Count: {}",
+        self.read_retry_count, self.write_retry_count)
+  }
+}
+
+/// SPC-3 Section 7.4.11
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InformationalExceptionsControl {
+  pub perf: bool,
+  pub ebf: bool,
+  pub ewasc: bool,
+  pub dexcpt: bool,
+  pub test: bool,
+  pub logerr: bool,
+  pub mrie: u8,
+  /// In units of 100 ms
+  pub interval_timer: u32,
+  pub report_count: u32,
+}
+
+impl InformationalExceptionsControl {
+  pub const LEN: usize = 10;
+  pub const MRIE_NO_REPORTING: u8 = 0x0;
+  pub const MRIE_GENERATE_UNIT_ATTENTION: u8 = 0x2;
+  pub const MRIE_CONDITIONALLY_GENERATE_RECOVERED_ERROR: u8 = 0x3;
+  pub const MRIE_UNCONDITIONALLY_GENERATE_RECOVERED_ERROR: u8 = 0x4;
+  pub const MRIE_GENERATE_NO_SENSE: u8 = 0x5;
+  pub const MRIE_ONLY_REPORT_ON_REQUEST: u8 = 0x6;
+
+  /// Enable informational exception (TapeAlert) reporting with the given
+  /// method. MRIE_ONLY_REPORT_ON_REQUEST leaves the TapeAlert log page to be
+  /// polled; the others also report through sense data every
+  /// `interval_timer` (100 ms units, 0 for vendor default).
+  pub fn set_reporting(device: &Device, mrie: u8, interval_timer: u32, save: bool)
+      -> Result <DeviceStatus, String> {
+    apply_mode_page(device, save, |page: &mut InformationalExceptionsControl| {
+      page.dexcpt = mrie == Self::MRIE_NO_REPORTING;
+      page.test = false;
+      page.mrie = mrie;
+      page.interval_timer = interval_timer;
+    })
+  }
+}
+
+impl ModePageType for InformationalExceptionsControl {
+  const PAGE_CODE: u8 = 0x1C;
+
+  fn from_page(page: &ModePage) -> Option <InformationalExceptionsControl> {
+    let d = &page.data;
+    if d.len() < Self::LEN {
+      return None;
+    }
+
+    Some(InformationalExceptionsControl {
+      perf: bit(d[0], 0x80),
+      ebf: bit(d[0], 0x20),
+      ewasc: bit(d[0], 0x10),
+      dexcpt: bit(d[0], 0x08),
+      test: bit(d[0], 0x04),
+      logerr: bit(d[0], 0x01),
+      mrie: d[1] & 0x0F,
+      interval_timer: u32::from_be_bytes([d[2], d[3], d[4], d[5]]),
+      report_count: u32::from_be_bytes([d[6], d[7], d[8], d[9]]),
+    })
+  }
+
+  fn to_data(&self) -> Vec <u8> {
+    let mut d = vec![0; Self::LEN];
+    d[0] = flag(self.perf, 0x80) | flag(self.ebf, 0x20) | flag(self.ewasc, 0x10)
+        | flag(self.dexcpt, 0x08) | flag(self.test, 0x04) | flag(self.logerr, 0x01);
+    d[1] = self.mrie & 0x0F;
+    d[2..6].copy_from_slice(&self.interval_timer.to_be_bytes());
+    d[6..10].copy_from_slice(&self.report_count.to_be_bytes());
+    d
+  }
+}
+
+impl fmt::Display for InformationalExceptionsControl {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Informational Exceptions Control:")?;
+    writeln!(f, "  PERF: {} EBF: {} EWASC: {} DEXCPT: {} TEST: {} LOGERR: {}",
+        self.perf, self.ebf, self.ewasc, self.dexcpt, self.test, self.logerr)?;
+    writeln!(f, "  MRIE: {} Interval Timer: {} Report Count: {}",
+        self.mrie, self.interval_timer, self.report_count)
+  }
+}
+
+#[test]
+fn ssc_mode_pages_test() {
+  use crate::commands::mode::TypedModePage;
+
+  let buf = [0x8F, 0x0E, 0xC0, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01,
+      0x00, 0x00, 0x00, 0x00];
+  let (page, _) = ModePage::from_bytes(&buf).unwrap();
+  let compression = match page.decode() {
+    TypedModePage::DataCompression(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert!(compression.dce);
+  assert!(compression.dcc);
+  assert!(compression.dde);
+  assert_eq!(compression.compression_algorithm, 1);
+  assert_eq!(compression.to_data(), buf[2..].to_vec());
+
+  let mut partition = MediumPartition { maximum_additional_partitions: 1, ..Default::default() };
+  partition.set_partition_sizes(9, &[0xFFFF, 37]);
+  let data = partition.to_data();
+  assert_eq!(data, vec![0x01, 0x01, 0x38, 0x00, 0x09, 0x00, 0xFF, 0xFF, 0x00, 0x25]);
+  let page = ModePage { page_code: 0x11, data, ..Default::default() };
+  assert_eq!(MediumPartition::from_page(&page), Some(partition));
+
+  let extension = ModePage {
+    spf: true,
+    page_code: 0x10,
+    subpage_code: 0x01,
+    data: vec![0x08, 0x00, 0x01, 0x00, 0x10],
+    ..Default::default()
+  };
+  match extension.decode() {
+    TypedModePage::DeviceConfigurationExtension(t) => {
+      assert!(t.tarpf);
+      assert_eq!(t.write_mode, 1);
+      assert_eq!(t.pews, 16);
+    },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+}
+
+#[test]
+fn medium_partition_change_test() {
+  //Drive currently has a single partition
+  let current = ModePage {
+    page_code: 0x11,
+    data: vec![0x01, 0x00, 0x18, 0x00, 0x09, 0x00, 0xFF, 0xFF],
+    ..Default::default()
+  };
+  let changeable = ModePage {
+    page_code: 0x11,
+    data: vec![0x00, 0xFF, 0xFC, 0x00, 0x0F, 0x00, 0xFF, 0xFF],
+    ..Default::default()
+  };
+
+  let mut partition = MediumPartition::from_page(&current).unwrap();
+  partition.set_partition_sizes(9, &[0xFFFF, 37]);
+  let changed = partition.to_page();
+
+  let applied = current.apply_change(&changed, &changeable);
+  assert_eq!(applied.data, vec![0x01, 0x01, 0x38, 0x00, 0x09, 0x00, 0xFF, 0xFF, 0x00, 0x25]);
+  let applied = MediumPartition::from_page(&applied).unwrap();
+  assert!(applied.idp);
+  assert_eq!(applied.additional_partitions_defined, 1);
+  assert_eq!(applied.partition_sizes, vec![0xFFFF, 37]);
+}
+
+#[test]
+fn spc_mode_pages_test() {
+  let data = vec![0x04, 0x02, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x3C];
+  let page = ModePage { page_code: 0x0A, data: data.clone(), ..Default::default() };
+  let control = Control::from_page(&page).unwrap();
+  assert!(control.d_sense);
+  assert_eq!(control.qerr, 1);
+  assert_eq!(control.extended_self_test_completion_time, 60);
+  assert_eq!(control.to_data(), data);
+
+  let data = vec![0x08, 0x03, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x01];
+  let page = ModePage { page_code: 0x1C, data: data.clone(), ..Default::default() };
+  let exceptions = InformationalExceptionsControl::from_page(&page).unwrap();
+  assert!(exceptions.dexcpt);
+  assert_eq!(exceptions.mrie,
+      InformationalExceptionsControl::MRIE_CONDITIONALLY_GENERATE_RECOVERED_ERROR);
+  assert_eq!(exceptions.interval_timer, 10);
+  assert_eq!(exceptions.report_count, 1);
+  assert_eq!(exceptions.to_data(), data);
+}
@@ -0,0 +1,54 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-3 Section 6.8
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeSelect10 {
+  pub pf: bool,
+  pub sp: bool,
+  pub parameter_list_length: u16,
+}
+
+impl ModeSelect10 {
+  const OP_CODE: u8 = 0x55;
+
+  pub fn new() -> ModeSelect10 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ModeSelect10> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ModeSelect10 {
+      pf: buf[1] & 0x10 == 0x10,
+      sp: buf[1] & 0x01 == 0x01,
+      parameter_list_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+}
+
+impl crate::Input for ModeSelect10 {
+}
+
+impl crate::Command for ModeSelect10 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.pf { 0x10 } else { 0x00 };
+    data[1] |= if self.sp { 0x01 } else { 0x00 };
+    data[7..9].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,54 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-3 Section 6.7
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeSelect6 {
+  pub pf: bool,
+  pub sp: bool,
+  pub parameter_list_length: u8,
+}
+
+impl ModeSelect6 {
+  const OP_CODE: u8 = 0x15;
+
+  pub fn new() -> ModeSelect6 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ModeSelect6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ModeSelect6 {
+      pf: buf[1] & 0x10 == 0x10,
+      sp: buf[1] & 0x01 == 0x01,
+      parameter_list_length: buf[4],
+    })
+  }
+}
+
+impl crate::Input for ModeSelect6 {
+}
+
+impl crate::Command for ModeSelect6 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.pf { 0x10 } else { 0x00 };
+    data[1] |= if self.sp { 0x01 } else { 0x00 };
+    data[4] = self.parameter_list_length;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,81 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::ModeParameters;
+
+/// SPC-3 Section 6.10
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeSense10 {
+  pub llbaa: bool,
+  pub dbd: bool,
+  pub pc: u8,
+  pub page_code: u8,
+  pub subpage_code: u8,
+  pub allocation_length: u16,
+}
+
+impl ModeSense10 {
+  pub const CURRENT: u8 = 0x0;
+  pub const CHANGEABLE: u8 = 0x1;
+  pub const DEFAULT: u8 = 0x2;
+  pub const SAVED: u8 = 0x3;
+  pub const ALL_PAGES: u8 = 0x3F;
+  pub const ALL_SUBPAGES: u8 = 0xFF;
+  const OP_CODE: u8 = 0x5A;
+
+  pub fn new() -> ModeSense10 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ModeSense10> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ModeSense10 {
+      llbaa: buf[1] & 0x10 == 0x10,
+      dbd: buf[1] & 0x08 == 0x08,
+      pc: buf[2] >> 6,
+      page_code: buf[2] & 0x3F,
+      subpage_code: buf[3],
+      allocation_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <ModeParameters> {
+    ModeParameters::from_bytes_10(buf)
+  }
+}
+
+impl crate::Output for ModeSense10 {
+}
+
+impl crate::Command for ModeSense10 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    if self.pc > Self::SAVED {
+      return Err("Page control must be in the range 0..4");
+    }
+
+    if self.page_code > Self::ALL_PAGES {
+      return Err("Page code must be in the range 0..64");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.llbaa { 0x10 } else { 0x00 };
+    data[1] |= if self.dbd { 0x08 } else { 0x00 };
+    data[2] = (self.pc << 6) | self.page_code;
+    data[3] = self.subpage_code;
+    data[7..9].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,78 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::ModeParameters;
+
+/// SPC-3 Section 6.9
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeSense6 {
+  pub dbd: bool,
+  pub pc: u8,
+  pub page_code: u8,
+  pub subpage_code: u8,
+  pub allocation_length: u8,
+}
+
+impl ModeSense6 {
+  pub const CURRENT: u8 = 0x0;
+  pub const CHANGEABLE: u8 = 0x1;
+  pub const DEFAULT: u8 = 0x2;
+  pub const SAVED: u8 = 0x3;
+  pub const ALL_PAGES: u8 = 0x3F;
+  pub const ALL_SUBPAGES: u8 = 0xFF;
+  const OP_CODE: u8 = 0x1A;
+
+  pub fn new() -> ModeSense6 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ModeSense6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ModeSense6 {
+      dbd: buf[1] & 0x08 == 0x08,
+      pc: buf[2] >> 6,
+      page_code: buf[2] & 0x3F,
+      subpage_code: buf[3],
+      allocation_length: buf[4],
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <ModeParameters> {
+    ModeParameters::from_bytes_6(buf)
+  }
+}
+
+impl crate::Output for ModeSense6 {
+}
+
+impl crate::Command for ModeSense6 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    if self.pc > Self::SAVED {
+      return Err("Page control must be in the range 0..4");
+    }
+
+    if self.page_code > Self::ALL_PAGES {
+      return Err("Page code must be in the range 0..64");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.dbd { 0x08 } else { 0x00 };
+    data[2] = (self.pc << 6) | self.page_code;
+    data[3] = self.subpage_code;
+    data[4] = self.allocation_length;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
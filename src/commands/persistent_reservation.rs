@@ -0,0 +1,479 @@
+use crate::commands::PersistentReserveIn;
+use crate::commands::PersistentReserveOut;
+use crate::device::{Device, DeviceStatus, Status};
+use std::convert::TryInto;
+use std::fmt;
+
+/// SPC-3 Section 6.11.3.4 persistent reservation types
+pub const WRITE_EXCLUSIVE: u8 = 0x1;
+pub const EXCLUSIVE_ACCESS: u8 = 0x3;
+pub const WRITE_EXCLUSIVE_REGISTRANTS_ONLY: u8 = 0x5;
+pub const EXCLUSIVE_ACCESS_REGISTRANTS_ONLY: u8 = 0x6;
+pub const WRITE_EXCLUSIVE_ALL_REGISTRANTS: u8 = 0x7;
+pub const EXCLUSIVE_ACCESS_ALL_REGISTRANTS: u8 = 0x8;
+
+/// Error from a reservation helper. A reservation conflict is kept apart
+/// from other failures since it is the expected outcome when another
+/// initiator holds the device.
+#[derive(Debug)]
+pub enum ReservationError {
+  /// Status RESERVATION CONFLICT: another initiator holds a reservation, or
+  /// the reservation key given is not registered for this initiator
+  ReservationConflict,
+  Failed(String),
+}
+
+impl From <String> for ReservationError {
+  fn from(e: String) -> ReservationError {
+    ReservationError::Failed(e)
+  }
+}
+
+impl fmt::Display for ReservationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ReservationError::ReservationConflict => { write!(f, "Reservation conflict") },
+      ReservationError::Failed(e) => { write!(f, "{}", e) },
+    }
+  }
+}
+
+impl std::error::Error for ReservationError {
+}
+
+/// Same as check_good in device.rs, reporting RESERVATION CONFLICT on its own
+pub(crate) fn check_reservation(name: &str, status: DeviceStatus)
+    -> Result <DeviceStatus, ReservationError> {
+  match status.status {
+    Status::Good => { Ok(status) },
+    Status::ReservationConflict => { Err(ReservationError::ReservationConflict) },
+    _ => { Err(ReservationError::Failed(format!("{} failed:\n{}", name, status))) },
+  }
+}
+
+/// SPC-3 Section 6.11.2 READ KEYS parameter data
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservationKeys {
+  pub generation: u32,
+  pub keys: Vec <u64>,
+}
+
+impl ReservationKeys {
+  pub fn from_bytes(buf: &[u8]) -> Option <ReservationKeys> {
+    let (generation, data) = split_header(buf)?;
+
+    Some(ReservationKeys {
+      generation,
+      keys: data.chunks_exact(8).map(|t| u64::from_be_bytes(t.try_into().unwrap())).collect(),
+    })
+  }
+}
+
+/// A persistent reservation held on the logical unit
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reservation {
+  pub key: u64,
+  pub scope: u8,
+  pub reservation_type: u8,
+}
+
+/// SPC-3 Section 6.11.3 READ RESERVATION parameter data
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservationStatus {
+  pub generation: u32,
+  /// None if no persistent reservation is held
+  pub reservation: Option <Reservation>,
+}
+
+impl ReservationStatus {
+  pub fn from_bytes(buf: &[u8]) -> Option <ReservationStatus> {
+    let (generation, data) = split_header(buf)?;
+
+    let reservation = if data.len() >= 16 {
+      Some(Reservation {
+        key: u64::from_be_bytes(data[0..8].try_into().unwrap()),
+        scope: data[13] >> 4,
+        reservation_type: data[13] & 0x0F,
+      })
+    } else {
+      None
+    };
+
+    Some(ReservationStatus {
+      generation,
+      reservation,
+    })
+  }
+}
+
+/// SPC-3 Section 6.11.4 REPORT CAPABILITIES parameter data
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservationCapabilities {
+  /// Compatible reservation handling, RESERVE(6)/RELEASE(6) follow SPC-3
+  /// Section 5.6.3
+  pub crh: bool,
+  /// Specify initiator ports capable
+  pub sip_c: bool,
+  /// All target ports capable
+  pub atp_c: bool,
+  /// Persist through power loss capable
+  pub ptpl_c: bool,
+  /// Type mask valid
+  pub tmv: bool,
+  /// Persist through power loss activated
+  pub ptpl_a: bool,
+  /// Bit n is set if reservation type n is supported
+  pub type_mask: u16,
+}
+
+impl ReservationCapabilities {
+  pub fn from_bytes(buf: &[u8]) -> Option <ReservationCapabilities> {
+    if buf.len() < 8 {
+      return None;
+    }
+
+    Some(ReservationCapabilities {
+      crh: buf[2] & 0x10 == 0x10,
+      sip_c: buf[2] & 0x08 == 0x08,
+      atp_c: buf[2] & 0x04 == 0x04,
+      ptpl_c: buf[2] & 0x01 == 0x01,
+      tmv: buf[3] & 0x80 == 0x80,
+      ptpl_a: buf[3] & 0x01 == 0x01,
+      type_mask: u16::from_le_bytes([buf[4], buf[5]]),
+    })
+  }
+
+  /// Whether a reservation type is supported, None if the device does not
+  /// report a type mask
+  pub fn supports_type(&self, reservation_type: u8) -> Option <bool> {
+    if !self.tmv || reservation_type > 15 {
+      return None;
+    }
+    Some(self.type_mask & (1 << reservation_type) != 0)
+  }
+}
+
+/// SPC-3 Section 6.11.5 full status descriptor, one per registered I_T nexus
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullStatusDescriptor {
+  pub key: u64,
+  pub all_tg_pt: bool,
+  /// Reservation holder
+  pub r_holder: bool,
+  pub scope: u8,
+  pub reservation_type: u8,
+  pub relative_target_port_identifier: u16,
+  pub transport_id: Vec <u8>,
+}
+
+/// SPC-3 Section 6.11.5 READ FULL STATUS parameter data
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservationFullStatus {
+  pub generation: u32,
+  pub descriptors: Vec <FullStatusDescriptor>,
+}
+
+impl ReservationFullStatus {
+  pub fn from_bytes(buf: &[u8]) -> Option <ReservationFullStatus> {
+    let (generation, data) = split_header(buf)?;
+
+    let mut descriptors = Vec::new();
+    let mut offset = 0;
+    while offset + 24 <= data.len() {
+      let t = &data[offset..];
+      let length = u32::from_be_bytes(t[20..24].try_into().unwrap()) as usize;
+      let transport_id = match t.get(24..(24 + length)) {
+        Some(id) => { id.to_vec() },
+        None => { break; },
+      };
+
+      descriptors.push(FullStatusDescriptor {
+        key: u64::from_be_bytes(t[0..8].try_into().unwrap()),
+        all_tg_pt: t[12] & 0x02 == 0x02,
+        r_holder: t[12] & 0x01 == 0x01,
+        scope: t[13] >> 4,
+        reservation_type: t[13] & 0x0F,
+        relative_target_port_identifier: u16::from_be_bytes([t[18], t[19]]),
+        transport_id,
+      });
+      offset += 24 + length;
+    }
+
+    Some(ReservationFullStatus {
+      generation,
+      descriptors,
+    })
+  }
+}
+
+/// PERSISTENT RESERVE IN parameter data, by service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PersistentReserveInOutput {
+  Keys(ReservationKeys),
+  Reservation(ReservationStatus),
+  Capabilities(ReservationCapabilities),
+  FullStatus(ReservationFullStatus),
+}
+
+/// Generation and the additional data, truncated to what was transferred
+fn split_header(buf: &[u8]) -> Option <(u32, &[u8])> {
+  if buf.len() < 8 {
+    return None;
+  }
+
+  let generation = u32::from_be_bytes(buf[0..4].try_into().unwrap());
+  let additional_length = u32::from_be_bytes(buf[4..8].try_into().unwrap()) as usize;
+  Some((generation, &buf[8..buf.len().min(8 + additional_length)]))
+}
+
+/// SPC-3 Section 6.12.3 PERSISTENT RESERVE OUT parameter list, for every
+/// service action except REGISTER AND MOVE
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentReserveOutParameters {
+  pub reservation_key: u64,
+  pub service_action_reservation_key: u64,
+  /// Specify initiator ports, transport_ids is sent when set
+  pub spec_i_pt: bool,
+  pub all_tg_pt: bool,
+  /// Activate persist through power loss
+  pub aptpl: bool,
+  pub transport_ids: Vec <u8>,
+}
+
+impl PersistentReserveOutParameters {
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let mut rv = vec![0; 24];
+    rv[0..8].copy_from_slice(&self.reservation_key.to_be_bytes());
+    rv[8..16].copy_from_slice(&self.service_action_reservation_key.to_be_bytes());
+    rv[20] = if self.spec_i_pt { 0x08 } else { 0x00 };
+    rv[20] |= if self.all_tg_pt { 0x04 } else { 0x00 };
+    rv[20] |= if self.aptpl { 0x01 } else { 0x00 };
+
+    if self.spec_i_pt {
+      rv.extend_from_slice(&(self.transport_ids.len() as u32).to_be_bytes());
+      rv.extend_from_slice(&self.transport_ids);
+    }
+    rv
+  }
+}
+
+/// SPC-3 Section 6.12.4 REGISTER AND MOVE parameter list
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegisterAndMoveParameters {
+  pub reservation_key: u64,
+  pub service_action_reservation_key: u64,
+  /// Unregister the I_T nexus the command is sent through
+  pub unreg: bool,
+  pub aptpl: bool,
+  pub relative_target_port_identifier: u16,
+  pub transport_id: Vec <u8>,
+}
+
+impl RegisterAndMoveParameters {
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let mut rv = vec![0; 24];
+    rv[0..8].copy_from_slice(&self.reservation_key.to_be_bytes());
+    rv[8..16].copy_from_slice(&self.service_action_reservation_key.to_be_bytes());
+    rv[17] = if self.unreg { 0x02 } else { 0x00 };
+    rv[17] |= if self.aptpl { 0x01 } else { 0x00 };
+    rv[18..20].copy_from_slice(&self.relative_target_port_identifier.to_be_bytes());
+    rv[20..24].copy_from_slice(&(self.transport_id.len() as u32).to_be_bytes());
+    rv.extend_from_slice(&self.transport_id);
+    rv
+  }
+}
+
+fn read_persistent_reservations(device: &Device, service_action: u8)
+    -> Result <PersistentReserveInOutput, ReservationError> {
+  let cmd = PersistentReserveIn {
+    service_action,
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_reservation("PERSISTENT RESERVE IN", status)?;
+
+  cmd.parse_buffer(&buf)
+      .ok_or_else(|| ReservationError::Failed("Could not parse PERSISTENT RESERVE IN data".to_string()))
+}
+
+/// Reservation keys registered with the logical unit
+pub fn read_keys(device: &Device) -> Result <ReservationKeys, ReservationError> {
+  match read_persistent_reservations(device, PersistentReserveIn::READ_KEYS)? {
+    PersistentReserveInOutput::Keys(t) => { Ok(t) },
+    t => { Err(ReservationError::Failed(format!("Unexpected output {:?}", t))) },
+  }
+}
+
+/// Persistent reservation currently held on the logical unit
+pub fn read_reservation(device: &Device) -> Result <ReservationStatus, ReservationError> {
+  match read_persistent_reservations(device, PersistentReserveIn::READ_RESERVATION)? {
+    PersistentReserveInOutput::Reservation(t) => { Ok(t) },
+    t => { Err(ReservationError::Failed(format!("Unexpected output {:?}", t))) },
+  }
+}
+
+/// Issue a PERSISTENT RESERVE OUT with a basic parameter list
+pub fn persistent_reserve_out(device: &Device, service_action: u8, reservation_type: u8,
+    parameters: &PersistentReserveOutParameters) -> Result <DeviceStatus, ReservationError> {
+  let buf = parameters.to_bytes();
+  let cmd = PersistentReserveOut {
+    service_action,
+    scope: PersistentReserveOut::LU_SCOPE,
+    reservation_type,
+    parameter_list_length: buf.len() as u32,
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, &buf)?;
+  check_reservation("PERSISTENT RESERVE OUT", status)
+}
+
+/// Register `key` for this I_T nexus, replacing any key already registered
+pub fn register(device: &Device, key: u64) -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::REGISTER_AND_IGNORE_EXISTING_KEY, 0,
+      &PersistentReserveOutParameters {
+    service_action_reservation_key: key,
+    ..Default::default()
+  })
+}
+
+/// Register `key` for this I_T nexus with plain REGISTER. Fails with
+/// [`ReservationError::ReservationConflict`] if the I_T nexus already has a
+/// registration, which is left as it is.
+pub fn register_new(device: &Device, key: u64) -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::REGISTER, 0,
+      &PersistentReserveOutParameters {
+    service_action_reservation_key: key,
+    ..Default::default()
+  })
+}
+
+/// Remove the registration of `key`, releasing its reservation if held
+pub fn unregister(device: &Device, key: u64) -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::REGISTER, 0,
+      &PersistentReserveOutParameters {
+    reservation_key: key,
+    ..Default::default()
+  })
+}
+
+pub fn reserve(device: &Device, key: u64, reservation_type: u8)
+    -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::RESERVE, reservation_type,
+      &PersistentReserveOutParameters {
+    reservation_key: key,
+    ..Default::default()
+  })
+}
+
+pub fn release(device: &Device, key: u64, reservation_type: u8)
+    -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::RELEASE, reservation_type,
+      &PersistentReserveOutParameters {
+    reservation_key: key,
+    ..Default::default()
+  })
+}
+
+/// Remove every registration and reservation on the logical unit
+pub fn clear(device: &Device, key: u64) -> Result <DeviceStatus, ReservationError> {
+  persistent_reserve_out(device, PersistentReserveOut::CLEAR, 0,
+      &PersistentReserveOutParameters {
+    reservation_key: key,
+    ..Default::default()
+  })
+}
+
+/// Remove the registrations of `victim_key` and take over its reservation.
+/// With `abort`, tasks from the preempted I_T nexuses are aborted too.
+pub fn preempt(device: &Device, key: u64, victim_key: u64, reservation_type: u8, abort: bool)
+    -> Result <DeviceStatus, ReservationError> {
+  let service_action = if abort {
+    PersistentReserveOut::PREEMPT_AND_ABORT
+  } else {
+    PersistentReserveOut::PREEMPT
+  };
+
+  persistent_reserve_out(device, service_action, reservation_type,
+      &PersistentReserveOutParameters {
+    reservation_key: key,
+    service_action_reservation_key: victim_key,
+    ..Default::default()
+  })
+}
+
+/// Move the reservation held by `key` to the I_T nexus given by
+/// `parameters.transport_id`
+pub fn register_and_move(device: &Device, reservation_type: u8,
+    parameters: &RegisterAndMoveParameters) -> Result <DeviceStatus, ReservationError> {
+  let buf = parameters.to_bytes();
+  let cmd = PersistentReserveOut {
+    service_action: PersistentReserveOut::REGISTER_AND_MOVE,
+    scope: PersistentReserveOut::LU_SCOPE,
+    reservation_type,
+    parameter_list_length: buf.len() as u32,
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, &buf)?;
+  check_reservation("PERSISTENT RESERVE OUT", status)
+}
+
+#[test]
+fn persistent_reservation_test() {
+  let cmd = PersistentReserveIn {
+    service_action: PersistentReserveIn::READ_RESERVATION,
+    allocation_length: 0xFFFF,
+  };
+  let buf = [
+    0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x10,
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00,
+  ];
+  match cmd.parse_buffer(&buf) {
+    Some(PersistentReserveInOutput::Reservation(t)) => {
+      assert_eq!(t.generation, 5);
+      assert_eq!(t.reservation, Some(Reservation {
+        key: 0x123456789ABCDEF0,
+        scope: 0,
+        reservation_type: EXCLUSIVE_ACCESS,
+      }));
+    },
+    t => { panic!("Parsed as {:?}", t); },
+  }
+
+  let keys = ReservationKeys::from_bytes(&[
+    0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10,
+    0, 0, 0, 0, 0, 0, 0, 1,
+    0, 0, 0, 0, 0, 0, 0, 2,
+  ]).unwrap();
+  assert_eq!(keys.keys, vec![1, 2]);
+
+  let capabilities = ReservationCapabilities::from_bytes(&[
+    0x00, 0x08, 0x11, 0x80, 0x0A, 0x00, 0x00, 0x00,
+  ]).unwrap();
+  assert!(capabilities.crh);
+  assert_eq!(capabilities.supports_type(EXCLUSIVE_ACCESS), Some(true));
+  assert_eq!(capabilities.supports_type(EXCLUSIVE_ACCESS_ALL_REGISTRANTS), Some(false));
+
+  let parameters = PersistentReserveOutParameters {
+    reservation_key: 1,
+    service_action_reservation_key: 2,
+    aptpl: true,
+    ..Default::default()
+  };
+  assert_eq!(parameters.to_bytes(), vec![
+    0, 0, 0, 0, 0, 0, 0, 1,
+    0, 0, 0, 0, 0, 0, 0, 2,
+    0, 0, 0, 0, 0x01, 0, 0, 0,
+  ]);
+}
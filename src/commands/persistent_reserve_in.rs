@@ -0,0 +1,74 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::persistent_reservation::{*};
+
+/// SPC-3 Section 6.11
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentReserveIn {
+  pub service_action: u8,
+  pub allocation_length: u16,
+}
+
+impl PersistentReserveIn {
+  const OP_CODE: u8 = 0x5E;
+  pub const READ_KEYS: u8 = 0x00;
+  pub const READ_RESERVATION: u8 = 0x01;
+  pub const REPORT_CAPABILITIES: u8 = 0x02;
+  pub const READ_FULL_STATUS: u8 = 0x03;
+
+  pub fn new() -> PersistentReserveIn {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <PersistentReserveIn> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(PersistentReserveIn {
+      service_action: buf[1] & 0x1F,
+      allocation_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <PersistentReserveInOutput> {
+    match self.service_action {
+      Self::READ_KEYS => {
+        ReservationKeys::from_bytes(buf).map(PersistentReserveInOutput::Keys)
+      },
+      Self::READ_RESERVATION => {
+        ReservationStatus::from_bytes(buf).map(PersistentReserveInOutput::Reservation)
+      },
+      Self::REPORT_CAPABILITIES => {
+        ReservationCapabilities::from_bytes(buf).map(PersistentReserveInOutput::Capabilities)
+      },
+      Self::READ_FULL_STATUS => {
+        ReservationFullStatus::from_bytes(buf).map(PersistentReserveInOutput::FullStatus)
+      },
+      _ => { None },
+    }
+  }
+}
+
+impl crate::Output for PersistentReserveIn {
+}
+
+impl crate::Command for PersistentReserveIn {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.service_action & 0x1F;
+    data[7..9].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
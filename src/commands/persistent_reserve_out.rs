@@ -0,0 +1,71 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-3 Section 6.12
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentReserveOut {
+  pub service_action: u8,
+  pub scope: u8,
+  pub reservation_type: u8,
+  pub parameter_list_length: u32,
+}
+
+impl PersistentReserveOut {
+  const OP_CODE: u8 = 0x5F;
+  pub const REGISTER: u8 = 0x00;
+  pub const RESERVE: u8 = 0x01;
+  pub const RELEASE: u8 = 0x02;
+  pub const CLEAR: u8 = 0x03;
+  pub const PREEMPT: u8 = 0x04;
+  pub const PREEMPT_AND_ABORT: u8 = 0x05;
+  pub const REGISTER_AND_IGNORE_EXISTING_KEY: u8 = 0x06;
+  pub const REGISTER_AND_MOVE: u8 = 0x07;
+
+  pub const LU_SCOPE: u8 = 0x0;
+
+  pub fn new() -> PersistentReserveOut {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <PersistentReserveOut> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(PersistentReserveOut {
+      service_action: buf[1] & 0x1F,
+      scope: buf[2] >> 4,
+      reservation_type: buf[2] & 0x0F,
+      parameter_list_length: u32::from_be_bytes(buf[5..9].try_into().unwrap()),
+    })
+  }
+}
+
+impl crate::Input for PersistentReserveOut {
+}
+
+impl crate::Command for PersistentReserveOut {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    if self.service_action > Self::REGISTER_AND_MOVE {
+      return Err("Invalid service action");
+    }
+
+    let mut data = vec![0; 10];
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.service_action;
+    data[2] = (self.scope << 4) | (self.reservation_type & 0x0F);
+    data[5..9].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
 
 /// SSC-4 Section 7.5
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PreventAllowMediumRemoval {
   pub prevent: u8,
 }
@@ -14,12 +16,25 @@ impl PreventAllowMediumRemoval {
   pub fn new() -> PreventAllowMediumRemoval {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <PreventAllowMediumRemoval> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(PreventAllowMediumRemoval {
+      prevent: buf[4] & 0x3,
+    })
+  }
 }
 
 impl crate::NoIO for PreventAllowMediumRemoval {
 }
 
 impl crate::Command for PreventAllowMediumRemoval {
+  type SenseInfo = ParameterSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -33,7 +48,7 @@ impl crate::Command for PreventAllowMediumRemoval {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
   }
 }
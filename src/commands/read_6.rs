@@ -1,8 +1,10 @@
 use crate::sense::Sense;
+use crate::commands::ReadSenseInfo;
 
 /// SSC-4 Section 6.4
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Read6 {
   pub sili: bool,
   pub fixed: bool,
@@ -15,12 +17,27 @@ impl Read6 {
   pub fn new() -> Read6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Read6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Read6 {
+      sili: buf[1] & 0x2 == 0x2,
+      fixed: buf[1] & 0x1 == 0x1,
+      transfer_length: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::Output for Read6 {
 }
 
 impl crate::Command for Read6 {
+  type SenseInfo = ReadSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -37,19 +54,8 @@ impl crate::Command for Read6 {
     Ok(data)
   }
 
-  //TODO Might be a better way to send this to a Formatter so it doesn't have to make a String.
-  //  Can't just impl fmt because we need the Sense.
-  //  One way might be to attach a Sense ref to the instance then fmt() could look at it.
-  //  Remove it after fmt().
-  fn parse_sense(&self, sense: &Sense) -> String {
-    //TODO Check the conditions that should be set
-    //  Also this is just a quick example. Might need to be i64?
-    format!(concat!(
-        "== Read6 Specific ==\n",
-        "Requested transfer length minus the actual block length is {}"
-        ),
-        u64::from_be_bytes(sense.information)
-    )
+  fn parse_sense(&self, sense: &Sense) -> ReadSenseInfo {
+    ReadSenseInfo::from_sense(sense, self.fixed, self.transfer_length)
   }
 }
 
@@ -83,13 +89,13 @@ use crate::device::Device;
 use crate::device::DeviceStatus;
 
 #[no_mangle]
-pub extern fn device_issue_read6(
+pub extern "C" fn device_issue_read6(
     device: *mut Device, cdb: *const Read6, buf: *mut u8, size: usize) -> *mut DeviceStatus {
   let device = unsafe { &mut *device };
   let cdb = unsafe { &*cdb };
-  let mut buf = unsafe { std::slice::from_raw_parts_mut(buf, size) };
+  let buf = unsafe { std::slice::from_raw_parts_mut(buf, size) };
 
-  let result = device.issue_cmd_with_output(cdb, &mut buf);
+  let result = device.issue_cmd_with_output(cdb, buf);
   match result {
     Ok(rv) => {
       let boxed_rv: Box <DeviceStatus> = Box::new(rv);
@@ -105,10 +111,10 @@ pub extern fn device_issue_read6(
 use crate::Command;
 
 #[no_mangle]
-pub extern fn read_6_status_to_stdout(cmd: *const Read6, status: *mut DeviceStatus) {
+pub extern "C" fn read_6_status_to_stdout(cmd: *const Read6, status: *mut DeviceStatus) {
   let cmd = unsafe { &*cmd };
   let status = unsafe { &mut *status };
   if let Some(sense) = &status.sense {
-    println!("{}", cmd.parse_sense(sense));
+    print!("{}", cmd.parse_sense(sense));
   }
 }
@@ -0,0 +1,87 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::mam::{*};
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.16
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadAttribute {
+  pub service_action: u8,
+  pub logical_volume_number: u8,
+  pub partition_number: u8,
+  pub first_attribute_identifier: u16,
+  pub allocation_length: u32,
+  /// Return attributes from the device server cache
+  pub cache: bool,
+}
+
+impl ReadAttribute {
+  const OP_CODE: u8 = 0x8C;
+  pub const ATTRIBUTE_VALUES: u8 = 0x00;
+  pub const ATTRIBUTE_LIST: u8 = 0x01;
+  pub const LOGICAL_VOLUME_LIST: u8 = 0x02;
+  pub const PARTITION_LIST: u8 = 0x03;
+  pub const SUPPORTED_ATTRIBUTES: u8 = 0x05;
+
+  pub fn new() -> ReadAttribute {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadAttribute> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadAttribute {
+      service_action: buf[1] & 0x1F,
+      logical_volume_number: buf[5],
+      partition_number: buf[7],
+      first_attribute_identifier: u16::from_be_bytes([buf[8], buf[9]]),
+      allocation_length: u32::from_be_bytes(buf[10..14].try_into().unwrap()),
+      cache: buf[14] & 0x01 == 0x01,
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <ReadAttributeOutput> {
+    match self.service_action {
+      Self::ATTRIBUTE_VALUES => { parse_values(buf).map(ReadAttributeOutput::Values) },
+      Self::ATTRIBUTE_LIST | Self::SUPPORTED_ATTRIBUTES => {
+        parse_attribute_list(buf).map(ReadAttributeOutput::AttributeList)
+      },
+      Self::LOGICAL_VOLUME_LIST if buf.len() >= 4 => {
+        Some(ReadAttributeOutput::VolumeList { first: buf[2], count: buf[3] })
+      },
+      Self::PARTITION_LIST if buf.len() >= 4 => {
+        Some(ReadAttributeOutput::PartitionList { first: buf[2], count: buf[3] })
+      },
+      _ => { None },
+    }
+  }
+}
+
+impl crate::Output for ReadAttribute {
+}
+
+impl crate::Command for ReadAttribute {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 16];
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.service_action & 0x1F;
+    data[5] = self.logical_volume_number;
+    data[7] = self.partition_number;
+    data[8..10].copy_from_slice(&self.first_attribute_identifier.to_be_bytes());
+    data[10..14].copy_from_slice(&self.allocation_length.to_be_bytes());
+    data[14] = if self.cache { 0x01 } else { 0x00 };
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
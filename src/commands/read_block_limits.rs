@@ -1,19 +1,23 @@
 use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
 use core::convert::TryInto;
 
 /// SSC-4 Section 7.6
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadBlockLimits {
   pub mloi: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadBlockLimitsOutput {
   BlockLimits(ReadBlockLimitsOutputRange),
   MaximumLogicalObjectIdentifier(u64),
 }
 
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadBlockLimitsOutputRange {
   pub granularity: u8,
   pub maximum_block_length_limit: u32,
@@ -29,6 +33,17 @@ impl ReadBlockLimits {
     Default::default()
   }
 
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadBlockLimits> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadBlockLimits {
+      mloi: buf[1] & 0x1 == 0x1,
+    })
+  }
+
   pub fn output_len(&self) -> usize {
     if self.mloi { Self::MLOI_SIZE } else { Self::BLOCK_LIMITS_SIZE }
   }
@@ -60,6 +75,8 @@ impl crate::Output for ReadBlockLimits {
 }
 
 impl crate::Command for ReadBlockLimits {
+  type SenseInfo = ParameterSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -69,7 +86,7 @@ impl crate::Command for ReadBlockLimits {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
   }
 }
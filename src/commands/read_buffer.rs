@@ -0,0 +1,90 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::firmware::{*};
+
+/// SPC-4 Section 6.17
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadBuffer {
+  pub mode: u8,
+  pub buffer_id: u8,
+  pub buffer_offset: u32,
+  pub allocation_length: u32,
+}
+
+impl ReadBuffer {
+  const OP_CODE: u8 = 0x3C;
+  pub const COMBINED_HEADER_AND_DATA: u8 = 0x00;
+  pub const VENDOR_SPECIFIC: u8 = 0x01;
+  pub const DATA: u8 = 0x02;
+  pub const DESCRIPTOR: u8 = 0x03;
+  pub const ECHO_BUFFER: u8 = 0x0A;
+  pub const ECHO_BUFFER_DESCRIPTOR: u8 = 0x0B;
+  pub const ERROR_HISTORY: u8 = 0x1C;
+
+  pub fn new() -> ReadBuffer {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadBuffer> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadBuffer {
+      mode: buf[1] & 0x1F,
+      buffer_id: buf[2],
+      buffer_offset: u32::from_be_bytes([0, buf[3], buf[4], buf[5]]),
+      allocation_length: u32::from_be_bytes([0, buf[6], buf[7], buf[8]]),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <ReadBufferOutput> {
+    match self.mode {
+      Self::DESCRIPTOR => {
+        BufferDescriptor::from_bytes(buf).map(ReadBufferOutput::Descriptor)
+      },
+      Self::ECHO_BUFFER_DESCRIPTOR => {
+        EchoBufferDescriptor::from_bytes(buf).map(ReadBufferOutput::EchoBufferDescriptor)
+      },
+      Self::ERROR_HISTORY if self.buffer_id <= ERROR_HISTORY_DIRECTORY_NEW_I_T_NEXUS_AND_SNAPSHOT => {
+        ErrorHistoryDirectory::from_bytes(buf).map(ReadBufferOutput::ErrorHistoryDirectory)
+      },
+      _ => { None },
+    }
+  }
+}
+
+impl crate::Output for ReadBuffer {
+}
+
+impl crate::Command for ReadBuffer {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    if self.mode > 0x1F {
+      return Err("Mode must be in the range 0..32");
+    }
+    if !(0..(2_u32.pow(24))).contains(&self.buffer_offset) {
+      return Err("Buffer offset must be in the range 0..2^24");
+    }
+    if !(0..(2_u32.pow(24))).contains(&self.allocation_length) {
+      return Err("Allocation length must be in the range 0..2^24");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.mode;
+    data[2] = self.buffer_id;
+    data[3..6].copy_from_slice(&self.buffer_offset.to_be_bytes()[1..]);
+    data[6..9].copy_from_slice(&self.allocation_length.to_be_bytes()[1..]);
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,63 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.18
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadMediaSerialNumber {
+  pub allocation_length: u32,
+}
+
+impl ReadMediaSerialNumber {
+  const OP_CODE: u8 = 0xAB;
+  const SERVICE_ACTION: u8 = 0x01;
+
+  pub fn new() -> ReadMediaSerialNumber {
+    ReadMediaSerialNumber {
+      allocation_length: 0x200,
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadMediaSerialNumber> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(ReadMediaSerialNumber {
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  /// The vendor specific serial number, empty if the medium has none
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <Vec <u8>> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let length = u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize;
+    Some(buf[4..buf.len().min(4 + length)].to_vec())
+  }
+}
+
+impl crate::Output for ReadMediaSerialNumber {
+}
+
+impl crate::Command for ReadMediaSerialNumber {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -1,9 +1,11 @@
 use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
 use std::convert::TryInto;
 //use std::fmt;
 
 /// SSC-4 Section 7.7
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadPosition {
   pub service_action: u8,
   pub allocation_length: u16,
@@ -23,6 +25,18 @@ impl ReadPosition {
     Default::default()
   }
 
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadPosition> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadPosition {
+      service_action: buf[1] & 0x1F,
+      allocation_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+
   pub fn output_len(&self) -> usize {
     match self.service_action {
       ReadPosition::SHORT_FORM_BLOCK | ReadPosition::SHORT_FORM_VENDOR => {
@@ -37,7 +51,7 @@ impl ReadPosition {
   pub fn parse_buffer(&self, buf: &[u8]) -> Option <ReadPositionOutput> {
     match self.service_action {
       ReadPosition::SHORT_FORM_BLOCK | ReadPosition::SHORT_FORM_VENDOR => {
-        if buf.len() < ReadPosition::SHORT_FORM_LEN.into() {
+        if buf.len() < ReadPosition::SHORT_FORM_LEN {
           return None;
         }
 
@@ -55,13 +69,13 @@ impl ReadPosition {
           last_logical_object_location:
               u32::from_be_bytes(buf[8..12].try_into().unwrap()),
           number_of_logical_objects_in_object_buffer:
-              u32::from_be_bytes([0, buf[13], buf[14], buf[15]].try_into().unwrap()),
+              u32::from_be_bytes([0, buf[13], buf[14], buf[15]]),
           number_of_bytes_in_object_buffer:
               u32::from_be_bytes(buf[16..20].try_into().unwrap()),
         }))
       },
       ReadPosition::LONG_FORM => {
-        if buf.len() < ReadPosition::LONG_FORM_LEN.into() {
+        if buf.len() < ReadPosition::LONG_FORM_LEN {
           return None;
         }
 
@@ -81,7 +95,7 @@ impl ReadPosition {
         }))
       },
       ReadPosition::EXTENDED_FORM => {
-        if buf.len() < ReadPosition::EXTENDED_FORM_LEN.into() {
+        if buf.len() < ReadPosition::EXTENDED_FORM_LEN {
           return None;
         }
 
@@ -95,7 +109,7 @@ impl ReadPosition {
           bpew: buf[0] & 0x01 == 0x01,
           partition_number: buf[1],
           number_of_logical_objects_in_object_buffer:
-              u32::from_be_bytes([0, buf[5], buf[6], buf[7]].try_into().unwrap()),
+              u32::from_be_bytes([0, buf[5], buf[6], buf[7]]),
           first_logical_object_location:
               u64::from_be_bytes(buf[8..16].try_into().unwrap()),
           last_logical_object_location:
@@ -115,6 +129,8 @@ impl crate::Output for ReadPosition {
 }
 
 impl crate::Command for ReadPosition {
+  type SenseInfo = ParameterSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 10];
 
@@ -128,12 +144,13 @@ impl crate::Command for ReadPosition {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String { 
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
   }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadPositionOutput {
   ShortForm(ReadPositionOutputShortForm),
   LongForm(ReadPositionOutputLongForm),
@@ -159,6 +176,7 @@ impl fmt::Display for ReadPositionOutput {
 */
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadPositionOutputShortForm {
   pub bop: bool,
   pub eop: bool,
@@ -183,6 +201,7 @@ impl fmt::Display for ReadPositionOutputShortForm {
 */
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadPositionOutputLongForm {
   pub bop: bool,
   pub eop: bool,
@@ -204,6 +223,7 @@ impl fmt::Display for ReadPositionOutputLongForm {
 */
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadPositionOutputExtendedForm {
   pub bop: bool,
   pub eop: bool,
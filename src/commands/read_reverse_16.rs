@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::ReadSenseInfo;
 
 /// SSC-4 Section 5.4
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadReverse16 {
   pub bytord: bool,
   pub sili: bool,
@@ -17,12 +20,30 @@ impl ReadReverse16 {
   pub fn new() -> ReadReverse16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadReverse16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadReverse16 {
+      bytord: buf[1] & 0x4 == 0x4,
+      sili: buf[1] & 0x2 == 0x2,
+      fixed: buf[1] & 0x1 == 0x1,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      transfer_length: u32::from_be_bytes([0, buf[12], buf[13], buf[14]]),
+    })
+  }
 }
 
 impl crate::Output for ReadReverse16 {
 }
 
 impl crate::Command for ReadReverse16 {
+  type SenseInfo = ReadSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -42,7 +63,7 @@ impl crate::Command for ReadReverse16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ReadSenseInfo {
+    ReadSenseInfo::from_sense(sense, self.fixed, self.transfer_length)
   }
 }
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::ReadSenseInfo;
 
 /// SSC-4 Section 6.5
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadReverse6 {
   pub bytord: bool,
   pub sili: bool,
@@ -15,12 +17,28 @@ impl ReadReverse6 {
   pub fn new() -> ReadReverse6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReadReverse6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReadReverse6 {
+      bytord: buf[1] & 0x4 == 0x4,
+      sili: buf[1] & 0x2 == 0x2,
+      fixed: buf[1] & 0x1 == 0x1,
+      transfer_length: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::Output for ReadReverse6 {
 }
 
 impl crate::Command for ReadReverse6 {
+  type SenseInfo = ReadSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -38,7 +56,7 @@ impl crate::Command for ReadReverse6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ReadSenseInfo {
+    ReadSenseInfo::from_sense(sense, self.fixed, self.transfer_length)
   }
 }
@@ -0,0 +1,65 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::diagnostic::DiagnosticPage;
+
+/// SPC-4 Section 6.27
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceiveDiagnosticResults {
+  /// Page code valid, otherwise the results of the last SEND DIAGNOSTIC
+  /// are returned
+  pub pcv: bool,
+  pub page_code: u8,
+  pub allocation_length: u16,
+}
+
+impl ReceiveDiagnosticResults {
+  const OP_CODE: u8 = 0x1C;
+
+  pub fn new() -> ReceiveDiagnosticResults {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReceiveDiagnosticResults> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReceiveDiagnosticResults {
+      pcv: buf[1] & 0x01 == 0x01,
+      page_code: buf[2],
+      allocation_length: u16::from_be_bytes([buf[3], buf[4]]),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <DiagnosticPage> {
+    DiagnosticPage::from_bytes(buf)
+  }
+}
+
+impl crate::Output for ReceiveDiagnosticResults {
+}
+
+impl crate::Command for ReceiveDiagnosticResults {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    if !self.pcv && self.page_code != 0 {
+      return Err("Page code must be 0 when PCV is not set");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.pcv { 0x01 } else { 0x00 };
+    data[2] = self.page_code;
+    data[3..5].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
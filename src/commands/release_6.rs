@@ -0,0 +1,56 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-2 Section 7.17
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Release6 {
+}
+
+impl Release6 {
+  const OP_CODE: u8 = 0x17;
+
+  pub fn new() -> Release6 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Release6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Release6 {
+    })
+  }
+}
+
+impl crate::NoIO for Release6 {
+}
+
+impl crate::Command for Release6 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    data[0] = Self::OP_CODE;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
+
+#[test]
+fn release_6_test() {
+  use crate::Command;
+
+  let buf = Release6::new().to_bytes().unwrap();
+  assert_eq!(buf, vec![0x17, 0x00, 0x00, 0x00, 0x00, 0x00]);
+  assert!(Release6::from_bytes(&buf).is_some());
+  assert!(Release6::from_bytes(&buf[..5]).is_none());
+  assert!(Release6::from_bytes(&[0x00; 6]).is_none());
+}
@@ -1,8 +1,10 @@
 use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
 use std::convert::TryInto;
 
 /// SSC-4 Section 7.9
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReportDensitySupport {
   pub medium_type: bool,
   pub media: bool,
@@ -10,12 +12,14 @@ pub struct ReportDensitySupport {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReportDensitySupportOutput {
   Density(ReportDensitySupportOutputDensity),
   Medium(ReportDensitySupportOutputMedium),
 }
 
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReportDensitySupportOutputDensity {
   pub primary_density_code: u8,
   pub secondary_density_code: u8,
@@ -34,6 +38,7 @@ pub struct ReportDensitySupportOutputDensity {
 }
 
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReportDensitySupportOutputMedium {
   pub medium_type: u8,
   pub primary_density_codes: Vec <u8>,
@@ -53,6 +58,19 @@ impl ReportDensitySupport {
     Default::default()
   }
 
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReportDensitySupport> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReportDensitySupport {
+      medium_type: buf[1] & 0x2 == 0x2,
+      media: buf[1] & 0x1 == 0x1,
+      allocation_length: u16::from_be_bytes([buf[7], buf[8]]),
+    })
+  }
+
   fn parse_density(&self, buf: &[u8]) -> Option <ReportDensitySupportOutputDensity> {
     if buf.len() != Self::DENSITY_SIZE {
       return None;
@@ -126,16 +144,14 @@ impl ReportDensitySupport {
     let mut rv: Vec <ReportDensitySupportOutput> = Vec::new();
     if self.medium_type {
       for i in (4..len).step_by(Self::MEDIUM_SIZE) {
-        match self.parse_medium(&buf[i..(i + Self::MEDIUM_SIZE)]) {
-          Some(thing) => { rv.push(ReportDensitySupportOutput::Medium(thing)); },
-          None => { },
+        if let Some(thing) = self.parse_medium(&buf[i..(i + Self::MEDIUM_SIZE)]) {
+          rv.push(ReportDensitySupportOutput::Medium(thing));
         }
       }
     } else {
       for i in (4..len).step_by(Self::DENSITY_SIZE) {
-        match self.parse_density(&buf[i..(i + Self::DENSITY_SIZE)]) {
-          Some(thing) => { rv.push(ReportDensitySupportOutput::Density(thing)); },
-          None => { },
+        if let Some(thing) = self.parse_density(&buf[i..(i + Self::DENSITY_SIZE)]) {
+          rv.push(ReportDensitySupportOutput::Density(thing));
         }
       }
     }
@@ -148,6 +164,8 @@ impl crate::Output for ReportDensitySupport {
 }
 
 impl crate::Command for ReportDensitySupport {
+  type SenseInfo = ParameterSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 10];
 
@@ -160,7 +178,7 @@ impl crate::Command for ReportDensitySupport {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
   }
 }
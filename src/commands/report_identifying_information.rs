@@ -0,0 +1,70 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::identifying_information::{*};
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.26
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportIdentifyingInformation {
+  pub information_type: u8,
+  pub allocation_length: u32,
+}
+
+impl ReportIdentifyingInformation {
+  const OP_CODE: u8 = 0xA3;
+  const SERVICE_ACTION: u8 = 0x05;
+
+  pub fn new() -> ReportIdentifyingInformation {
+    ReportIdentifyingInformation {
+      allocation_length: 4 + MAX_INFORMATION_LENGTH,
+      ..Default::default()
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReportIdentifyingInformation> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(ReportIdentifyingInformation {
+      information_type: buf[10] >> 1,
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <IdentifyingInformationOutput> {
+    if self.information_type == SUPPORTED_INFORMATION_TYPES {
+      parse_supported(buf).map(IdentifyingInformationOutput::Supported)
+    } else {
+      parse_information(buf).map(IdentifyingInformationOutput::Information)
+    }
+  }
+}
+
+impl crate::Output for ReportIdentifyingInformation {
+}
+
+impl crate::Command for ReportIdentifyingInformation {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    if self.information_type > 0x7F {
+      return Err("Information type must be in the range 0..128");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+    data[10] = self.information_type << 1;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,80 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::lun::Lun;
+use std::convert::TryInto;
+
+/// SPC-3 Section 6.21
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportLuns {
+  pub select_report: u8,
+  pub allocation_length: u32,
+}
+
+impl ReportLuns {
+  const OP_CODE: u8 = 0xA0;
+  /// Logical units with a LUN that is not a well known logical unit
+  pub const SELECT_LOGICAL_UNITS: u8 = 0x00;
+  pub const SELECT_WELL_KNOWN: u8 = 0x01;
+  pub const SELECT_ALL: u8 = 0x02;
+  /// Room for the header and 255 LUNs
+  pub const DEFAULT_LEN: u32 = 8 + 8 * 255;
+
+  pub fn new() -> ReportLuns {
+    ReportLuns {
+      allocation_length: Self::DEFAULT_LEN,
+      ..Default::default()
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReportLuns> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(ReportLuns {
+      select_report: buf[2],
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  /// LUN list from the parameter data. If the list was truncated by the
+  /// allocation length, only the LUNs that fit are returned.
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <Vec <Lun>> {
+    if buf.len() < 8 {
+      return None;
+    }
+
+    let lun_list_length = u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize;
+    let end = buf.len().min(8 + lun_list_length);
+
+    Some(buf[8..end].chunks_exact(8).filter_map(Lun::from_bytes).collect())
+  }
+
+  /// Length of the full LUN list, to retry with a larger allocation length
+  pub fn required_len(buf: &[u8]) -> Option <usize> {
+    Some(8 + u32::from_be_bytes(buf.get(0..4)?.try_into().unwrap()) as usize)
+  }
+}
+
+impl crate::Output for ReportLuns {
+}
+
+impl crate::Command for ReportLuns {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[2] = self.select_report;
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,322 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+use std::fmt;
+use std::time::Duration;
+
+/// SPC-3 Section 6.23
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportSupportedOperationCodes {
+  /// Return command timeouts descriptors
+  pub rctd: bool,
+  pub reporting_options: u8,
+  pub requested_operation_code: u8,
+  pub requested_service_action: u16,
+  pub allocation_length: u32,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReportSupportedOperationCodesOutput {
+  AllCommands(Vec <CommandDescriptor>),
+  OneCommand(OneCommandDescriptor),
+}
+
+/// SPC-3 Section 6.23.3 command timeouts descriptor. Timeouts are in
+/// seconds, zero when not specified.
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandTimeoutsDescriptor {
+  pub command_specific: u8,
+  pub nominal_command_processing_timeout: u32,
+  pub recommended_command_timeout: u32,
+}
+
+impl CommandTimeoutsDescriptor {
+  const SIZE: usize = 12;
+
+  fn from_bytes(buf: &[u8]) -> Option <CommandTimeoutsDescriptor> {
+    if buf.len() < Self::SIZE {
+      return None;
+    }
+
+    Some(CommandTimeoutsDescriptor {
+      command_specific: buf[3],
+      nominal_command_processing_timeout: u32::from_be_bytes(buf[4..8].try_into().unwrap()),
+      recommended_command_timeout: u32::from_be_bytes(buf[8..12].try_into().unwrap()),
+    })
+  }
+
+  /// Recommended command timeout, None if the device left it unspecified
+  pub fn recommended(&self) -> Option <Duration> {
+    match self.recommended_command_timeout {
+      0 => { None },
+      t => { Some(Duration::from_secs(t.into())) },
+    }
+  }
+}
+
+/// SPC-3 Section 6.23.2 command descriptor, returned when all commands are
+/// reported
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandDescriptor {
+  pub operation_code: u8,
+  pub service_action: u16,
+  /// SERVACTV, service_action is valid
+  pub service_action_valid: bool,
+  pub cdb_length: u16,
+  pub timeouts: Option <CommandTimeoutsDescriptor>,
+}
+
+impl CommandDescriptor {
+  /// Whether this descriptor describes the command in `cdb`. The service
+  /// action is taken from byte 1 as used by every service action command
+  /// except the variable length CDBs.
+  pub fn matches(&self, cdb: &[u8]) -> bool {
+    match cdb.first() {
+      Some(t) if *t == self.operation_code => {},
+      _ => { return false; },
+    }
+
+    !self.service_action_valid ||
+        cdb.get(1).is_some_and(|t| u16::from(t & 0x1F) == self.service_action)
+  }
+}
+
+/// SPC-3 Section 6.23.2 one command parameter data
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneCommandDescriptor {
+  pub support: u8,
+  /// Bitmap with the same length as the CDB. Each set bit may be set in the
+  /// corresponding CDB bit; the first byte holds the operation code.
+  pub cdb_usage_data: Vec <u8>,
+  pub timeouts: Option <CommandTimeoutsDescriptor>,
+}
+
+impl OneCommandDescriptor {
+  pub const SUPPORT_NOT_AVAILABLE: u8 = 0x0;
+  pub const SUPPORT_NOT_SUPPORTED: u8 = 0x1;
+  pub const SUPPORT_STANDARD: u8 = 0x3;
+  pub const SUPPORT_VENDOR_SPECIFIC: u8 = 0x5;
+
+  pub fn is_supported(&self) -> bool {
+    self.support == Self::SUPPORT_STANDARD || self.support == Self::SUPPORT_VENDOR_SPECIFIC
+  }
+}
+
+impl ReportSupportedOperationCodes {
+  const OP_CODE: u8 = 0xA3;
+  const SERVICE_ACTION: u8 = 0x0C;
+  pub const ALL_COMMANDS: u8 = 0x0;
+  pub const ONE_COMMAND: u8 = 0x1;
+  pub const ONE_COMMAND_SERVICE_ACTION: u8 = 0x2;
+
+  pub fn new() -> ReportSupportedOperationCodes {
+    Default::default()
+  }
+
+  /// Request the descriptors of every supported command
+  pub fn all_commands() -> ReportSupportedOperationCodes {
+    ReportSupportedOperationCodes {
+      rctd: true,
+      reporting_options: Self::ALL_COMMANDS,
+      allocation_length: 0x10000,
+      ..Default::default()
+    }
+  }
+
+  /// Request the CDB usage data of one command
+  pub fn one_command(operation_code: u8, service_action: Option <u16>)
+      -> ReportSupportedOperationCodes {
+    ReportSupportedOperationCodes {
+      rctd: true,
+      reporting_options: if service_action.is_some() {
+        Self::ONE_COMMAND_SERVICE_ACTION
+      } else {
+        Self::ONE_COMMAND
+      },
+      requested_operation_code: operation_code,
+      requested_service_action: service_action.unwrap_or(0),
+      allocation_length: 4 + 16 + CommandTimeoutsDescriptor::SIZE as u32,
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReportSupportedOperationCodes> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(ReportSupportedOperationCodes {
+      rctd: buf[2] & 0x80 == 0x80,
+      reporting_options: buf[2] & 0x07,
+      requested_operation_code: buf[3],
+      requested_service_action: u16::from_be_bytes(buf[4..6].try_into().unwrap()),
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <ReportSupportedOperationCodesOutput> {
+    if self.reporting_options == Self::ALL_COMMANDS {
+      Self::parse_all_commands(buf).map(ReportSupportedOperationCodesOutput::AllCommands)
+    } else {
+      Self::parse_one_command(buf).map(ReportSupportedOperationCodesOutput::OneCommand)
+    }
+  }
+
+  fn parse_all_commands(buf: &[u8]) -> Option <Vec <CommandDescriptor>> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let end = buf.len().min(4 + u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize);
+    let mut rv = Vec::new();
+    let mut offset = 4;
+    while offset + 8 <= end {
+      let t = &buf[offset..];
+      let ctdp = t[5] & 0x02 == 0x02;
+      offset += 8;
+
+      let timeouts = if ctdp {
+        // Stop at a descriptor truncated by the allocation length
+        let timeouts = match CommandTimeoutsDescriptor::from_bytes(&buf[offset..end]) {
+          Some(t) => { t },
+          None => { break; },
+        };
+        offset += CommandTimeoutsDescriptor::SIZE;
+        Some(timeouts)
+      } else {
+        None
+      };
+
+      rv.push(CommandDescriptor {
+        operation_code: t[0],
+        service_action: u16::from_be_bytes(t[2..4].try_into().unwrap()),
+        service_action_valid: t[5] & 0x01 == 0x01,
+        cdb_length: u16::from_be_bytes(t[6..8].try_into().unwrap()),
+        timeouts,
+      });
+    }
+
+    Some(rv)
+  }
+
+  fn parse_one_command(buf: &[u8]) -> Option <OneCommandDescriptor> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let ctdp = buf[1] & 0x80 == 0x80;
+    let cdb_size = u16::from_be_bytes(buf[2..4].try_into().unwrap()) as usize;
+    let cdb_usage_data = buf.get(4..(4 + cdb_size))?.to_vec();
+    let timeouts = if ctdp {
+      Some(CommandTimeoutsDescriptor::from_bytes(&buf[(4 + cdb_size)..])?)
+    } else {
+      None
+    };
+
+    Some(OneCommandDescriptor {
+      support: buf[1] & 0x07,
+      cdb_usage_data,
+      timeouts,
+    })
+  }
+}
+
+impl fmt::Display for ReportSupportedOperationCodesOutput {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ReportSupportedOperationCodesOutput::AllCommands(commands) => {
+        for t in commands {
+          write!(f, "{:02X}h", t.operation_code)?;
+          if t.service_action_valid {
+            write!(f, "/{:02X}h", t.service_action)?;
+          }
+          write!(f, " CDB length {}", t.cdb_length)?;
+          if let Some(timeouts) = &t.timeouts {
+            write!(f, ", nominal {}s, recommended {}s",
+                timeouts.nominal_command_processing_timeout,
+                timeouts.recommended_command_timeout)?;
+          }
+          writeln!(f)?;
+        }
+        Ok(())
+      },
+      ReportSupportedOperationCodesOutput::OneCommand(t) => {
+        writeln!(f, "Support: {}", t.support)?;
+        writeln!(f, "CDB Usage Data: {:02X?}", t.cdb_usage_data)?;
+        if let Some(timeouts) = &t.timeouts {
+          writeln!(f, "Nominal Command Processing Timeout: {}s",
+              timeouts.nominal_command_processing_timeout)?;
+          writeln!(f, "Recommended Command Timeout: {}s", timeouts.recommended_command_timeout)?;
+        }
+        Ok(())
+      },
+    }
+  }
+}
+
+impl crate::Output for ReportSupportedOperationCodes {
+}
+
+impl crate::Command for ReportSupportedOperationCodes {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    if self.reporting_options > Self::ONE_COMMAND_SERVICE_ACTION {
+      return Err("Invalid reporting options");
+    }
+
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[2] = if self.rctd { 0x80 } else { 0x00 } | self.reporting_options;
+    data[3] = self.requested_operation_code;
+    data[4..6].copy_from_slice(&self.requested_service_action.to_be_bytes());
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
+
+#[test]
+fn report_supported_operation_codes_test() {
+  let cmd = ReportSupportedOperationCodes::all_commands();
+  let buf = [
+    0x00, 0x00, 0x00, 0x1C,
+    // LOCATE(16) with timeouts
+    0x92, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x10,
+    0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x02, 0x58, 0x00, 0x00, 0x07, 0x08,
+    // REPORT SUPPORTED OPERATION CODES
+    0xA3, 0x00, 0x00, 0x0C, 0x00, 0x01, 0x00, 0x0C,
+  ];
+  let commands = match cmd.parse_buffer(&buf) {
+    Some(ReportSupportedOperationCodesOutput::AllCommands(t)) => { t },
+    t => { panic!("Parsed as {:?}", t); },
+  };
+  assert_eq!(commands.len(), 2);
+  assert!(commands[0].matches(&[0x92, 0x00]));
+  assert_eq!(commands[0].timeouts.as_ref().unwrap().recommended(),
+      Some(Duration::from_secs(1800)));
+  assert!(commands[1].matches(&[0xA3, 0x0C]));
+  assert!(!commands[1].matches(&[0xA3, 0x0A]));
+
+  let cmd = ReportSupportedOperationCodes::one_command(0x00, None);
+  let buf = [0x00, 0x03, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07];
+  match cmd.parse_buffer(&buf) {
+    Some(ReportSupportedOperationCodesOutput::OneCommand(t)) => {
+      assert!(t.is_supported());
+      assert_eq!(t.cdb_usage_data, vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x07]);
+      assert_eq!(t.timeouts, None);
+    },
+    t => { panic!("Parsed as {:?}", t); },
+  }
+}
@@ -0,0 +1,58 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::timestamp::Timestamp;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.30
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportTimestamp {
+  pub allocation_length: u32,
+}
+
+impl ReportTimestamp {
+  const OP_CODE: u8 = 0xA3;
+  const SERVICE_ACTION: u8 = 0x0F;
+
+  pub fn new() -> ReportTimestamp {
+    ReportTimestamp {
+      allocation_length: Timestamp::LEN as u32,
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <ReportTimestamp> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(ReportTimestamp {
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <Timestamp> {
+    Timestamp::from_bytes(buf)
+  }
+}
+
+impl crate::Output for ReportTimestamp {
+}
+
+impl crate::Command for ReportTimestamp {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,89 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-3 Section 6.27
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequestSense {
+  /// Return descriptor format sense data
+  pub desc: bool,
+  pub allocation_length: u8,
+}
+
+impl RequestSense {
+  const OP_CODE: u8 = 0x03;
+  /// Enough for any fixed or descriptor format sense data
+  pub const MAX_LEN: u8 = 252;
+
+  pub fn new() -> RequestSense {
+    RequestSense {
+      allocation_length: Self::MAX_LEN,
+      ..Default::default()
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <RequestSense> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(RequestSense {
+      desc: buf[1] & 0x1 == 0x1,
+      allocation_length: buf[4],
+    })
+  }
+
+  /// The returned parameter data is ordinary sense data. A response with
+  /// sense key NO SENSE means there is nothing to report.
+  pub fn parse_buffer(&self, buf: &[u8]) -> Option <Sense> {
+    Sense::from_buf(buf)
+  }
+}
+
+impl crate::Output for RequestSense {
+}
+
+impl crate::Command for RequestSense {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.desc { 0x1 } else { 0x0 };
+    data[4] = self.allocation_length;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
+
+#[test]
+fn request_sense_test() {
+  use crate::Command;
+  use crate::sense::{SenseKey, SenseKeySpecific};
+
+  let cmd = RequestSense {
+    desc: true,
+    allocation_length: RequestSense::MAX_LEN,
+  };
+  assert_eq!(cmd.to_bytes().unwrap(), vec![0x03, 0x01, 0x00, 0x00, 0xFC, 0x00]);
+
+  // Descriptor format, NOT READY, becoming ready at 50%
+  let buf = [
+    0x72, 0x02, 0x04, 0x01, 0x00, 0x00, 0x00, 0x08,
+    0x02, 0x06, 0x00, 0x00, 0x80, 0x80, 0x00, 0x00,
+  ];
+  let sense = cmd.parse_buffer(&buf).unwrap();
+  assert!(matches!(sense.sense_key, SenseKey::NotReady));
+  assert_eq!(sense.additional_sense_code, 0x04);
+  assert_eq!(sense.additional_sense_code_qualifier, 0x01);
+  match sense.sense_key_specific {
+    SenseKeySpecific::NotReady(t) => { assert_eq!(t.progress, 0x8000); },
+    t => { panic!("Sense key specific was {:?}", t); },
+  }
+}
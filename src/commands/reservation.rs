@@ -0,0 +1,117 @@
+use crate::commands::Release6;
+use crate::commands::Reserve6;
+use crate::commands::persistent_reservation::{self, check_reservation, ReservationError};
+use crate::device::Device;
+
+/// How a [`ReservationGuard`] reserves the logical unit
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReservationKind {
+  /// SPC-2 RESERVE(6) and RELEASE(6)
+  Legacy,
+  /// Reserve with PERSISTENT RESERVE OUT using `key`. If this I_T nexus is
+  /// not registered yet, `key` is registered first and unregistered again on
+  /// release. An existing registration of `key` is used and kept.
+  Persistent { key: u64, reservation_type: u8 },
+}
+
+/// Map the result of registering the guard's key to whether the guard owns
+/// that registration and must unregister it on release
+fn registered_by_guard <T> (result: Result <T, ReservationError>)
+    -> Result <bool, ReservationError> {
+  match result {
+    Ok(_) => { Ok(true) },
+    //A conflict means this I_T nexus is already registered. If that is with
+    //another key, the reservation that follows fails with a conflict too.
+    Err(ReservationError::ReservationConflict) => { Ok(false) },
+    Err(e) => { Err(e) },
+  }
+}
+
+/// Holds a reservation on a device until it is dropped or released. Created
+/// with [`Device::reserve`].
+pub struct ReservationGuard <'a> {
+  device: &'a Device,
+  kind: ReservationKind,
+  /// Whether the guard registered the key itself
+  registered: bool,
+  released: bool,
+}
+
+impl <'a> ReservationGuard <'a> {
+  pub(crate) fn acquire(device: &'a Device, kind: ReservationKind)
+      -> Result <ReservationGuard <'a>, ReservationError> {
+    let mut registered = false;
+    match &kind {
+      ReservationKind::Legacy => {
+        let status = device.issue_cmd(&Reserve6::new())?;
+        check_reservation("RESERVE(6)", status)?;
+      },
+      ReservationKind::Persistent { key, reservation_type } => {
+        registered = registered_by_guard(persistent_reservation::register_new(device, *key))?;
+        if let Err(e) = persistent_reservation::reserve(device, *key, *reservation_type) {
+          if registered {
+            let _ = persistent_reservation::unregister(device, *key);
+          }
+          return Err(e);
+        }
+      },
+    }
+
+    Ok(ReservationGuard {
+      device,
+      kind,
+      registered,
+      released: false,
+    })
+  }
+
+  pub fn device(&self) -> &'a Device {
+    self.device
+  }
+
+  pub fn kind(&self) -> &ReservationKind {
+    &self.kind
+  }
+
+  /// Release the reservation, reporting any failure. Dropping the guard
+  /// does the same but ignores failures.
+  pub fn release(mut self) -> Result <(), ReservationError> {
+    self.released = true;
+    self.release_internal()
+  }
+
+  fn release_internal(&self) -> Result <(), ReservationError> {
+    match &self.kind {
+      ReservationKind::Legacy => {
+        let status = self.device.issue_cmd(&Release6::new())?;
+        check_reservation("RELEASE(6)", status)?;
+      },
+      ReservationKind::Persistent { key, reservation_type } => {
+        let released = persistent_reservation::release(self.device, *key, *reservation_type);
+        if self.registered {
+          persistent_reservation::unregister(self.device, *key)?;
+        }
+        released?;
+      },
+    }
+    Ok(())
+  }
+}
+
+impl Drop for ReservationGuard <'_> {
+  fn drop(&mut self) {
+    if !self.released {
+      let _ = self.release_internal();
+    }
+  }
+}
+
+#[test]
+fn registered_by_guard_test() {
+  assert!(matches!(registered_by_guard(Ok(())), Ok(true)));
+  assert!(matches!(registered_by_guard::<()>(Err(ReservationError::ReservationConflict)),
+      Ok(false)));
+  assert!(matches!(registered_by_guard::<()>(Err(ReservationError::Failed("x".to_string()))),
+      Err(ReservationError::Failed(_))));
+}
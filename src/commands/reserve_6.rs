@@ -0,0 +1,57 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-2 Section 7.21. Obsolete in SPC-3 in favour of PERSISTENT RESERVE
+/// OUT, but the only reservation method some older devices support.
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reserve6 {
+}
+
+impl Reserve6 {
+  const OP_CODE: u8 = 0x16;
+
+  pub fn new() -> Reserve6 {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Reserve6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Reserve6 {
+    })
+  }
+}
+
+impl crate::NoIO for Reserve6 {
+}
+
+impl crate::Command for Reserve6 {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    data[0] = Self::OP_CODE;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
+
+#[test]
+fn reserve_6_test() {
+  use crate::Command;
+
+  let buf = Reserve6::new().to_bytes().unwrap();
+  assert_eq!(buf, vec![0x16, 0x00, 0x00, 0x00, 0x00, 0x00]);
+  assert!(Reserve6::from_bytes(&buf).is_some());
+  assert!(Reserve6::from_bytes(&buf[..5]).is_none());
+  assert!(Reserve6::from_bytes(&[0x00; 6]).is_none());
+}
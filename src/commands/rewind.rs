@@ -1,33 +1,50 @@
 use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 7.10
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rewind {
   pub immed: bool,
 }
 
 impl Rewind {
+  const OP_CODE: u8 = 0x01;
+
   pub fn new() -> Rewind {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Rewind> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Rewind {
+      immed: buf[1] & 0x1 == 0x1,
+    })
+  }
 }
 
 impl crate::NoIO for Rewind {
 }
 
 impl crate::Command for Rewind {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
-    data[0] = 0x01;
+    data[0] = Self::OP_CODE;
     data[1] = if self.immed { 0x1 } else { 0x0 };
 
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    String::from("")
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
 
@@ -38,7 +55,7 @@ use crate::device::Device;
 use crate::device::DeviceStatus;
 
 #[no_mangle]
-pub extern fn device_issue_rewind(
+pub extern "C" fn device_issue_rewind(
     device: *mut Device, cdb: *const Rewind) -> *mut DeviceStatus {
   let device = unsafe { &mut *device };
   let cdb = unsafe { &*cdb };
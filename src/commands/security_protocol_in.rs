@@ -0,0 +1,73 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.30
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityProtocolIn {
+  pub security_protocol: u8,
+  pub security_protocol_specific: u16,
+  /// Allocation length is in 512 byte increments
+  pub inc_512: bool,
+  pub allocation_length: u32,
+}
+
+impl SecurityProtocolIn {
+  const OP_CODE: u8 = 0xA2;
+  pub const SECURITY_PROTOCOL_INFORMATION: u8 = 0x00;
+  /// SSC-4 Section 8.5
+  pub const TAPE_DATA_ENCRYPTION: u8 = 0x20;
+
+  pub fn new() -> SecurityProtocolIn {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SecurityProtocolIn> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(SecurityProtocolIn {
+      security_protocol: buf[1],
+      security_protocol_specific: u16::from_be_bytes([buf[2], buf[3]]),
+      inc_512: buf[4] & 0x80 == 0x80,
+      allocation_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+
+  /// Supported security protocol list, returned for security protocol 0x00
+  /// with security protocol specific 0x0000
+  pub fn parse_supported_protocols(buf: &[u8]) -> Option <Vec <u8>> {
+    if buf.len() < 8 {
+      return None;
+    }
+
+    let length = u16::from_be_bytes([buf[6], buf[7]]) as usize;
+    Some(buf[8..buf.len().min(8 + length)].to_vec())
+  }
+}
+
+impl crate::Output for SecurityProtocolIn {
+}
+
+impl crate::Command for SecurityProtocolIn {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.security_protocol;
+    data[2..4].copy_from_slice(&self.security_protocol_specific.to_be_bytes());
+    data[4] = if self.inc_512 { 0x80 } else { 0x00 };
+    data[6..10].copy_from_slice(&self.allocation_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
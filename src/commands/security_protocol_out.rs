@@ -0,0 +1,59 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.31
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityProtocolOut {
+  pub security_protocol: u8,
+  pub security_protocol_specific: u16,
+  /// Transfer length is in 512 byte increments
+  pub inc_512: bool,
+  pub transfer_length: u32,
+}
+
+impl SecurityProtocolOut {
+  const OP_CODE: u8 = 0xB5;
+
+  pub fn new() -> SecurityProtocolOut {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SecurityProtocolOut> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(SecurityProtocolOut {
+      security_protocol: buf[1],
+      security_protocol_specific: u16::from_be_bytes([buf[2], buf[3]]),
+      inc_512: buf[4] & 0x80 == 0x80,
+      transfer_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+}
+
+impl crate::Input for SecurityProtocolOut {
+}
+
+impl crate::Command for SecurityProtocolOut {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.security_protocol;
+    data[2..4].copy_from_slice(&self.security_protocol_specific.to_be_bytes());
+    data[4] = if self.inc_512 { 0x80 } else { 0x00 };
+    data[6..10].copy_from_slice(&self.transfer_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
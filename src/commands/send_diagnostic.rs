@@ -0,0 +1,78 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-4 Section 6.42
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SendDiagnostic {
+  pub self_test_code: u8,
+  /// Page format, the parameter list is a diagnostic page
+  pub pf: bool,
+  /// Run the default self-test, self_test_code must be zero
+  pub self_test: bool,
+  pub dev_off_l: bool,
+  pub unit_off_l: bool,
+  pub parameter_list_length: u16,
+}
+
+impl SendDiagnostic {
+  const OP_CODE: u8 = 0x1D;
+  pub const NO_SELF_TEST: u8 = 0x0;
+  pub const BACKGROUND_SHORT: u8 = 0x1;
+  pub const BACKGROUND_EXTENDED: u8 = 0x2;
+  pub const ABORT_BACKGROUND: u8 = 0x4;
+  pub const FOREGROUND_SHORT: u8 = 0x5;
+  pub const FOREGROUND_EXTENDED: u8 = 0x6;
+
+  pub fn new() -> SendDiagnostic {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SendDiagnostic> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(SendDiagnostic {
+      self_test_code: buf[1] >> 5,
+      pf: buf[1] & 0x10 == 0x10,
+      self_test: buf[1] & 0x04 == 0x04,
+      dev_off_l: buf[1] & 0x02 == 0x02,
+      unit_off_l: buf[1] & 0x01 == 0x01,
+      parameter_list_length: u16::from_be_bytes([buf[3], buf[4]]),
+    })
+  }
+}
+
+impl crate::Input for SendDiagnostic {
+}
+
+impl crate::Command for SendDiagnostic {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    if self.self_test_code > 0x7 {
+      return Err("Self-test code must be in the range 0..8");
+    }
+    if self.self_test && self.self_test_code != Self::NO_SELF_TEST {
+      return Err("Self-test code must be 0 when SELFTEST is set");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.self_test_code << 5
+        | if self.pf         { 0x10 } else { 0x00 }
+        | if self.self_test  { 0x04 } else { 0x00 }
+        | if self.dev_off_l  { 0x02 } else { 0x00 }
+        | if self.unit_off_l { 0x01 } else { 0x00 };
+    data[3..5].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
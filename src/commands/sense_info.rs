@@ -0,0 +1,404 @@
+use crate::sense::{Sense, SenseKey, SenseKeySpecific, Residue};
+use std::convert::TryFrom;
+use std::fmt;
+
+fn asc_ascq(sense: &Sense) -> u16 {
+  (sense.additional_sense_code as u16) << 8 |
+  sense.additional_sense_code_qualifier as u16
+}
+
+/// SSC-4 Section 4.2 position related conditions reported through sense data.
+/// Shared by the per-command interpretations below.
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PositionSense {
+  pub filemark: bool,
+  pub end_of_data: bool,
+  pub beginning_of_partition: bool,
+  pub end_of_partition: bool,
+  pub early_warning: bool,
+  pub programmable_early_warning: bool,
+}
+
+impl PositionSense {
+  pub fn from_sense(sense: &Sense) -> PositionSense {
+    let key = asc_ascq(sense);
+    let no_error = matches!(sense.sense_key,
+        SenseKey::NoSense | SenseKey::RecoveredError);
+
+    PositionSense {
+      filemark: sense.filemark,
+      end_of_data: key == 0x0005 ||
+          (matches!(sense.sense_key, SenseKey::BlankCheck) && key == 0x0000),
+      beginning_of_partition: key == 0x0004,
+      end_of_partition: key == 0x0002 ||
+          matches!(sense.sense_key, SenseKey::VolumeOverflow),
+      early_warning: sense.eom && no_error && key == 0x0000,
+      programmable_early_warning: key == 0x0007,
+    }
+  }
+}
+
+impl fmt::Display for PositionSense {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.filemark { writeln!(f, "Filemark encountered")?; }
+    if self.end_of_data { writeln!(f, "End of data encountered")?; }
+    if self.beginning_of_partition { writeln!(f, "Beginning of partition encountered")?; }
+    if self.end_of_partition { writeln!(f, "End of partition encountered")?; }
+    if self.early_warning { writeln!(f, "Early warning encountered")?; }
+    if self.programmable_early_warning {
+      writeln!(f, "Programmable early warning encountered")?;
+    }
+    Ok(())
+  }
+}
+
+/// Field rejected by the device server, from the sense key specific
+/// field pointer (SPC-3 Section 4.5.2.4.2)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvalidField {
+  pub in_cdb: bool,
+  pub byte: u16,
+  pub bit: Option <u8>,
+}
+
+impl InvalidField {
+  pub fn from_sense(sense: &Sense) -> Option <InvalidField> {
+    if let SenseKeySpecific::IllegalRequest(pointer) = &sense.sense_key_specific {
+      Some(InvalidField {
+        in_cdb: pointer.c_d,
+        byte: pointer.field_pointer,
+        bit: if pointer.bpv { Some(pointer.bit_pointer) } else { None },
+      })
+    } else {
+      None
+    }
+  }
+}
+
+impl fmt::Display for InvalidField {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Invalid field in {} at byte {}",
+        if self.in_cdb { "CDB" } else { "parameter data" }, self.byte)?;
+    if let Some(bit) = self.bit {
+      write!(f, " bit {}", bit)?;
+    }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for READ(6), READ(16), READ REVERSE(6),
+/// READ REVERSE(16) and RECOVER BUFFERED DATA (SSC-4 Section 6.4)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadSenseInfo {
+  pub position: PositionSense,
+  pub incorrect_length: bool,
+  /// Length of the logical block on the medium. Only reported in variable
+  /// block mode when the length did not match the request.
+  pub actual_block_length: Option <u32>,
+  /// Blocks requested but not transferred. Only reported in fixed block mode.
+  pub blocks_not_transferred: Option <u32>,
+}
+
+impl ReadSenseInfo {
+  pub fn from_sense(sense: &Sense, fixed: bool, transfer_length: u32) -> ReadSenseInfo {
+    let mut rv = ReadSenseInfo {
+      position: PositionSense::from_sense(sense),
+      incorrect_length: sense.ili,
+      ..Default::default()
+    };
+
+    match sense.residue(fixed) {
+      Some(Residue::Blocks(residue)) => {
+        rv.blocks_not_transferred = u32::try_from(residue).ok();
+      },
+      Some(Residue::Bytes(residue)) if sense.ili => {
+        rv.actual_block_length =
+            u32::try_from(transfer_length as i64 - residue).ok();
+      },
+      _ => {},
+    }
+
+    rv
+  }
+}
+
+impl fmt::Display for ReadSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if self.incorrect_length { writeln!(f, "Incorrect length")?; }
+    if let Some(len) = self.actual_block_length {
+      writeln!(f, "Actual block length: {}", len)?;
+    }
+    if let Some(count) = self.blocks_not_transferred {
+      writeln!(f, "Blocks not transferred: {}", count)?;
+    }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for WRITE(6) and WRITE(16) (SSC-4 Section 6.8)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteSenseInfo {
+  pub position: PositionSense,
+  /// Blocks (fixed) or bytes (variable) that were requested but not written
+  pub not_written: Option <Residue>,
+}
+
+impl WriteSenseInfo {
+  pub fn from_sense(sense: &Sense, fixed: bool) -> WriteSenseInfo {
+    WriteSenseInfo {
+      position: PositionSense::from_sense(sense),
+      not_written: sense.residue(fixed),
+    }
+  }
+}
+
+impl fmt::Display for WriteSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    match self.not_written {
+      Some(Residue::Blocks(count)) => { writeln!(f, "Blocks not written: {}", count) },
+      Some(Residue::Bytes(count)) => { writeln!(f, "Bytes not written: {}", count) },
+      None => { Ok(()) },
+    }
+  }
+}
+
+/// Sense interpretation for WRITE FILEMARKS(6) and WRITE FILEMARKS(16)
+/// (SSC-4 Section 6.9)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteFilemarksSenseInfo {
+  pub position: PositionSense,
+  pub filemarks_not_written: Option <u32>,
+}
+
+impl WriteFilemarksSenseInfo {
+  pub fn from_sense(sense: &Sense) -> WriteFilemarksSenseInfo {
+    WriteFilemarksSenseInfo {
+      position: PositionSense::from_sense(sense),
+      filemarks_not_written: sense.information_signed()
+          .and_then(|count| u32::try_from(count).ok()),
+    }
+  }
+}
+
+impl fmt::Display for WriteFilemarksSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if let Some(count) = self.filemarks_not_written {
+      writeln!(f, "Filemarks not written: {}", count)?;
+    }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for VERIFY(6) and VERIFY(16) (SSC-4 Section 6.7)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerifySenseInfo {
+  pub position: PositionSense,
+  pub incorrect_length: bool,
+  pub miscompare: bool,
+  /// Blocks (fixed) or bytes (variable) that were requested but not verified
+  pub not_verified: Option <Residue>,
+}
+
+impl VerifySenseInfo {
+  pub fn from_sense(sense: &Sense, fixed: bool) -> VerifySenseInfo {
+    VerifySenseInfo {
+      position: PositionSense::from_sense(sense),
+      incorrect_length: sense.ili,
+      miscompare: matches!(sense.sense_key, SenseKey::Miscompare),
+      not_verified: sense.residue(fixed),
+    }
+  }
+}
+
+impl fmt::Display for VerifySenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if self.incorrect_length { writeln!(f, "Incorrect length")?; }
+    if self.miscompare { writeln!(f, "Miscompare")?; }
+    match self.not_verified {
+      Some(Residue::Blocks(count)) => { writeln!(f, "Blocks not verified: {}", count) },
+      Some(Residue::Bytes(count)) => { writeln!(f, "Bytes not verified: {}", count) },
+      None => { Ok(()) },
+    }
+  }
+}
+
+/// Sense interpretation for SPACE(6) and SPACE(16) (SSC-4 Section 6.6)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpaceSenseInfo {
+  pub position: PositionSense,
+  /// Requested count minus the number of objects actually spaced over.
+  /// Negative when spacing toward the beginning of the partition.
+  pub not_spaced: Option <i64>,
+}
+
+impl SpaceSenseInfo {
+  pub fn from_sense(sense: &Sense) -> SpaceSenseInfo {
+    SpaceSenseInfo {
+      position: PositionSense::from_sense(sense),
+      not_spaced: sense.information_signed(),
+    }
+  }
+}
+
+impl fmt::Display for SpaceSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if let Some(count) = self.not_spaced {
+      writeln!(f, "Objects not spaced over: {}", count)?;
+    }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for LOCATE(10), LOCATE(16) and ALLOW OVERWRITE
+/// (SSC-4 Sections 6.3 and 7.1)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocateSenseInfo {
+  pub position: PositionSense,
+  /// SEQUENTIAL POSITIONING ERROR, the logical position is not the requested one
+  pub positioning_error: bool,
+  pub invalid_field: Option <InvalidField>,
+}
+
+impl LocateSenseInfo {
+  pub fn from_sense(sense: &Sense) -> LocateSenseInfo {
+    LocateSenseInfo {
+      position: PositionSense::from_sense(sense),
+      positioning_error: asc_ascq(sense) == 0x3B00,
+      invalid_field: InvalidField::from_sense(sense),
+    }
+  }
+}
+
+impl fmt::Display for LocateSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if self.positioning_error { writeln!(f, "Sequential positioning error")?; }
+    if let Some(field) = &self.invalid_field { writeln!(f, "{}", field)?; }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for commands that move or prepare the medium and
+/// may be issued with IMMED: REWIND, LOAD UNLOAD, ERASE, FORMAT MEDIUM and
+/// SET CAPACITY (SSC-4 Section 4.2.2)
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediumOperationSenseInfo {
+  pub position: PositionSense,
+  pub operation_in_progress: bool,
+  /// Progress indication out of 65536
+  pub progress: Option <u16>,
+  pub medium_not_present: bool,
+  pub medium_removal_prevented: bool,
+  pub write_protected: bool,
+  pub invalid_field: Option <InvalidField>,
+}
+
+impl MediumOperationSenseInfo {
+  pub fn from_sense(sense: &Sense) -> MediumOperationSenseInfo {
+    let key = asc_ascq(sense);
+
+    MediumOperationSenseInfo {
+      position: PositionSense::from_sense(sense),
+      operation_in_progress: matches!(key,
+          0x0016 | 0x0018..=0x001C | 0x0401 | 0x0404 | 0x0407),
+      progress: if let SenseKeySpecific::NotReady(p) = &sense.sense_key_specific {
+        Some(p.progress)
+      } else {
+        None
+      },
+      medium_not_present: sense.additional_sense_code == 0x3A,
+      medium_removal_prevented: key == 0x5302,
+      write_protected: sense.additional_sense_code == 0x27,
+      invalid_field: InvalidField::from_sense(sense),
+    }
+  }
+
+  /// Progress indication as a percentage
+  pub fn percent(&self) -> Option <f64> {
+    self.progress.map(|p| p as f64 * 100.0 / 65536.0)
+  }
+}
+
+impl fmt::Display for MediumOperationSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.position)?;
+    if self.operation_in_progress { writeln!(f, "Operation in progress")?; }
+    if let Some(percent) = self.percent() { writeln!(f, "Progress: {:.1}%", percent)?; }
+    if self.medium_not_present { writeln!(f, "Medium not present")?; }
+    if self.medium_removal_prevented { writeln!(f, "Medium removal prevented")?; }
+    if self.write_protected { writeln!(f, "Write protected")?; }
+    if let Some(field) = &self.invalid_field { writeln!(f, "{}", field)?; }
+    Ok(())
+  }
+}
+
+/// Sense interpretation for commands whose only command specific failures
+/// are rejected CDB or parameter fields
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterSenseInfo {
+  pub invalid_field: Option <InvalidField>,
+}
+
+impl ParameterSenseInfo {
+  pub fn from_sense(sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo {
+      invalid_field: InvalidField::from_sense(sense),
+    }
+  }
+}
+
+impl fmt::Display for ParameterSenseInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(field) = &self.invalid_field { writeln!(f, "{}", field)?; }
+    Ok(())
+  }
+}
+
+#[test]
+fn read_variable_ili_test() {
+  //ILI, VALID, information of 20 with a request of 100 bytes
+  let buf = [
+    0xF0, 0x00, 0x20, 0x00, 0x00, 0x00, 0x14, 0x0A,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+  let sense = Sense::from_buf(&buf).unwrap();
+
+  let info = ReadSenseInfo::from_sense(&sense, false, 100);
+  assert!(info.incorrect_length);
+  assert_eq!(info.actual_block_length, Some(80));
+  assert_eq!(info.blocks_not_transferred, None);
+
+  let info = ReadSenseInfo::from_sense(&sense, true, 100);
+  assert_eq!(info.actual_block_length, None);
+  assert_eq!(info.blocks_not_transferred, Some(20));
+}
+
+#[test]
+fn space_filemark_test() {
+  //FILEMARK, VALID, information of 2, ASC/ASCQ FILEMARK DETECTED
+  let buf = [
+    0xF0, 0x00, 0x80, 0x00, 0x00, 0x00, 0x02, 0x0A,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+  ];
+  let sense = Sense::from_buf(&buf).unwrap();
+
+  let info = SpaceSenseInfo::from_sense(&sense);
+  assert!(info.position.filemark);
+  assert!(!info.position.end_of_data);
+  assert_eq!(info.not_spaced, Some(2));
+}
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
 
 /// SSC-4 Section 7.11
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetCapacity {
   pub immed: bool,
   pub medium_for_proportion_value: u16,
@@ -13,12 +15,26 @@ impl SetCapacity {
   pub fn new() -> SetCapacity {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SetCapacity> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(SetCapacity {
+      immed: buf[1] & 0x1 == 0x1,
+      medium_for_proportion_value: u16::from_be_bytes([buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::NoIO for SetCapacity {
 }
 
 impl crate::Command for SetCapacity {
+  type SenseInfo = MediumOperationSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -29,7 +45,7 @@ impl crate::Command for SetCapacity {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String { 
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
   }
 }
@@ -0,0 +1,59 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.44
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetIdentifyingInformation {
+  pub information_type: u8,
+  /// Zero clears the information
+  pub parameter_list_length: u32,
+}
+
+impl SetIdentifyingInformation {
+  const OP_CODE: u8 = 0xA4;
+  const SERVICE_ACTION: u8 = 0x06;
+
+  pub fn new() -> SetIdentifyingInformation {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SetIdentifyingInformation> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(SetIdentifyingInformation {
+      information_type: buf[10] >> 1,
+      parameter_list_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+}
+
+impl crate::Input for SetIdentifyingInformation {
+}
+
+impl crate::Command for SetIdentifyingInformation {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    if self.information_type > 0x7E {
+      return Err("Information type must be in the range 0..127");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[6..10].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+    data[10] = self.information_type << 1;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
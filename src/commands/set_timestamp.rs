@@ -0,0 +1,54 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use crate::commands::timestamp::Timestamp;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.45
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetTimestamp {
+  pub parameter_list_length: u32,
+}
+
+impl SetTimestamp {
+  const OP_CODE: u8 = 0xA4;
+  const SERVICE_ACTION: u8 = 0x0F;
+
+  pub fn new() -> SetTimestamp {
+    SetTimestamp {
+      parameter_list_length: Timestamp::LEN as u32,
+    }
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <SetTimestamp> {
+    if buf.len() < 12 || buf[0] != Self::OP_CODE || buf[1] & 0x1F != Self::SERVICE_ACTION {
+      return None;
+    }
+
+    Some(SetTimestamp {
+      parameter_list_length: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+    })
+  }
+}
+
+impl crate::Input for SetTimestamp {
+}
+
+impl crate::Command for SetTimestamp {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 12];
+
+    data[0] = Self::OP_CODE;
+    data[1] = Self::SERVICE_ACTION;
+    data[6..10].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -1,13 +1,17 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::SpaceSenseInfo;
 
 /// SSC-4 Section 7.12
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Space16Positioning {
   pub partition_number: u8,
   pub logical_object_identifier: u64,
 }
 
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Space16 {
   pub code: u8,
   pub count: u64,
@@ -24,12 +28,36 @@ impl Space16 {
   pub fn new() -> Space16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Space16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    let parameter_length = u16::from_be_bytes([buf[12], buf[13]]);
+    Some(Space16 {
+      code: buf[1] & 0x0F,
+      count: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      positioning_info: if parameter_length == 16 && buf.len() >= 32 {
+        Some(Space16Positioning {
+          partition_number: buf[19],
+          logical_object_identifier:
+              u64::from_be_bytes(buf[20..28].try_into().unwrap()),
+        })
+      } else {
+        None
+      },
+    })
+  }
 }
 
 impl crate::NoIO for Space16 {
 }
 
 impl crate::Command for Space16 {
+  type SenseInfo = SpaceSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data =
         if self.positioning_info.is_none() {
@@ -45,10 +73,9 @@ impl crate::Command for Space16 {
     data[0] = Self::OP_CODE;
     data[1] = self.code;
     data[4..12].copy_from_slice(&self.count.to_be_bytes());
-    if self.positioning_info.is_some() {
+    if let Some(pos) = &self.positioning_info {
       data[12..14].copy_from_slice(&16_u16.to_be_bytes());
-      
-      let pos = self.positioning_info.as_ref().unwrap();
+
       data[19] = pos.partition_number;
       data[20..28].copy_from_slice(
           &pos.logical_object_identifier.to_be_bytes());
@@ -57,7 +84,7 @@ impl crate::Command for Space16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> SpaceSenseInfo {
+    SpaceSenseInfo::from_sense(sense)
   }
 }
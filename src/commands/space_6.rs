@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::SpaceSenseInfo;
 
 /// SSC-4 Section 6.6
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Space6 {
   pub code: u8,
   pub count: u32,
@@ -17,12 +19,26 @@ impl Space6 {
   pub fn new() -> Space6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Space6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Space6 {
+      code: buf[1] & 0x0F,
+      count: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::NoIO for Space6 {
 }
 
 impl crate::Command for Space6 {
+  type SenseInfo = SpaceSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -41,7 +57,7 @@ impl crate::Command for Space6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> SpaceSenseInfo {
+    SpaceSenseInfo::from_sense(sense)
   }
 }
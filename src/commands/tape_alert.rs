@@ -0,0 +1,346 @@
+use crate::commands::LogPage;
+use crate::commands::LogPageType;
+use crate::commands::log::read_typed_log_page;
+use crate::device::Device;
+use crate::sense::Sense;
+use std::fmt;
+
+/// TapeAlert flag severity, SSC-4 Annex A
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TapeAlertSeverity {
+  Information,
+  Warning,
+  Critical,
+}
+
+/// Catalogue entry for one TapeAlert flag
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TapeAlertFlagInfo {
+  pub flag: u8,
+  pub name: &'static str,
+  pub severity: TapeAlertSeverity,
+  /// Recommended application client message; empty for obsolete and
+  /// reserved flags
+  pub message: &'static str,
+}
+
+impl fmt::Display for TapeAlertFlagInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:#04x} {} ({:?})", self.flag, self.name, self.severity)
+  }
+}
+
+static TAPE_ALERT_FLAG_INFO: [TapeAlertFlagInfo; 64] = [
+  TapeAlertFlagInfo { flag: 0x01, name: "Read warning", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive is having problems reading data. No data has been lost, but there has been a reduction in the performance of the tape." },
+  TapeAlertFlagInfo { flag: 0x02, name: "Write warning", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive is having problems writing data. No data has been lost, but there has been a reduction in the capacity of the tape." },
+  TapeAlertFlagInfo { flag: 0x03, name: "Hard error", severity: TapeAlertSeverity::Warning,
+      message: "The operation has stopped because an error has occurred while reading or writing data that the drive cannot correct." },
+  TapeAlertFlagInfo { flag: 0x04, name: "Media", severity: TapeAlertSeverity::Critical,
+      message: "Your data is at risk: 1. Copy any data you require from this tape. 2. Do not use this tape again. 3. Restart the operation with a different tape." },
+  TapeAlertFlagInfo { flag: 0x05, name: "Read failure", severity: TapeAlertSeverity::Critical,
+      message: "The tape is damaged or the drive is faulty. Call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x06, name: "Write failure", severity: TapeAlertSeverity::Critical,
+      message: "The tape is from a faulty batch or the tape drive is faulty: 1. Use a good tape to test the drive. 2. If problem persists, call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x07, name: "Media life", severity: TapeAlertSeverity::Warning,
+      message: "The tape cartridge has reached the end of its calculated useful life: 1. Copy data you need to another tape. 2. Discard the old tape." },
+  TapeAlertFlagInfo { flag: 0x08, name: "Not data grade", severity: TapeAlertSeverity::Warning,
+      message: "The tape cartridge is not data-grade. Any data you back up to the tape is at risk. Replace the cartridge with a data-grade tape." },
+  TapeAlertFlagInfo { flag: 0x09, name: "Write protect", severity: TapeAlertSeverity::Critical,
+      message: "You are trying to write to a write-protected cartridge. Remove the write-protection or use another tape." },
+  TapeAlertFlagInfo { flag: 0x0A, name: "No removal", severity: TapeAlertSeverity::Information,
+      message: "You cannot eject the cartridge because the tape drive is in use. Wait until the operation is complete before ejecting the cartridge." },
+  TapeAlertFlagInfo { flag: 0x0B, name: "Cleaning media", severity: TapeAlertSeverity::Information,
+      message: "The tape in the drive is a cleaning cartridge." },
+  TapeAlertFlagInfo { flag: 0x0C, name: "Unsupported format", severity: TapeAlertSeverity::Information,
+      message: "You have tried to load a cartridge of a type which is not supported by this drive." },
+  TapeAlertFlagInfo { flag: 0x0D, name: "Recoverable mechanical cartridge failure", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed because the tape in the drive has experienced a mechanical failure: 1. Discard the old tape. 2. Restart the operation with a different tape." },
+  TapeAlertFlagInfo { flag: 0x0E, name: "Unrecoverable mechanical cartridge failure", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed because the tape in the drive has experienced a mechanical failure: 1. Do not attempt to extract the tape cartridge. 2. Call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x0F, name: "Memory chip in cartridge failure", severity: TapeAlertSeverity::Warning,
+      message: "The memory in the tape cartridge has failed, which reduces performance. Do not use the cartridge for further write operations." },
+  TapeAlertFlagInfo { flag: 0x10, name: "Forced eject", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed because the tape cartridge was manually de-mounted while the tape drive was actively writing or reading." },
+  TapeAlertFlagInfo { flag: 0x11, name: "Read only format", severity: TapeAlertSeverity::Warning,
+      message: "You have loaded a cartridge of a type that is read-only in this drive. The cartridge will appear as write-protected." },
+  TapeAlertFlagInfo { flag: 0x12, name: "Tape directory corrupted on load", severity: TapeAlertSeverity::Warning,
+      message: "The tape directory on the tape cartridge has been corrupted. File search performance will be degraded. The tape directory can be rebuilt by reading all the data on the cartridge." },
+  TapeAlertFlagInfo { flag: 0x13, name: "Nearing media life", severity: TapeAlertSeverity::Information,
+      message: "The tape cartridge is nearing the end of its calculated life. It is recommended that you: 1. Use another tape cartridge for your next backup. 2. Store this tape cartridge in a safe place in case you need to restore data from it." },
+  TapeAlertFlagInfo { flag: 0x14, name: "Clean now", severity: TapeAlertSeverity::Critical,
+      message: "The tape drive needs cleaning: 1. If the operation has stopped, eject the tape and clean the drive. 2. If the operation has not stopped, wait for it to finish and then clean the drive." },
+  TapeAlertFlagInfo { flag: 0x15, name: "Clean periodic", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive is due for routine cleaning: 1. Wait for the current operation to finish. 2. Then use a cleaning cartridge." },
+  TapeAlertFlagInfo { flag: 0x16, name: "Expired cleaning media", severity: TapeAlertSeverity::Critical,
+      message: "The last cleaning cartridge used in the tape drive has worn out: 1. Discard the worn out cleaning cartridge. 2. Wait for the current operation to finish. 3. Then use a new cleaning cartridge." },
+  TapeAlertFlagInfo { flag: 0x17, name: "Invalid cleaning tape", severity: TapeAlertSeverity::Critical,
+      message: "The last cleaning cartridge used in the tape drive was an invalid type: 1. Do not use this cleaning cartridge in this drive. 2. Wait for the current operation to finish. 3. Then use a valid cleaning cartridge." },
+  TapeAlertFlagInfo { flag: 0x18, name: "Retension requested", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive has requested a retension operation." },
+  TapeAlertFlagInfo { flag: 0x19, name: "Dual-port interface error", severity: TapeAlertSeverity::Warning,
+      message: "A redundant interface port on the tape drive has failed." },
+  TapeAlertFlagInfo { flag: 0x1A, name: "Cooling fan failure", severity: TapeAlertSeverity::Warning,
+      message: "A tape drive cooling fan has failed." },
+  TapeAlertFlagInfo { flag: 0x1B, name: "Power supply failure", severity: TapeAlertSeverity::Warning,
+      message: "A redundant power supply has failed inside the tape drive enclosure. Check the enclosure user's manual for instructions on replacing the failed power supply." },
+  TapeAlertFlagInfo { flag: 0x1C, name: "Power consumption", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive power consumption is outside the specified range." },
+  TapeAlertFlagInfo { flag: 0x1D, name: "Drive maintenance", severity: TapeAlertSeverity::Warning,
+      message: "Preventive maintenance of the tape drive is required. Check the tape drive user's manual for device specific preventive maintenance tasks or call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x1E, name: "Hardware A", severity: TapeAlertSeverity::Critical,
+      message: "The tape drive has a hardware fault: 1. Eject the tape or magazine. 2. Reset the drive. 3. Restart the operation." },
+  TapeAlertFlagInfo { flag: 0x1F, name: "Hardware B", severity: TapeAlertSeverity::Critical,
+      message: "The tape drive has a hardware fault: 1. Turn the tape drive off and then on again. 2. Restart the operation. 3. If the problem persists, call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x20, name: "Interface", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive has a problem with the application client interface: 1. Check the cables and cable connections. 2. Restart the operation." },
+  TapeAlertFlagInfo { flag: 0x21, name: "Eject media", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed: 1. Eject the tape or magazine. 2. Insert the tape or magazine again. 3. Restart the operation." },
+  TapeAlertFlagInfo { flag: 0x22, name: "Download fail", severity: TapeAlertSeverity::Warning,
+      message: "The firmware download has failed because you have tried to use the incorrect firmware for this tape drive. Obtain the correct firmware and try again." },
+  TapeAlertFlagInfo { flag: 0x23, name: "Drive humidity", severity: TapeAlertSeverity::Warning,
+      message: "Environmental conditions inside the tape drive are outside the specified humidity range." },
+  TapeAlertFlagInfo { flag: 0x24, name: "Drive temperature", severity: TapeAlertSeverity::Warning,
+      message: "Environmental conditions inside the tape drive are outside the specified temperature range." },
+  TapeAlertFlagInfo { flag: 0x25, name: "Drive voltage", severity: TapeAlertSeverity::Warning,
+      message: "The voltage supply to the tape drive is outside the specified range." },
+  TapeAlertFlagInfo { flag: 0x26, name: "Predictive failure", severity: TapeAlertSeverity::Critical,
+      message: "A hardware failure of the tape drive is predicted. Call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x27, name: "Diagnostics required", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive may have a hardware fault. Run extended diagnostics to verify and diagnose the problem. Check the tape drive user's manual for device specific instructions on running extended diagnostic tests." },
+  TapeAlertFlagInfo { flag: 0x28, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x29, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2A, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2B, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2C, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2D, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2E, name: "Obsolete", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x2F, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x30, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x31, name: "Diminished native capacity", severity: TapeAlertSeverity::Information,
+      message: "The tape cartridge cannot reach its full native capacity in this drive. Use it only if the reduced capacity is acceptable." },
+  TapeAlertFlagInfo { flag: 0x32, name: "Lost statistics", severity: TapeAlertSeverity::Warning,
+      message: "Media statistics have been lost at some time in the past." },
+  TapeAlertFlagInfo { flag: 0x33, name: "Tape directory invalid at unload", severity: TapeAlertSeverity::Warning,
+      message: "The tape directory on the tape cartridge just unloaded has been corrupted. File search performance will be degraded. The tape directory can be rebuilt by reading all the data." },
+  TapeAlertFlagInfo { flag: 0x34, name: "Tape system area write failure", severity: TapeAlertSeverity::Critical,
+      message: "The tape just unloaded could not write its system area successfully: 1. Copy data to another tape cartridge. 2. Discard the old cartridge." },
+  TapeAlertFlagInfo { flag: 0x35, name: "Tape system area read failure", severity: TapeAlertSeverity::Critical,
+      message: "The tape system area could not be read successfully at load time: 1. Copy data to another tape cartridge." },
+  TapeAlertFlagInfo { flag: 0x36, name: "No start of data", severity: TapeAlertSeverity::Critical,
+      message: "The start of data could not be found on the tape: 1. Check that you are using the correct format tape. 2. Discard the tape or return the tape to your supplier." },
+  TapeAlertFlagInfo { flag: 0x37, name: "Loading failure", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed because the media cannot be loaded and threaded: 1. Remove the cartridge, inspect it as specified in the product manual, and retry the operation. 2. If the problem persists, call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x38, name: "Unrecoverable unload failure", severity: TapeAlertSeverity::Critical,
+      message: "The operation has failed because the medium cannot be unloaded: 1. Do not attempt to extract the tape cartridge. 2. Call the tape drive supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x39, name: "Automation interface failure", severity: TapeAlertSeverity::Critical,
+      message: "The tape drive has a problem with the automation interface: 1. Check the power to the automation system. 2. Check the cables and cable connections. 3. Call the supplier helpline if the problem persists." },
+  TapeAlertFlagInfo { flag: 0x3A, name: "Firmware failure", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive has reset itself due to a detected firmware fault. If the problem persists, call the supplier helpline." },
+  TapeAlertFlagInfo { flag: 0x3B, name: "WORM medium - integrity check failed", severity: TapeAlertSeverity::Warning,
+      message: "The tape drive has detected an inconsistency during the WORM medium integrity checks. Someone may have tampered with the cartridge." },
+  TapeAlertFlagInfo { flag: 0x3C, name: "WORM medium - overwrite attempted", severity: TapeAlertSeverity::Warning,
+      message: "An attempt had been made to overwrite user data on a WORM medium: 1. If a WORM medium was used inadvertently, replace it with a normal data medium. 2. If a WORM medium was used intentionally, check that the software application is compatible with the WORM medium format you are using and that the medium is bar-coded correctly for WORM." },
+  TapeAlertFlagInfo { flag: 0x3D, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x3E, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x3F, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+  TapeAlertFlagInfo { flag: 0x40, name: "Reserved", severity: TapeAlertSeverity::Information,
+      message: "" },
+];
+
+/// Set of TapeAlert flags 1 through 64, as carried by the TapeAlert
+/// Supported Flags VPD page and the TapeAlert log page. Flag 1 is the most
+/// significant bit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeAlertFlags(pub u64);
+
+impl TapeAlertFlags {
+  pub const MIN_FLAG: u8 = 1;
+  pub const MAX_FLAG: u8 = 64;
+
+  pub fn new() -> TapeAlertFlags {
+    Default::default()
+  }
+
+  /// Decode the 8 byte big-endian bitmap. Returns None if `buf` is short.
+  pub fn from_bytes(buf: &[u8]) -> Option <TapeAlertFlags> {
+    if buf.len() < 8 {
+      return None;
+    }
+
+    let mut bits = [0; 8];
+    bits.copy_from_slice(&buf[..8]);
+    Some(TapeAlertFlags(u64::from_be_bytes(bits)))
+  }
+
+  pub fn to_bytes(&self) -> [u8; 8] {
+    self.0.to_be_bytes()
+  }
+
+  fn mask(flag: u8) -> u64 {
+    if (Self::MIN_FLAG..=Self::MAX_FLAG).contains(&flag) {
+      1 << (Self::MAX_FLAG - flag)
+    } else {
+      0
+    }
+  }
+
+  /// Whether `flag` (1 to 64) is set
+  pub fn contains(&self, flag: u8) -> bool {
+    self.0 & Self::mask(flag) != 0
+  }
+
+  /// Set `flag` (1 to 64); out of range flags are ignored
+  pub fn insert(&mut self, flag: u8) {
+    self.0 |= Self::mask(flag);
+  }
+
+  pub fn remove(&mut self, flag: u8) {
+    self.0 &= !Self::mask(flag);
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0 == 0
+  }
+
+  /// Numbers of the set flags in ascending order
+  pub fn flags(&self) -> Vec <u8> {
+    (Self::MIN_FLAG..=Self::MAX_FLAG).filter(|f| self.contains(*f)).collect()
+  }
+
+  /// Catalogue entry for `flag` (1 to 64)
+  pub fn info(flag: u8) -> Option <&'static TapeAlertFlagInfo> {
+    if (Self::MIN_FLAG..=Self::MAX_FLAG).contains(&flag) {
+      Some(&TAPE_ALERT_FLAG_INFO[flag as usize - 1])
+    } else {
+      None
+    }
+  }
+
+  /// Catalogue entries of the set flags in ascending order
+  pub fn active(&self) -> Vec <&'static TapeAlertFlagInfo> {
+    self.flags().into_iter().filter_map(Self::info).collect()
+  }
+
+  /// Highest severity among the set flags
+  pub fn max_severity(&self) -> Option <TapeAlertSeverity> {
+    self.active().iter().map(|i| i.severity).max()
+  }
+}
+
+impl fmt::Display for TapeAlertFlags {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let flags: Vec <String> = self.flags().iter().map(|f| format!("{:#04x}", f)).collect();
+    write!(f, "{}", flags.join(" "))
+  }
+}
+
+/// SSC-4 Section 8.2.3 TapeAlert log page. Reading the page clears the
+/// flags on the device.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeAlertLog {
+  pub flags: TapeAlertFlags,
+}
+
+impl LogPageType for TapeAlertLog {
+  const PAGE_CODE: u8 = 0x2E;
+
+  fn from_page(page: &LogPage) -> Option <TapeAlertLog> {
+    let mut flags = TapeAlertFlags::new();
+    for parameter in &page.parameters {
+      let active = parameter.value.first().is_some_and(|v| v & 0x01 == 0x01);
+      if active && parameter.parameter_code <= TapeAlertFlags::MAX_FLAG.into() {
+        flags.insert(parameter.parameter_code as u8);
+      }
+    }
+
+    Some(TapeAlertLog { flags })
+  }
+}
+
+impl fmt::Display for TapeAlertLog {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "TapeAlert:")?;
+    for info in self.flags.active() {
+      writeln!(f, "  {}", info)?;
+    }
+    Ok(())
+  }
+}
+
+/// Read the TapeAlert log page, typically after sense data reports an
+/// informational exception (see [`Sense::is_informational_exception`]).
+///
+/// [`Sense::is_informational_exception`]: crate::Sense::is_informational_exception
+pub fn read_tape_alert_flags(device: &Device) -> Result <TapeAlertFlags, String> {
+  read_typed_log_page::<TapeAlertLog>(device).map(|t| t.flags)
+}
+
+/// Read the TapeAlert flags behind an informational exception. Returns None
+/// without issuing a command if `sense` does not report one, so the flags are
+/// not cleared needlessly.
+pub fn read_tape_alert_flags_for_sense(device: &Device, sense: &Sense)
+    -> Result <Option <TapeAlertFlags>, String> {
+  if !sense.is_informational_exception() {
+    return Ok(None);
+  }
+
+  read_tape_alert_flags(device).map(Some)
+}
+
+#[test]
+fn tape_alert_flags_test() {
+  let flags = TapeAlertFlags::from_bytes(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01])
+      .unwrap();
+  assert!(flags.contains(1));
+  assert!(flags.contains(64));
+  assert!(!flags.contains(2));
+  assert!(!flags.contains(0));
+  assert_eq!(flags.flags(), vec![1, 64]);
+
+  let mut other = TapeAlertFlags::new();
+  other.insert(1);
+  other.insert(64);
+  other.insert(65);
+  assert_eq!(other, flags);
+  assert_eq!(other.to_bytes(), [0x80, 0, 0, 0, 0, 0, 0, 0x01]);
+}
+
+#[test]
+fn tape_alert_log_test() {
+  use crate::commands::log::TypedLogPage;
+
+  let mut buf = vec![0x2E, 0x00, 0x01, 0x40];
+  for flag in 1..=64_u16 {
+    buf.extend_from_slice(&flag.to_be_bytes());
+    buf.extend_from_slice(&[0x00, 0x01, if flag == 0x04 || flag == 0x14 { 0x01 } else { 0x00 }]);
+  }
+
+  let page = LogPage::from_bytes(&buf).unwrap();
+  let log = match page.decode() {
+    TypedLogPage::TapeAlertLog(t) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(log.flags.flags(), vec![0x04, 0x14]);
+  assert_eq!(TapeAlertFlags::info(0x14).unwrap().name, "Clean now");
+  assert_eq!(TapeAlertFlags::info(0x04).unwrap().name, "Media");
+  assert_eq!(log.flags.max_severity(), Some(TapeAlertSeverity::Critical));
+  assert!(TapeAlertFlags::info(65).is_none());
+}
@@ -0,0 +1,690 @@
+use crate::commands::SecurityProtocolIn;
+use crate::commands::SecurityProtocolOut;
+use crate::device::{Device, DeviceStatus, check_good};
+use std::convert::TryInto;
+use std::fmt;
+
+// SSC-4 Section 8.5.2 Tape Data Encryption security protocol pages
+pub const IN_SUPPORT_PAGES: u16 = 0x0000;
+pub const OUT_SUPPORT_PAGES: u16 = 0x0001;
+pub const DATA_ENCRYPTION_CAPABILITIES: u16 = 0x0010;
+pub const SUPPORTED_KEY_FORMATS: u16 = 0x0011;
+pub const DATA_ENCRYPTION_MANAGEMENT_CAPABILITIES: u16 = 0x0012;
+pub const DATA_ENCRYPTION_STATUS: u16 = 0x0020;
+pub const NEXT_BLOCK_ENCRYPTION_STATUS: u16 = 0x0021;
+pub const DEVICE_SERVER_KEY_WRAPPING_PUBLIC_KEY: u16 = 0x0031;
+pub const SET_DATA_ENCRYPTION: u16 = 0x0010;
+
+pub const SCOPE_PUBLIC: u8 = 0x0;
+pub const SCOPE_LOCAL: u8 = 0x1;
+pub const SCOPE_ALL_I_T_NEXUS: u8 = 0x2;
+
+pub const ENCRYPTION_MODE_DISABLE: u8 = 0x0;
+pub const ENCRYPTION_MODE_EXTERNAL: u8 = 0x1;
+pub const ENCRYPTION_MODE_ENCRYPT: u8 = 0x2;
+
+pub const DECRYPTION_MODE_DISABLE: u8 = 0x0;
+pub const DECRYPTION_MODE_RAW: u8 = 0x1;
+pub const DECRYPTION_MODE_DECRYPT: u8 = 0x2;
+pub const DECRYPTION_MODE_MIXED: u8 = 0x3;
+
+pub const KEY_FORMAT_PLAIN_TEXT: u8 = 0x00;
+pub const KEY_FORMAT_VENDOR_SPECIFIC_REFERENCE: u8 = 0x01;
+pub const KEY_FORMAT_WRAPPED: u8 = 0x02;
+pub const KEY_FORMAT_ESP_SCSI: u8 = 0x03;
+
+fn page_header(buf: &[u8], page_code: u16) -> Option <&[u8]> {
+  if buf.len() < 4 || u16::from_be_bytes([buf[0], buf[1]]) != page_code {
+    return None;
+  }
+
+  let length = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+  Some(&buf[..buf.len().min(4 + length)])
+}
+
+/// SSC-4 Section 8.5.2.7 key-associated data (KAD) descriptor
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyAssociatedData {
+  pub kad_type: u8,
+  pub authenticated: u8,
+  pub descriptor: Vec <u8>,
+}
+
+impl KeyAssociatedData {
+  /// Unauthenticated KAD, such as a key label
+  pub const UKAD: u8 = 0x00;
+  /// Authenticated KAD
+  pub const AKAD: u8 = 0x01;
+  pub const NONCE: u8 = 0x02;
+  pub const METADATA: u8 = 0x03;
+
+  pub fn list_from_bytes(buf: &[u8]) -> Vec <KeyAssociatedData> {
+    let mut rv = Vec::new();
+    let mut offset = 0;
+    while offset + 4 <= buf.len() {
+      let length = u16::from_be_bytes([buf[offset + 2], buf[offset + 3]]) as usize;
+      let descriptor = match buf.get((offset + 4)..(offset + 4 + length)) {
+        Some(t) => { t.to_vec() },
+        None => { break; },
+      };
+
+      rv.push(KeyAssociatedData {
+        kad_type: buf[offset],
+        authenticated: buf[offset + 1] & 0x07,
+        descriptor,
+      });
+      offset += 4 + length;
+    }
+
+    rv
+  }
+
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let mut rv = vec![self.kad_type, self.authenticated & 0x07];
+    rv.extend_from_slice(&(self.descriptor.len() as u16).to_be_bytes());
+    rv.extend_from_slice(&self.descriptor);
+    rv
+  }
+
+  /// Descriptor as text, for the key labels commonly stored in a UKAD
+  pub fn as_string(&self) -> String {
+    String::from_utf8_lossy(&self.descriptor).trim_end_matches('\0').to_string()
+  }
+}
+
+/// SSC-4 Section 8.5.2.3 data encryption algorithm descriptor
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataEncryptionAlgorithm {
+  pub algorithm_index: u8,
+  /// Algorithm valid for mounted volume
+  pub avfmv: bool,
+  /// Supplemental decryption key capable
+  pub sdk_c: bool,
+  /// Message authentication code capable
+  pub mac_c: bool,
+  /// Distinguish encrypted logical block capable
+  pub delb_c: bool,
+  pub decrypt_c: u8,
+  pub encrypt_c: u8,
+  /// Algorithm valid for current logical position
+  pub avfclp: u8,
+  pub nonce_c: u8,
+  /// KAD format capable
+  pub kadf_c: bool,
+  /// Volume contains encrypted logical blocks capable
+  pub vcelb_c: bool,
+  /// UKAD fixed
+  pub ukadf: bool,
+  /// AKAD fixed
+  pub akadf: bool,
+  pub maximum_ukad_length: u16,
+  pub maximum_akad_length: u16,
+  pub key_length: u16,
+  /// Decryption KAD capable
+  pub dkad_c: u8,
+  /// External encryption mode control capable
+  pub eemc_c: u8,
+  /// Raw decryption mode control capabilities
+  pub rdmc_c: u8,
+  /// Encryption algorithm records encryption mode
+  pub earem: bool,
+  pub security_algorithm_code: u32,
+}
+
+impl DataEncryptionAlgorithm {
+  /// AES-256 in GCM mode, the algorithm used by LTO drives
+  pub const AES_256_GCM: u32 = 0x0001_0014;
+  /// ENCRYPT_C and DECRYPT_C value for an algorithm capable in hardware
+  pub const CAPABLE: u8 = 0x2;
+
+  fn from_bytes(buf: &[u8]) -> Option <DataEncryptionAlgorithm> {
+    if buf.len() < 24 {
+      return None;
+    }
+
+    Some(DataEncryptionAlgorithm {
+      algorithm_index: buf[0],
+      avfmv: buf[4] & 0x80 == 0x80,
+      sdk_c: buf[4] & 0x40 == 0x40,
+      mac_c: buf[4] & 0x20 == 0x20,
+      delb_c: buf[4] & 0x10 == 0x10,
+      decrypt_c: (buf[4] >> 2) & 0x3,
+      encrypt_c: buf[4] & 0x3,
+      avfclp: buf[5] >> 6,
+      nonce_c: (buf[5] >> 4) & 0x3,
+      kadf_c: buf[5] & 0x08 == 0x08,
+      vcelb_c: buf[5] & 0x04 == 0x04,
+      ukadf: buf[5] & 0x02 == 0x02,
+      akadf: buf[5] & 0x01 == 0x01,
+      maximum_ukad_length: u16::from_be_bytes([buf[6], buf[7]]),
+      maximum_akad_length: u16::from_be_bytes([buf[8], buf[9]]),
+      key_length: u16::from_be_bytes([buf[10], buf[11]]),
+      dkad_c: buf[12] >> 6,
+      eemc_c: (buf[12] >> 4) & 0x3,
+      rdmc_c: (buf[12] >> 1) & 0x7,
+      earem: buf[12] & 0x01 == 0x01,
+      security_algorithm_code: u32::from_be_bytes(buf[20..24].try_into().unwrap()),
+    })
+  }
+
+  pub fn can_encrypt(&self) -> bool {
+    self.encrypt_c == Self::CAPABLE
+  }
+
+  pub fn can_decrypt(&self) -> bool {
+    self.decrypt_c == Self::CAPABLE
+  }
+}
+
+/// SSC-4 Section 8.5.2.3 Data Encryption Capabilities page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataEncryptionCapabilities {
+  /// External data encryption control capable
+  pub extdecc: u8,
+  /// Configuration prevented
+  pub cfg_p: u8,
+  pub algorithms: Vec <DataEncryptionAlgorithm>,
+}
+
+impl DataEncryptionCapabilities {
+  pub fn from_bytes(buf: &[u8]) -> Option <DataEncryptionCapabilities> {
+    let buf = page_header(buf, DATA_ENCRYPTION_CAPABILITIES)?;
+    if buf.len() < 20 {
+      return None;
+    }
+
+    let mut algorithms = Vec::new();
+    let mut offset = 20;
+    while offset + 4 <= buf.len() {
+      let length = 4 + u16::from_be_bytes([buf[offset + 2], buf[offset + 3]]) as usize;
+      match buf.get(offset..(offset + length)).and_then(DataEncryptionAlgorithm::from_bytes) {
+        Some(t) => { algorithms.push(t); },
+        None => { break; },
+      }
+      offset += length;
+    }
+
+    Some(DataEncryptionCapabilities {
+      extdecc: (buf[4] >> 2) & 0x3,
+      cfg_p: buf[4] & 0x3,
+      algorithms,
+    })
+  }
+
+  pub fn algorithm(&self, algorithm_index: u8) -> Option <&DataEncryptionAlgorithm> {
+    self.algorithms.iter().find(|t| t.algorithm_index == algorithm_index)
+  }
+}
+
+/// SSC-4 Section 8.5.2.5 Data Encryption Status page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataEncryptionStatus {
+  pub i_t_nexus_scope: u8,
+  pub key_scope: u8,
+  pub encryption_mode: u8,
+  pub decryption_mode: u8,
+  pub algorithm_index: u8,
+  pub key_instance_counter: u32,
+  pub parameters_control: u8,
+  /// Volume contains encrypted logical blocks
+  pub vcelb: bool,
+  /// Check external encryption mode status
+  pub ceems: u8,
+  /// Raw decryption mode disabled
+  pub rdmd: bool,
+  pub kad: Vec <KeyAssociatedData>,
+}
+
+impl DataEncryptionStatus {
+  pub fn from_bytes(buf: &[u8]) -> Option <DataEncryptionStatus> {
+    let buf = page_header(buf, DATA_ENCRYPTION_STATUS)?;
+    if buf.len() < 24 {
+      return None;
+    }
+
+    Some(DataEncryptionStatus {
+      i_t_nexus_scope: buf[4] >> 5,
+      key_scope: buf[4] & 0x07,
+      encryption_mode: buf[5],
+      decryption_mode: buf[6],
+      algorithm_index: buf[7],
+      key_instance_counter: u32::from_be_bytes(buf[8..12].try_into().unwrap()),
+      parameters_control: (buf[12] >> 4) & 0x07,
+      vcelb: buf[12] & 0x08 == 0x08,
+      ceems: (buf[12] >> 1) & 0x03,
+      rdmd: buf[12] & 0x01 == 0x01,
+      kad: KeyAssociatedData::list_from_bytes(&buf[24..]),
+    })
+  }
+
+  pub fn is_encrypting(&self) -> bool {
+    self.encryption_mode != ENCRYPTION_MODE_DISABLE
+  }
+}
+
+impl fmt::Display for DataEncryptionStatus {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let encryption_mode = match self.encryption_mode {
+      ENCRYPTION_MODE_DISABLE => { "Disable" },
+      ENCRYPTION_MODE_EXTERNAL => { "External" },
+      ENCRYPTION_MODE_ENCRYPT => { "Encrypt" },
+      _ => { "Unknown" },
+    };
+    let decryption_mode = match self.decryption_mode {
+      DECRYPTION_MODE_DISABLE => { "Disable" },
+      DECRYPTION_MODE_RAW => { "Raw" },
+      DECRYPTION_MODE_DECRYPT => { "Decrypt" },
+      DECRYPTION_MODE_MIXED => { "Mixed" },
+      _ => { "Unknown" },
+    };
+
+    writeln!(f, "Encryption Mode: {}", encryption_mode)?;
+    writeln!(f, "Decryption Mode: {}", decryption_mode)?;
+    writeln!(f, "Algorithm Index: {}", self.algorithm_index)?;
+    writeln!(f, "Key Instance Counter: {}", self.key_instance_counter)?;
+    writeln!(f, "Volume Contains Encrypted Logical Blocks: {}", self.vcelb)?;
+    for t in &self.kad {
+      writeln!(f, "KAD {}: {}", t.kad_type, t.as_string())?;
+    }
+    Ok(())
+  }
+}
+
+/// SSC-4 Section 8.5.2.6 Next Block Encryption Status page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NextBlockEncryptionStatus {
+  pub logical_object_number: u64,
+  pub compression_status: u8,
+  pub encryption_status: u8,
+  pub algorithm_index: u8,
+  /// Encryption mode external status
+  pub emes: bool,
+  /// Raw decryption mode disabled status
+  pub rdmds: bool,
+  pub kad_format: u8,
+  pub kad: Vec <KeyAssociatedData>,
+}
+
+impl NextBlockEncryptionStatus {
+  pub const UNABLE_TO_DETERMINE: u8 = 0x0;
+  pub const NOT_A_LOGICAL_BLOCK: u8 = 0x2;
+  pub const NOT_ENCRYPTED: u8 = 0x3;
+  pub const ENCRYPTED_UNSUPPORTED_ALGORITHM: u8 = 0x4;
+  pub const ENCRYPTED: u8 = 0x5;
+  /// Encrypted with a supported algorithm, but the key is not available
+  pub const ENCRYPTED_KEY_NOT_AVAILABLE: u8 = 0x6;
+
+  pub fn from_bytes(buf: &[u8]) -> Option <NextBlockEncryptionStatus> {
+    let buf = page_header(buf, NEXT_BLOCK_ENCRYPTION_STATUS)?;
+    if buf.len() < 16 {
+      return None;
+    }
+
+    Some(NextBlockEncryptionStatus {
+      logical_object_number: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      compression_status: buf[12] >> 4,
+      encryption_status: buf[12] & 0x0F,
+      algorithm_index: buf[13],
+      emes: buf[14] & 0x02 == 0x02,
+      rdmds: buf[14] & 0x01 == 0x01,
+      kad_format: buf[15],
+      kad: KeyAssociatedData::list_from_bytes(&buf[16..]),
+    })
+  }
+}
+
+/// SSC-4 Section 8.5.2.8 Device Server Key Wrapping Public Key page
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyWrappingPublicKey {
+  pub public_key_type: u32,
+  pub public_key_format: u32,
+  pub public_key: Vec <u8>,
+}
+
+impl KeyWrappingPublicKey {
+  pub const TYPE_RSA_2048: u32 = 0x0000_0000;
+  pub const TYPE_ECC_521: u32 = 0x0000_0010;
+
+  pub fn from_bytes(buf: &[u8]) -> Option <KeyWrappingPublicKey> {
+    let buf = page_header(buf, DEVICE_SERVER_KEY_WRAPPING_PUBLIC_KEY)?;
+    if buf.len() < 14 {
+      return None;
+    }
+
+    let length = u16::from_be_bytes([buf[12], buf[13]]) as usize;
+    Some(KeyWrappingPublicKey {
+      public_key_type: u32::from_be_bytes(buf[4..8].try_into().unwrap()),
+      public_key_format: u32::from_be_bytes(buf[8..12].try_into().unwrap()),
+      public_key: buf.get(14..(14 + length))?.to_vec(),
+    })
+  }
+}
+
+/// Any page returned by SECURITY PROTOCOL IN for the Tape Data Encryption
+/// security protocol
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TapeEncryptionPage {
+  InSupportPages(Vec <u16>),
+  OutSupportPages(Vec <u16>),
+  DataEncryptionCapabilities(DataEncryptionCapabilities),
+  DataEncryptionStatus(DataEncryptionStatus),
+  NextBlockEncryptionStatus(NextBlockEncryptionStatus),
+  KeyWrappingPublicKey(KeyWrappingPublicKey),
+  Unknown { page_code: u16, data: Vec <u8> },
+}
+
+fn supported_pages(buf: &[u8], page_code: u16) -> Option <Vec <u16>> {
+  let buf = page_header(buf, page_code)?;
+  Some(buf[4..].chunks_exact(2).map(|t| u16::from_be_bytes([t[0], t[1]])).collect())
+}
+
+impl TapeEncryptionPage {
+  pub fn from_bytes(buf: &[u8]) -> Option <TapeEncryptionPage> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let page_code = u16::from_be_bytes([buf[0], buf[1]]);
+    let rv = match page_code {
+      IN_SUPPORT_PAGES => {
+        TapeEncryptionPage::InSupportPages(supported_pages(buf, page_code)?)
+      },
+      OUT_SUPPORT_PAGES => {
+        TapeEncryptionPage::OutSupportPages(supported_pages(buf, page_code)?)
+      },
+      DATA_ENCRYPTION_CAPABILITIES => {
+        TapeEncryptionPage::DataEncryptionCapabilities(DataEncryptionCapabilities::from_bytes(buf)?)
+      },
+      DATA_ENCRYPTION_STATUS => {
+        TapeEncryptionPage::DataEncryptionStatus(DataEncryptionStatus::from_bytes(buf)?)
+      },
+      NEXT_BLOCK_ENCRYPTION_STATUS => {
+        TapeEncryptionPage::NextBlockEncryptionStatus(NextBlockEncryptionStatus::from_bytes(buf)?)
+      },
+      DEVICE_SERVER_KEY_WRAPPING_PUBLIC_KEY => {
+        TapeEncryptionPage::KeyWrappingPublicKey(KeyWrappingPublicKey::from_bytes(buf)?)
+      },
+      _ => {
+        TapeEncryptionPage::Unknown {
+          page_code,
+          data: page_header(buf, page_code)?[4..].to_vec(),
+        }
+      },
+    };
+
+    Some(rv)
+  }
+}
+
+/// SSC-4 Section 8.5.4.4 wrapped key, sent as the key of a Set Data
+/// Encryption page with KEY_FORMAT_WRAPPED
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WrappedKey {
+  pub parameter_set: u16,
+  pub label: Vec <u8>,
+  pub wrapped_key: Vec <u8>,
+  pub signature: Vec <u8>,
+}
+
+impl WrappedKey {
+  /// RSA-2048 wrapping with RSAES-OAEP
+  pub const PARAMETER_SET_RSA_2048: u16 = 0x0001;
+
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let mut rv = self.parameter_set.to_be_bytes().to_vec();
+    for t in &[&self.label, &self.wrapped_key, &self.signature] {
+      rv.extend_from_slice(&(t.len() as u16).to_be_bytes());
+      rv.extend_from_slice(t);
+    }
+    rv
+  }
+}
+
+/// SSC-4 Section 8.5.4.2 Set Data Encryption page. The key is left out of
+/// Debug and serde output and is overwritten when the page is dropped.
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetDataEncryption {
+  pub scope: u8,
+  pub lock: bool,
+  /// Check external encryption mode
+  pub ceem: u8,
+  /// Raw decryption mode control
+  pub rdmc: u8,
+  /// Supplemental decryption key
+  pub sdk: bool,
+  /// Clear key on demount
+  pub ckod: bool,
+  /// Clear key on reservation preempt
+  pub ckorp: bool,
+  /// Clear key on reservation loss
+  pub ckorl: bool,
+  pub encryption_mode: u8,
+  pub decryption_mode: u8,
+  pub algorithm_index: u8,
+  pub key_format: u8,
+  pub kad_format: u8,
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub key: Vec <u8>,
+  pub kad: Vec <KeyAssociatedData>,
+}
+
+impl SetDataEncryption {
+  /// Encrypt and decrypt with a plain text key for the I_T nexus. `label`
+  /// is sent as an unauthenticated KAD so the key can be looked up later.
+  pub fn enable(algorithm_index: u8, key: &[u8], label: Option <&str>) -> SetDataEncryption {
+    let mut rv = SetDataEncryption::disable();
+    rv.ckod = true;
+    rv.encryption_mode = ENCRYPTION_MODE_ENCRYPT;
+    rv.decryption_mode = DECRYPTION_MODE_DECRYPT;
+    rv.algorithm_index = algorithm_index;
+    rv.key_format = KEY_FORMAT_PLAIN_TEXT;
+    rv.key = key.to_vec();
+    rv.kad = label.map(|t| vec![KeyAssociatedData {
+      kad_type: KeyAssociatedData::UKAD,
+      authenticated: 0,
+      descriptor: t.as_bytes().to_vec(),
+    }]).unwrap_or_default();
+    rv
+  }
+
+  /// Stop encrypting and decrypting, clearing the key
+  pub fn disable() -> SetDataEncryption {
+    let mut rv = SetDataEncryption::default();
+    rv.scope = SCOPE_LOCAL;
+    rv
+  }
+
+  /// Encode the page. The buffer holds the key; it is allocated once so no
+  /// copy is left behind by a reallocation, and should be wiped after use.
+  pub fn to_bytes(&self) -> Vec <u8> {
+    let kad: Vec <Vec <u8>> = self.kad.iter().map(|t| t.to_bytes()).collect();
+    let mut rv = Vec::with_capacity(20 + self.key.len() + kad.iter().map(|t| t.len()).sum::<usize>());
+    rv.resize(20, 0);
+    rv[0..2].copy_from_slice(&SET_DATA_ENCRYPTION.to_be_bytes());
+    rv[4] = (self.scope << 5) | if self.lock { 0x01 } else { 0x00 };
+    rv[5] = ((self.ceem & 0x3) << 6) | ((self.rdmc & 0x3) << 4);
+    rv[5] |= if self.sdk { 0x08 } else { 0x00 };
+    rv[5] |= if self.ckod { 0x04 } else { 0x00 };
+    rv[5] |= if self.ckorp { 0x02 } else { 0x00 };
+    rv[5] |= if self.ckorl { 0x01 } else { 0x00 };
+    rv[6] = self.encryption_mode;
+    rv[7] = self.decryption_mode;
+    rv[8] = self.algorithm_index;
+    rv[9] = self.key_format;
+    rv[10] = self.kad_format;
+    rv[18..20].copy_from_slice(&(self.key.len() as u16).to_be_bytes());
+    rv.extend_from_slice(&self.key);
+    for t in &kad {
+      rv.extend_from_slice(t);
+    }
+
+    let length = (rv.len() - 4) as u16;
+    rv[2..4].copy_from_slice(&length.to_be_bytes());
+    rv
+  }
+}
+
+impl fmt::Debug for SetDataEncryption {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("SetDataEncryption")
+        .field("scope", &self.scope)
+        .field("lock", &self.lock)
+        .field("ceem", &self.ceem)
+        .field("rdmc", &self.rdmc)
+        .field("sdk", &self.sdk)
+        .field("ckod", &self.ckod)
+        .field("ckorp", &self.ckorp)
+        .field("ckorl", &self.ckorl)
+        .field("encryption_mode", &self.encryption_mode)
+        .field("decryption_mode", &self.decryption_mode)
+        .field("algorithm_index", &self.algorithm_index)
+        .field("key_format", &self.key_format)
+        .field("kad_format", &self.kad_format)
+        .field("key", &format_args!("<{} bytes redacted>", self.key.len()))
+        .field("kad", &self.kad)
+        .finish()
+  }
+}
+
+impl Drop for SetDataEncryption {
+  fn drop(&mut self) {
+    wipe(&mut self.key);
+  }
+}
+
+/// Overwrite key material so it does not linger in freed memory. Volatile
+/// writes keep the compiler from dropping the stores to a dead buffer.
+fn wipe(buf: &mut [u8]) {
+  for t in buf.iter_mut() {
+    unsafe { std::ptr::write_volatile(t, 0); }
+  }
+  std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Read one Tape Data Encryption page with SECURITY PROTOCOL IN
+pub fn read_encryption_page(device: &Device, page_code: u16)
+    -> Result <TapeEncryptionPage, String> {
+  let cmd = SecurityProtocolIn {
+    security_protocol: SecurityProtocolIn::TAPE_DATA_ENCRYPTION,
+    security_protocol_specific: page_code,
+    inc_512: false,
+    allocation_length: 0x2000,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("SECURITY PROTOCOL IN", status)?;
+
+  TapeEncryptionPage::from_bytes(&buf)
+      .ok_or_else(|| format!("Could not parse tape data encryption page {:#06x}", page_code))
+}
+
+pub fn read_encryption_status(device: &Device) -> Result <DataEncryptionStatus, String> {
+  match read_encryption_page(device, DATA_ENCRYPTION_STATUS)? {
+    TapeEncryptionPage::DataEncryptionStatus(t) => { Ok(t) },
+    t => { Err(format!("Unexpected page {:?}", t)) },
+  }
+}
+
+pub fn read_encryption_capabilities(device: &Device)
+    -> Result <DataEncryptionCapabilities, String> {
+  match read_encryption_page(device, DATA_ENCRYPTION_CAPABILITIES)? {
+    TapeEncryptionPage::DataEncryptionCapabilities(t) => { Ok(t) },
+    t => { Err(format!("Unexpected page {:?}", t)) },
+  }
+}
+
+pub fn read_next_block_encryption_status(device: &Device)
+    -> Result <NextBlockEncryptionStatus, String> {
+  match read_encryption_page(device, NEXT_BLOCK_ENCRYPTION_STATUS)? {
+    TapeEncryptionPage::NextBlockEncryptionStatus(t) => { Ok(t) },
+    t => { Err(format!("Unexpected page {:?}", t)) },
+  }
+}
+
+/// Send a Set Data Encryption page with SECURITY PROTOCOL OUT. The page is
+/// consumed so the key is wiped as soon as it has been sent.
+pub fn set_data_encryption(device: &Device, page: SetDataEncryption)
+    -> Result <DeviceStatus, String> {
+  let mut buf = page.to_bytes();
+  drop(page);
+  let cmd = SecurityProtocolOut {
+    security_protocol: SecurityProtocolIn::TAPE_DATA_ENCRYPTION,
+    security_protocol_specific: SET_DATA_ENCRYPTION,
+    inc_512: false,
+    transfer_length: buf.len() as u32,
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, &buf);
+  wipe(&mut buf);
+  check_good("SECURITY PROTOCOL OUT", status?)
+}
+
+#[test]
+fn tape_encryption_test() {
+  let page = SetDataEncryption::enable(1, &[0xAA; 32], Some("key1"));
+  let buf = page.to_bytes();
+  assert_eq!(buf.len(), 20 + 32 + 8);
+  assert_eq!(&buf[0..12], &[
+    0x00, 0x10, 0x00, 0x38, 0x20, 0x04, 0x02, 0x02, 0x01, 0x00, 0x00, 0x00,
+  ]);
+  assert_eq!(&buf[18..20], &[0x00, 0x20]);
+  assert_eq!(&buf[52..], &[0x00, 0x00, 0x00, 0x04, b'k', b'e', b'y', b'1']);
+
+  let mut buf = vec![
+    0x00, 0x20, 0x00, 0x1C, 0x42, 0x02, 0x02, 0x01,
+    0x00, 0x00, 0x00, 0x07, 0x08, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+  buf.extend_from_slice(&[0x00, 0x00, 0x00, 0x04, b'k', b'e', b'y', b'1']);
+  let status = match TapeEncryptionPage::from_bytes(&buf) {
+    Some(TapeEncryptionPage::DataEncryptionStatus(t)) => { t },
+    t => { panic!("Parsed as {:?}", t); },
+  };
+  assert!(status.is_encrypting());
+  assert!(status.vcelb);
+  assert_eq!(status.i_t_nexus_scope, SCOPE_ALL_I_T_NEXUS);
+  assert_eq!(status.key_instance_counter, 7);
+  assert_eq!(status.kad[0].as_string(), "key1");
+
+  let mut buf = vec![0x00, 0x10, 0x00, 0x28, 0x09];
+  buf.extend_from_slice(&[0; 15]);
+  buf.extend_from_slice(&[
+    0x01, 0x00, 0x00, 0x14, 0x8A, 0x8F, 0x00, 0x20,
+    0x00, 0x0C, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x14,
+  ]);
+  let capabilities = DataEncryptionCapabilities::from_bytes(&buf).unwrap();
+  assert_eq!(capabilities.extdecc, 2);
+  let algorithm = capabilities.algorithm(1).unwrap();
+  assert!(algorithm.can_encrypt() && algorithm.can_decrypt());
+  assert_eq!(algorithm.key_length, 32);
+  assert_eq!(algorithm.security_algorithm_code, DataEncryptionAlgorithm::AES_256_GCM);
+}
+
+#[test]
+fn set_data_encryption_key_test() {
+  let page = SetDataEncryption::enable(1, &[0xAA; 32], Some("key1"));
+  let debug = format!("{:?}", page);
+  assert!(debug.contains("<32 bytes redacted>"));
+  assert!(!debug.contains("170"));
+
+  let mut buf = page.to_bytes();
+  wipe(&mut buf);
+  assert!(buf.iter().all(|t| *t == 0));
+
+  #[cfg(feature = "json")]
+  {
+    let json = serde_json::to_string(&page).unwrap();
+    assert!(!json.contains("\"key\""));
+  }
+}
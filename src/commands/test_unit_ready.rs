@@ -0,0 +1,45 @@
+use crate::sense::Sense;
+use crate::commands::MediumOperationSenseInfo;
+
+/// SPC-3 Section 6.33
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestUnitReady {
+}
+
+impl TestUnitReady {
+  const OP_CODE: u8 = 0x00;
+
+  pub fn new() -> TestUnitReady {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <TestUnitReady> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(TestUnitReady {
+    })
+  }
+}
+
+impl crate::NoIO for TestUnitReady {
+}
+
+impl crate::Command for TestUnitReady {
+  type SenseInfo = MediumOperationSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 6];
+
+    data[0] = Self::OP_CODE;
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> MediumOperationSenseInfo {
+    MediumOperationSenseInfo::from_sense(sense)
+  }
+}
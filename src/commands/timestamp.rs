@@ -0,0 +1,140 @@
+use crate::commands::ReportTimestamp;
+use crate::commands::SetTimestamp;
+use crate::device::{Device, DeviceStatus, check_good};
+use std::convert::TryInto;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// SPC-4 Section 5.2 device clock, milliseconds since an origin
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timestamp {
+  pub timestamp_origin: u8,
+  /// 48 bit count of milliseconds
+  pub timestamp: u64,
+}
+
+impl Timestamp {
+  /// Length of the REPORT TIMESTAMP and SET TIMESTAMP parameter data
+  pub const LEN: usize = 12;
+  pub const MAX: u64 = (1 << 48) - 1;
+
+  /// Zeroed at power on or hard reset, the timestamp is the time since then
+  pub const ORIGIN_POWER_ON: u8 = 0x0;
+  pub const ORIGIN_SET_TIMESTAMP: u8 = 0x2;
+  pub const ORIGIN_OTHER: u8 = 0x3;
+
+  /// REPORT TIMESTAMP parameter data
+  pub fn from_bytes(buf: &[u8]) -> Option <Timestamp> {
+    if buf.len() < Self::LEN {
+      return None;
+    }
+
+    Some(Timestamp {
+      timestamp_origin: buf[2] & 0x07,
+      timestamp: u64::from_be_bytes([0, 0, buf[4], buf[5], buf[6], buf[7], buf[8], buf[9]]),
+    })
+  }
+
+  /// SET TIMESTAMP parameter list
+  pub fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    if self.timestamp > Self::MAX {
+      return Err("Timestamp must be in the range 0..2^48");
+    }
+
+    let mut data = vec![0; Self::LEN];
+    data[4..10].copy_from_slice(&self.timestamp.to_be_bytes()[2..]);
+
+    Ok(data)
+  }
+
+  /// Milliseconds since the Unix epoch, None if time is before the epoch
+  /// or beyond what 48 bits can hold
+  pub fn from_system_time(time: SystemTime) -> Option <Timestamp> {
+    let millis: u64 = time.duration_since(UNIX_EPOCH).ok()?.as_millis().try_into().ok()?;
+    if millis > Self::MAX {
+      return None;
+    }
+
+    Some(Timestamp {
+      timestamp_origin: Self::ORIGIN_SET_TIMESTAMP,
+      timestamp: millis,
+    })
+  }
+
+  pub fn now() -> Option <Timestamp> {
+    Self::from_system_time(SystemTime::now())
+  }
+
+  /// Wall clock time, None when the clock only counts from power on
+  pub fn to_system_time(&self) -> Option <SystemTime> {
+    match self.timestamp_origin {
+      Self::ORIGIN_POWER_ON => { None },
+      _ => { UNIX_EPOCH.checked_add(self.as_duration()) },
+    }
+  }
+
+  pub fn as_duration(&self) -> Duration {
+    Duration::from_millis(self.timestamp)
+  }
+}
+
+impl fmt::Display for Timestamp {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let origin = match self.timestamp_origin {
+      Self::ORIGIN_POWER_ON => { "power on" },
+      Self::ORIGIN_SET_TIMESTAMP => { "SET TIMESTAMP" },
+      Self::ORIGIN_OTHER => { "other" },
+      _ => { "reserved" },
+    };
+    writeln!(f, "Timestamp: {} ms", self.timestamp)?;
+    writeln!(f, "Origin: {}", origin)
+  }
+}
+
+pub fn report_timestamp(device: &Device) -> Result <Timestamp, String> {
+  let cmd = ReportTimestamp::new();
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+  let status = device.issue_cmd_with_output(&cmd, &mut buf)?;
+  check_good("REPORT TIMESTAMP", status)?;
+
+  cmd.parse_buffer(&buf).ok_or_else(|| "Could not parse timestamp".to_string())
+}
+
+pub fn set_timestamp(device: &Device, time: SystemTime) -> Result <DeviceStatus, String> {
+  let timestamp = Timestamp::from_system_time(time)
+      .ok_or_else(|| format!("{:?} cannot be held in a timestamp", time))?;
+  let buf = timestamp.to_bytes().map_err(|e| e.to_string())?;
+  let cmd = SetTimestamp {
+    parameter_list_length: buf.len() as u32,
+  };
+
+  let status = device.issue_cmd_with_input(&cmd, &buf)?;
+  check_good("SET TIMESTAMP", status)
+}
+
+/// Set the device clock to the host clock
+pub fn sync_timestamp(device: &Device) -> Result <DeviceStatus, String> {
+  set_timestamp(device, SystemTime::now())
+}
+
+#[test]
+fn timestamp_test() {
+  let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+  let timestamp = Timestamp::from_system_time(time).unwrap();
+  assert_eq!(timestamp.to_bytes().unwrap(), vec![
+    0x00, 0x00, 0x00, 0x00, 0x01, 0x8B, 0xCF, 0xE5, 0x68, 0x7B, 0x00, 0x00,
+  ]);
+  assert_eq!(timestamp.to_system_time(), Some(time));
+
+  let buf = [0x00, 0x0A, 0x02, 0x00, 0x01, 0x8B, 0xCF, 0xE5, 0x68, 0x7B, 0x00, 0x00];
+  assert_eq!(Timestamp::from_bytes(&buf), Some(timestamp));
+
+  let buf = [0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x27, 0x10, 0x00, 0x00];
+  let uptime = Timestamp::from_bytes(&buf).unwrap();
+  assert_eq!(uptime.as_duration(), Duration::from_secs(10));
+  assert_eq!(uptime.to_system_time(), None);
+
+  assert!(Timestamp { timestamp: 1 << 48, ..Default::default() }.to_bytes().is_err());
+}
@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::VerifySenseInfo;
 
 /// SSC-4 Section 5.5
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Verify16 {
   pub vte: bool,
   pub vlbpm: bool,
@@ -20,12 +23,33 @@ impl Verify16 {
   pub fn new() -> Verify16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Verify16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Verify16 {
+      vte: buf[1] & 0x20 == 0x20,
+      vlbpm: buf[1] & 0x10 == 0x10,
+      vbf: buf[1] & 0x08 == 0x08,
+      immed: buf[1] & 0x04 == 0x04,
+      bytcmp: buf[1] & 0x02 == 0x02,
+      fixed: buf[1] & 0x01 == 0x01,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      verification_length: u32::from_be_bytes([0, buf[12], buf[13], buf[14]]),
+    })
+  }
 }
 
 impl crate::Input for Verify16 {
 }
 
 impl crate::Command for Verify16 {
+  type SenseInfo = VerifySenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -48,7 +72,7 @@ impl crate::Command for Verify16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> VerifySenseInfo {
+    VerifySenseInfo::from_sense(sense, self.fixed)
   }
 }
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::VerifySenseInfo;
 
 /// SSC-4 Section 6.7
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Verify6 {
   pub vte: bool,
   pub vlbpm: bool,
@@ -18,12 +20,31 @@ impl Verify6 {
   pub fn new() -> Verify6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Verify6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Verify6 {
+      vte: buf[1] & 0x20 == 0x20,
+      vlbpm: buf[1] & 0x10 == 0x10,
+      vbf: buf[1] & 0x08 == 0x08,
+      immed: buf[1] & 0x04 == 0x04,
+      bytcmp: buf[1] & 0x02 == 0x02,
+      fixed: buf[1] & 0x01 == 0x01,
+      verification_length: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::Input for Verify6 {
 }
 
 impl crate::Command for Verify6 {
+  type SenseInfo = VerifySenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -44,7 +65,7 @@ impl crate::Command for Verify6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> VerifySenseInfo {
+    VerifySenseInfo::from_sense(sense, self.fixed)
   }
 }
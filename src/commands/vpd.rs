@@ -0,0 +1,459 @@
+use crate::commands::TapeAlertFlags;
+use std::fmt;
+
+/// Vital product data returned by an INQUIRY with EVPD set
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VpdPage {
+  /// SPC-3 Section 7.6.13
+  SupportedPages(Vec <u8>),
+  /// SPC-3 Section 7.6.12
+  UnitSerialNumber(String),
+  /// SPC-3 Section 7.6.3
+  DeviceIdentification(DeviceIdentification),
+  /// SPC-3 Section 7.6.4
+  ExtendedInquiryData(ExtendedInquiryData),
+  /// SSC-4 Section 8.4
+  SequentialAccessDeviceCapabilities(SequentialAccessDeviceCapabilities),
+  /// SSC-4 Section 8.4
+  ManufacturerAssignedSerialNumber(String),
+  /// SSC-4 Section 8.4
+  TapeAlertSupportedFlags(TapeAlertFlags),
+  /// SSC-4 Section 8.4
+  AutomationDeviceSerialNumber(String),
+  /// Any page without a typed parser
+  Unknown { page_code: u8, data: Vec <u8> },
+}
+
+impl VpdPage {
+  pub const SUPPORTED_PAGES: u8 = 0x00;
+  pub const UNIT_SERIAL_NUMBER: u8 = 0x80;
+  pub const DEVICE_IDENTIFICATION: u8 = 0x83;
+  pub const EXTENDED_INQUIRY_DATA: u8 = 0x86;
+  pub const SEQUENTIAL_ACCESS_DEVICE_CAPABILITIES: u8 = 0xB0;
+  pub const MANUFACTURER_ASSIGNED_SERIAL_NUMBER: u8 = 0xB1;
+  pub const TAPEALERT_SUPPORTED_FLAGS: u8 = 0xB2;
+  pub const AUTOMATION_DEVICE_SERIAL_NUMBER: u8 = 0xB3;
+
+  /// Parse a VPD page including its 4 byte header. The page length field is
+  /// honoured, but the buffer may be shorter if the allocation length was.
+  pub fn from_bytes(buf: &[u8]) -> Option <VpdPage> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let page_code = buf[1];
+    let page_length = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+    let end = std::cmp::min(buf.len(), page_length + 4);
+    let data = &buf[4..end];
+
+    match page_code {
+      Self::SUPPORTED_PAGES => {
+        Some(VpdPage::SupportedPages(data.to_vec()))
+      },
+      Self::UNIT_SERIAL_NUMBER => {
+        Some(VpdPage::UnitSerialNumber(ascii_field(data)))
+      },
+      Self::DEVICE_IDENTIFICATION => {
+        Some(VpdPage::DeviceIdentification(DeviceIdentification::from_bytes(data)))
+      },
+      Self::EXTENDED_INQUIRY_DATA => {
+        ExtendedInquiryData::from_bytes(data).map(VpdPage::ExtendedInquiryData)
+      },
+      Self::SEQUENTIAL_ACCESS_DEVICE_CAPABILITIES => {
+        SequentialAccessDeviceCapabilities::from_bytes(data)
+            .map(VpdPage::SequentialAccessDeviceCapabilities)
+      },
+      Self::MANUFACTURER_ASSIGNED_SERIAL_NUMBER => {
+        Some(VpdPage::ManufacturerAssignedSerialNumber(ascii_field(data)))
+      },
+      Self::TAPEALERT_SUPPORTED_FLAGS => {
+        TapeAlertFlags::from_bytes(data).map(VpdPage::TapeAlertSupportedFlags)
+      },
+      Self::AUTOMATION_DEVICE_SERIAL_NUMBER => {
+        Some(VpdPage::AutomationDeviceSerialNumber(ascii_field(data)))
+      },
+      _ => {
+        Some(VpdPage::Unknown { page_code, data: data.to_vec() })
+      },
+    }
+  }
+}
+
+impl fmt::Display for VpdPage {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      VpdPage::SupportedPages(t) => {
+        let pages: Vec <String> = t.iter().map(|p| format!("{:#04x}", p)).collect();
+        writeln!(f, "Supported VPD Pages: {}", pages.join(" "))
+      },
+      VpdPage::UnitSerialNumber(t) => { writeln!(f, "Unit Serial Number: {}", t) },
+      VpdPage::DeviceIdentification(t) => { write!(f, "{}", t) },
+      VpdPage::ExtendedInquiryData(t) => { write!(f, "{}", t) },
+      VpdPage::SequentialAccessDeviceCapabilities(t) => { write!(f, "{}", t) },
+      VpdPage::ManufacturerAssignedSerialNumber(t) => {
+        writeln!(f, "Manufacturer-assigned Serial Number: {}", t)
+      },
+      VpdPage::TapeAlertSupportedFlags(t) => { writeln!(f, "TapeAlert Supported Flags: {}", t) },
+      VpdPage::AutomationDeviceSerialNumber(t) => {
+        writeln!(f, "Automation Device Serial Number: {}", t)
+      },
+      VpdPage::Unknown { page_code, data } => {
+        writeln!(f, "VPD Page {:#04x}: {:02x?}", page_code, data)
+      },
+    }
+  }
+}
+
+pub(crate) fn ascii_field(field: &[u8]) -> String {
+  String::from_utf8_lossy(field).trim_matches(|c: char| c == '\0' || c.is_whitespace())
+      .to_string()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SPC-3 Section 7.6.3.1
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceIdentification {
+  pub designators: Vec <Designator>,
+}
+
+impl DeviceIdentification {
+  fn from_bytes(buf: &[u8]) -> DeviceIdentification {
+    let mut designators = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= buf.len() {
+      let length = buf[offset + 3] as usize;
+      let end = offset + 4 + length;
+      if end > buf.len() {
+        break;
+      }
+      designators.push(Designator::from_bytes(&buf[offset..end]));
+      offset = end;
+    }
+
+    DeviceIdentification { designators }
+  }
+
+  /// The NAA designator associated with the logical unit, as a hex string.
+  /// This is the identifier that stays with the drive across paths.
+  pub fn logical_unit_naa(&self) -> Option <String> {
+    self.designators.iter()
+        .filter(|d| d.association == Designator::ASSOCIATION_LOGICAL_UNIT)
+        .find_map(|d| match &d.identifier {
+          DesignatorIdentifier::Naa(t) => { Some(hex_string(t)) },
+          _ => { None },
+        })
+  }
+}
+
+impl fmt::Display for DeviceIdentification {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Device Identification:")?;
+    for designator in &self.designators {
+      writeln!(f, "  {}", designator)?;
+    }
+    Ok(())
+  }
+}
+
+/// SPC-3 Section 7.6.3.1 identification descriptor
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Designator {
+  pub protocol_identifier: u8,
+  pub code_set: u8,
+  pub piv: bool,
+  pub association: u8,
+  pub designator_type: u8,
+  pub identifier: DesignatorIdentifier,
+}
+
+/// Designator value decoded according to its designator type
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DesignatorIdentifier {
+  VendorSpecific(Vec <u8>),
+  T10VendorId { vendor_identification: String, vendor_specific: String },
+  Eui64(Vec <u8>),
+  Naa(Vec <u8>),
+  RelativeTargetPort(u16),
+  TargetPortGroup(u16),
+  LogicalUnitGroup(u16),
+  Md5LogicalUnit(Vec <u8>),
+  ScsiName(String),
+  Other(Vec <u8>),
+}
+
+impl Designator {
+  pub const CODE_SET_BINARY: u8 = 0x1;
+  pub const CODE_SET_ASCII: u8 = 0x2;
+  pub const CODE_SET_UTF8: u8 = 0x3;
+  pub const ASSOCIATION_LOGICAL_UNIT: u8 = 0x0;
+  pub const ASSOCIATION_TARGET_PORT: u8 = 0x1;
+  pub const ASSOCIATION_TARGET_DEVICE: u8 = 0x2;
+  pub const TYPE_VENDOR_SPECIFIC: u8 = 0x0;
+  pub const TYPE_T10_VENDOR_ID: u8 = 0x1;
+  pub const TYPE_EUI_64: u8 = 0x2;
+  pub const TYPE_NAA: u8 = 0x3;
+  pub const TYPE_RELATIVE_TARGET_PORT: u8 = 0x4;
+  pub const TYPE_TARGET_PORT_GROUP: u8 = 0x5;
+  pub const TYPE_LOGICAL_UNIT_GROUP: u8 = 0x6;
+  pub const TYPE_MD5_LOGICAL_UNIT: u8 = 0x7;
+  pub const TYPE_SCSI_NAME: u8 = 0x8;
+
+  /// Parse one descriptor; `buf` covers exactly the header and designator
+  fn from_bytes(buf: &[u8]) -> Designator {
+    let value = &buf[4..];
+    let designator_type = buf[1] & 0x0F;
+    let port_value = || if value.len() >= 4 {
+      u16::from_be_bytes([value[2], value[3]])
+    } else {
+      0
+    };
+
+    let identifier = match designator_type {
+      Self::TYPE_VENDOR_SPECIFIC => { DesignatorIdentifier::VendorSpecific(value.to_vec()) },
+      Self::TYPE_T10_VENDOR_ID => {
+        let split = std::cmp::min(value.len(), 8);
+        DesignatorIdentifier::T10VendorId {
+          vendor_identification: ascii_field(&value[..split]),
+          vendor_specific: ascii_field(&value[split..]),
+        }
+      },
+      Self::TYPE_EUI_64 => { DesignatorIdentifier::Eui64(value.to_vec()) },
+      Self::TYPE_NAA => { DesignatorIdentifier::Naa(value.to_vec()) },
+      Self::TYPE_RELATIVE_TARGET_PORT => {
+        DesignatorIdentifier::RelativeTargetPort(port_value())
+      },
+      Self::TYPE_TARGET_PORT_GROUP => { DesignatorIdentifier::TargetPortGroup(port_value()) },
+      Self::TYPE_LOGICAL_UNIT_GROUP => { DesignatorIdentifier::LogicalUnitGroup(port_value()) },
+      Self::TYPE_MD5_LOGICAL_UNIT => { DesignatorIdentifier::Md5LogicalUnit(value.to_vec()) },
+      Self::TYPE_SCSI_NAME => { DesignatorIdentifier::ScsiName(ascii_field(value)) },
+      _ => { DesignatorIdentifier::Other(value.to_vec()) },
+    };
+
+    Designator {
+      protocol_identifier: buf[0] >> 4,
+      code_set: buf[0] & 0x0F,
+      piv: buf[1] & 0x80 == 0x80,
+      association: (buf[1] >> 4) & 0x3,
+      designator_type,
+      identifier,
+    }
+  }
+}
+
+impl fmt::Display for Designator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let association = match self.association {
+      Self::ASSOCIATION_LOGICAL_UNIT => { "logical unit" },
+      Self::ASSOCIATION_TARGET_PORT => { "target port" },
+      Self::ASSOCIATION_TARGET_DEVICE => { "target device" },
+      _ => { "reserved" },
+    };
+    write!(f, "[{}] ", association)?;
+
+    match &self.identifier {
+      DesignatorIdentifier::VendorSpecific(t) => { write!(f, "Vendor Specific: {}", hex_string(t)) },
+      DesignatorIdentifier::T10VendorId { vendor_identification, vendor_specific } => {
+        write!(f, "T10 Vendor ID: {} {}", vendor_identification, vendor_specific)
+      },
+      DesignatorIdentifier::Eui64(t) => { write!(f, "EUI-64: 0x{}", hex_string(t)) },
+      DesignatorIdentifier::Naa(t) => { write!(f, "NAA: 0x{}", hex_string(t)) },
+      DesignatorIdentifier::RelativeTargetPort(t) => { write!(f, "Relative Target Port: {}", t) },
+      DesignatorIdentifier::TargetPortGroup(t) => { write!(f, "Target Port Group: {}", t) },
+      DesignatorIdentifier::LogicalUnitGroup(t) => { write!(f, "Logical Unit Group: {}", t) },
+      DesignatorIdentifier::Md5LogicalUnit(t) => { write!(f, "MD5 Logical Unit: {}", hex_string(t)) },
+      DesignatorIdentifier::ScsiName(t) => { write!(f, "SCSI Name: {}", t) },
+      DesignatorIdentifier::Other(t) => {
+        write!(f, "Type {:#x}: {}", self.designator_type, hex_string(t))
+      },
+    }
+  }
+}
+
+/// SPC-3 Section 7.6.4; later fields read as zero when the device returns
+/// the shorter SPC-3 page
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtendedInquiryData {
+  pub activate_microcode: u8,
+  pub spt: u8,
+  pub grd_chk: bool,
+  pub app_chk: bool,
+  pub ref_chk: bool,
+  pub uask_sup: bool,
+  pub group_sup: bool,
+  pub prior_sup: bool,
+  pub headsup: bool,
+  pub ordsup: bool,
+  pub simpsup: bool,
+  pub wu_sup: bool,
+  pub crd_sup: bool,
+  pub nv_sup: bool,
+  pub v_sup: bool,
+  pub p_i_i_sup: bool,
+  pub luiclr: bool,
+  pub r_sup: bool,
+  pub cbcs: bool,
+  pub multi_it_nexus_microcode_download: u8,
+  pub extended_self_test_completion_minutes: u16,
+  pub poa_sup: bool,
+  pub hra_sup: bool,
+  pub vsa_sup: bool,
+  pub maximum_supported_sense_data_length: u8,
+}
+
+impl ExtendedInquiryData {
+  /// `buf` starts after the 4 byte page header
+  fn from_bytes(buf: &[u8]) -> Option <ExtendedInquiryData> {
+    if buf.len() < 4 {
+      return None;
+    }
+
+    let byte = |i: usize| buf.get(i).copied().unwrap_or(0);
+
+    Some(ExtendedInquiryData {
+      activate_microcode: byte(0) >> 6,
+      spt: (byte(0) >> 3) & 0x7,
+      grd_chk: byte(0) & 0x04 == 0x04,
+      app_chk: byte(0) & 0x02 == 0x02,
+      ref_chk: byte(0) & 0x01 == 0x01,
+      uask_sup: byte(1) & 0x20 == 0x20,
+      group_sup: byte(1) & 0x10 == 0x10,
+      prior_sup: byte(1) & 0x08 == 0x08,
+      headsup: byte(1) & 0x04 == 0x04,
+      ordsup: byte(1) & 0x02 == 0x02,
+      simpsup: byte(1) & 0x01 == 0x01,
+      wu_sup: byte(2) & 0x08 == 0x08,
+      crd_sup: byte(2) & 0x04 == 0x04,
+      nv_sup: byte(2) & 0x02 == 0x02,
+      v_sup: byte(2) & 0x01 == 0x01,
+      p_i_i_sup: byte(3) & 0x10 == 0x10,
+      luiclr: byte(3) & 0x01 == 0x01,
+      r_sup: byte(4) & 0x10 == 0x10,
+      cbcs: byte(4) & 0x01 == 0x01,
+      multi_it_nexus_microcode_download: byte(5) & 0x0F,
+      extended_self_test_completion_minutes: u16::from_be_bytes([byte(6), byte(7)]),
+      poa_sup: byte(8) & 0x80 == 0x80,
+      hra_sup: byte(8) & 0x40 == 0x40,
+      vsa_sup: byte(8) & 0x20 == 0x20,
+      maximum_supported_sense_data_length: byte(9),
+    })
+  }
+}
+
+impl fmt::Display for ExtendedInquiryData {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Extended INQUIRY Data:")?;
+    writeln!(f, "  ACTIVATE_MICROCODE: {} SPT: {} GRD_CHK: {} APP_CHK: {} REF_CHK: {}",
+        self.activate_microcode, self.spt, self.grd_chk, self.app_chk, self.ref_chk)?;
+    writeln!(f, "  UASK_SUP: {} GROUP_SUP: {} PRIOR_SUP: {} HEADSUP: {} ORDSUP: {} SIMPSUP: {}",
+        self.uask_sup, self.group_sup, self.prior_sup, self.headsup, self.ordsup,
+        self.simpsup)?;
+    writeln!(f, "  WU_SUP: {} CRD_SUP: {} NV_SUP: {} V_SUP: {}",
+        self.wu_sup, self.crd_sup, self.nv_sup, self.v_sup)?;
+    writeln!(f, "  P_I_I_SUP: {} LUICLR: {} R_SUP: {} CBCS: {}",
+        self.p_i_i_sup, self.luiclr, self.r_sup, self.cbcs)?;
+    writeln!(f, "  Extended Self-Test Completion Minutes: {}",
+        self.extended_self_test_completion_minutes)?;
+    writeln!(f, "  Maximum Supported Sense Data Length: {}",
+        self.maximum_supported_sense_data_length)
+  }
+}
+
+/// SSC-4 Section 8.4
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequentialAccessDeviceCapabilities {
+  pub worm: bool,
+}
+
+impl SequentialAccessDeviceCapabilities {
+  /// `buf` starts after the 4 byte page header
+  fn from_bytes(buf: &[u8]) -> Option <SequentialAccessDeviceCapabilities> {
+    if buf.is_empty() {
+      return None;
+    }
+
+    Some(SequentialAccessDeviceCapabilities {
+      worm: buf[0] & 0x01 == 0x01,
+    })
+  }
+}
+
+impl fmt::Display for SequentialAccessDeviceCapabilities {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Sequential-Access Device Capabilities:")?;
+    writeln!(f, "  WORM: {}", self.worm)
+  }
+}
+
+#[test]
+fn device_identification_test() {
+  let buf = vec![
+    0x01, 0x83, 0x00, 0x28,
+    // T10 vendor ID, logical unit
+    0x02, 0x01, 0x00, 0x10,
+    b'I', b'B', b'M', b' ', b' ', b' ', b' ', b' ',
+    b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8',
+    // NAA, logical unit
+    0x01, 0x03, 0x00, 0x08,
+    0x50, 0x05, 0x07, 0x63, 0x12, 0x34, 0x56, 0x78,
+    // Relative target port
+    0x61, 0x94, 0x00, 0x04,
+    0x00, 0x00, 0x00, 0x01,
+  ];
+
+  let page = match VpdPage::from_bytes(&buf) {
+    Some(VpdPage::DeviceIdentification(t)) => { t },
+    t => { panic!("Decoded as {:?}", t); },
+  };
+  assert_eq!(page.designators.len(), 3);
+  match &page.designators[0].identifier {
+    DesignatorIdentifier::T10VendorId { vendor_identification, vendor_specific } => {
+      assert_eq!(vendor_identification, "IBM");
+      assert_eq!(vendor_specific, "12345678");
+    },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+  assert_eq!(page.designators[2].association, Designator::ASSOCIATION_TARGET_PORT);
+  assert!(page.designators[2].piv);
+  assert_eq!(page.designators[2].protocol_identifier, 0x6);
+  assert!(matches!(page.designators[2].identifier, DesignatorIdentifier::RelativeTargetPort(1)));
+  assert_eq!(page.logical_unit_naa(), Some("5005076312345678".to_string()));
+
+  let serial = [0x01, 0x80, 0x00, 0x0C, b' ', b' ', b'1', b'0', b'7', b'8',
+      b'0', b'0', b'0', b'1', b'2', b'3'];
+  match VpdPage::from_bytes(&serial) {
+    Some(VpdPage::UnitSerialNumber(t)) => { assert_eq!(t, "1078000123"); },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+}
+
+#[test]
+fn sequential_access_pages_test() {
+  let worm = [0x01, 0xB0, 0x00, 0x02, 0x01, 0x00];
+  match VpdPage::from_bytes(&worm) {
+    Some(VpdPage::SequentialAccessDeviceCapabilities(t)) => { assert!(t.worm); },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+
+  let flags = [0x01, 0xB2, 0x00, 0x0C, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00];
+  match VpdPage::from_bytes(&flags) {
+    Some(VpdPage::TapeAlertSupportedFlags(t)) => { assert_eq!(t.flags(), vec![1, 2]); },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+
+  let serial = [0x01, 0xB3, 0x00, 0x04, b'A', b'B', b'1', b'2'];
+  match VpdPage::from_bytes(&serial) {
+    Some(VpdPage::AutomationDeviceSerialNumber(t)) => { assert_eq!(t, "AB12"); },
+    t => { panic!("Decoded as {:?}", t); },
+  }
+}
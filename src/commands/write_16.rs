@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::WriteSenseInfo;
 
 /// SSC-4 Section 5.6
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Write16 {
   pub fcs: bool,
   pub lcs: bool,
@@ -18,12 +21,31 @@ impl Write16 {
   pub fn new() -> Write16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Write16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Write16 {
+      fcs: buf[1] & 0x8 == 0x8,
+      lcs: buf[1] & 0x4 == 0x4,
+      rsvd: buf[1] & 0x2 == 0x2,
+      fixed: buf[1] & 0x1 == 0x1,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      transfer_length: u32::from_be_bytes([0, buf[12], buf[13], buf[14]]),
+    })
+  }
 }
 
 impl crate::Input for Write16 {
 }
 
 impl crate::Command for Write16 {
+  type SenseInfo = WriteSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -44,7 +66,7 @@ impl crate::Command for Write16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> WriteSenseInfo {
+    WriteSenseInfo::from_sense(sense, self.fixed)
   }
 }
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::WriteSenseInfo;
 
 /// SSC-4 Section 6.8
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Write6 {
   pub fixed: bool,
   pub transfer_length: u32,
@@ -13,12 +15,26 @@ impl Write6 {
   pub fn new() -> Write6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <Write6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(Write6 {
+      fixed: buf[1] & 0x1 == 0x1,
+      transfer_length: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::Input for Write6 {
 }
 
 impl crate::Command for Write6 {
+  type SenseInfo = WriteSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -33,7 +49,7 @@ impl crate::Command for Write6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> WriteSenseInfo {
+    WriteSenseInfo::from_sense(sense, self.fixed)
   }
 }
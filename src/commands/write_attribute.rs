@@ -0,0 +1,59 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+use std::convert::TryInto;
+
+/// SPC-4 Section 6.46
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteAttribute {
+  /// Write through cache, the attributes reach the medium before status
+  pub wtc: bool,
+  pub logical_volume_number: u8,
+  pub partition_number: u8,
+  pub parameter_list_length: u32,
+}
+
+impl WriteAttribute {
+  const OP_CODE: u8 = 0x8D;
+
+  pub fn new() -> WriteAttribute {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <WriteAttribute> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(WriteAttribute {
+      wtc: buf[1] & 0x01 == 0x01,
+      logical_volume_number: buf[5],
+      partition_number: buf[7],
+      parameter_list_length: u32::from_be_bytes(buf[10..14].try_into().unwrap()),
+    })
+  }
+}
+
+impl crate::Input for WriteAttribute {
+}
+
+impl crate::Command for WriteAttribute {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 16];
+
+    data[0] = Self::OP_CODE;
+    data[1] = if self.wtc { 0x01 } else { 0x00 };
+    data[5] = self.logical_volume_number;
+    data[7] = self.partition_number;
+    data[10..14].copy_from_slice(&self.parameter_list_length.to_be_bytes());
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
@@ -0,0 +1,86 @@
+use crate::sense::Sense;
+use crate::commands::ParameterSenseInfo;
+
+/// SPC-4 Section 6.49
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteBuffer {
+  /// Mode specific field, the activation events in
+  /// DOWNLOAD_MICROCODE_WITH_OFFSETS_SELECT_ACTIVATION_SAVE_AND_DEFER_ACTIVATE
+  pub mode_specific: u8,
+  pub mode: u8,
+  pub buffer_id: u8,
+  pub buffer_offset: u32,
+  pub parameter_list_length: u32,
+}
+
+impl WriteBuffer {
+  const OP_CODE: u8 = 0x3B;
+  pub const COMBINED_HEADER_AND_DATA: u8 = 0x00;
+  pub const VENDOR_SPECIFIC: u8 = 0x01;
+  pub const DATA: u8 = 0x02;
+  pub const DOWNLOAD_MICROCODE_AND_ACTIVATE: u8 = 0x04;
+  pub const DOWNLOAD_MICROCODE_AND_SAVE: u8 = 0x05;
+  pub const DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_ACTIVATE: u8 = 0x06;
+  pub const DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_SAVE: u8 = 0x07;
+  pub const ECHO_BUFFER: u8 = 0x0A;
+  pub const DOWNLOAD_MICROCODE_WITH_OFFSETS_SELECT_ACTIVATION_SAVE_AND_DEFER_ACTIVATE: u8 = 0x0D;
+  pub const DOWNLOAD_MICROCODE_WITH_OFFSETS_SAVE_AND_DEFER_ACTIVATE: u8 = 0x0E;
+  pub const ACTIVATE_DEFERRED_MICROCODE: u8 = 0x0F;
+  pub const DOWNLOAD_APPLICATION_CLIENT_ERROR_HISTORY: u8 = 0x1C;
+
+  pub fn new() -> WriteBuffer {
+    Default::default()
+  }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <WriteBuffer> {
+    if buf.len() < 10 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(WriteBuffer {
+      mode_specific: buf[1] >> 5,
+      mode: buf[1] & 0x1F,
+      buffer_id: buf[2],
+      buffer_offset: u32::from_be_bytes([0, buf[3], buf[4], buf[5]]),
+      parameter_list_length: u32::from_be_bytes([0, buf[6], buf[7], buf[8]]),
+    })
+  }
+}
+
+impl crate::Input for WriteBuffer {
+}
+
+impl crate::Command for WriteBuffer {
+  type SenseInfo = ParameterSenseInfo;
+
+  fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
+    let mut data = vec![0; 10];
+
+    if self.mode_specific > 0x07 {
+      return Err("Mode specific must be in the range 0..8");
+    }
+    if self.mode > 0x1F {
+      return Err("Mode must be in the range 0..32");
+    }
+    if !(0..(2_u32.pow(24))).contains(&self.buffer_offset) {
+      return Err("Buffer offset must be in the range 0..2^24");
+    }
+    if !(0..(2_u32.pow(24))).contains(&self.parameter_list_length) {
+      return Err("Parameter list length must be in the range 0..2^24");
+    }
+
+    data[0] = Self::OP_CODE;
+    data[1] = self.mode_specific << 5 | self.mode;
+    data[2] = self.buffer_id;
+    data[3..6].copy_from_slice(&self.buffer_offset.to_be_bytes()[1..]);
+    data[6..9].copy_from_slice(&self.parameter_list_length.to_be_bytes()[1..]);
+
+    Ok(data)
+  }
+
+  fn parse_sense(&self, sense: &Sense) -> ParameterSenseInfo {
+    ParameterSenseInfo::from_sense(sense)
+  }
+}
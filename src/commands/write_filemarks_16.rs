@@ -1,7 +1,10 @@
 use crate::sense::Sense;
+use std::convert::TryInto;
+use crate::commands::WriteFilemarksSenseInfo;
 
 /// SSC-4 Section 5.7
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WriteFilemarks16 {
   pub fcs: bool,
   pub lcs: bool,
@@ -17,12 +20,30 @@ impl WriteFilemarks16 {
   pub fn new() -> WriteFilemarks16 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <WriteFilemarks16> {
+    if buf.len() < 16 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(WriteFilemarks16 {
+      fcs: buf[1] & 0x8 == 0x8,
+      lcs: buf[1] & 0x4 == 0x4,
+      immed: buf[1] & 0x1 == 0x1,
+      partition: buf[3],
+      logical_object_identifier: u64::from_be_bytes(buf[4..12].try_into().unwrap()),
+      filemark_count: u32::from_be_bytes([0, buf[12], buf[13], buf[14]]),
+    })
+  }
 }
 
 impl crate::NoIO for WriteFilemarks16 {
 }
 
 impl crate::Command for WriteFilemarks16 {
+  type SenseInfo = WriteFilemarksSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 16];
 
@@ -42,7 +63,7 @@ impl crate::Command for WriteFilemarks16 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> WriteFilemarksSenseInfo {
+    WriteFilemarksSenseInfo::from_sense(sense)
   }
 }
@@ -1,7 +1,9 @@
 use crate::sense::Sense;
+use crate::commands::WriteFilemarksSenseInfo;
 
 /// SSC-4 Section 6.9
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WriteFilemarks6 {
   pub immed: bool,
   pub filemark_count: u32,
@@ -13,12 +15,26 @@ impl WriteFilemarks6 {
   pub fn new() -> WriteFilemarks6 {
     Default::default()
   }
+
+  /// Decode a CDB previously built with to_bytes()
+  pub fn from_bytes(buf: &[u8]) -> Option <WriteFilemarks6> {
+    if buf.len() < 6 || buf[0] != Self::OP_CODE {
+      return None;
+    }
+
+    Some(WriteFilemarks6 {
+      immed: buf[1] & 0x1 == 0x1,
+      filemark_count: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+    })
+  }
 }
 
 impl crate::NoIO for WriteFilemarks6 {
 }
 
 impl crate::Command for WriteFilemarks6 {
+  type SenseInfo = WriteFilemarksSenseInfo;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str> {
     let mut data = vec![0; 6];
 
@@ -33,7 +49,7 @@ impl crate::Command for WriteFilemarks6 {
     Ok(data)
   }
 
-  fn parse_sense(&self, _sense: &Sense) -> String {
-    "".to_string()
+  fn parse_sense(&self, sense: &Sense) -> WriteFilemarksSenseInfo {
+    WriteFilemarksSenseInfo::from_sense(sense)
   }
 }
@@ -1,17 +1,26 @@
 extern crate libc;
 use std::ffi::CString;
 use std::ffi::CStr;
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_void};
 use std::io;
 use std::ptr;
 use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 use crate::scsi_sg;
+use crate::commands::TestUnitReady;
+use crate::commands::ReportSupportedOperationCodes;
+use crate::commands::ReportSupportedOperationCodesOutput;
+use crate::commands::CommandDescriptor;
+use crate::commands::{ReservationGuard, ReservationKind, ReservationError};
 use crate::sense::{*};
 use crate::{Command, NoIO, Output, Input};
 
 /// SAM-5 Section 5.3
 #[repr(C)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Status {
   Good,
   CheckCondition,
@@ -43,6 +52,7 @@ impl Status {
 /// Designed from the Linux sg documentation
 #[repr(C)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HostStatus {
   OK,
   NoConnect,
@@ -85,6 +95,7 @@ impl HostStatus {
 
 #[repr(C)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// SCSI driver status
 pub enum DriverStatus {
   OK,
@@ -119,6 +130,7 @@ impl DriverStatus {
 
 #[repr(C)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// SCSI driver suggested action
 pub enum DriverSuggest {
   Nothing,
@@ -146,18 +158,37 @@ impl DriverSuggest {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// SCSI device status information
 pub struct DeviceStatus {
   pub status: Status,
   pub host_status: HostStatus,
   pub driver_status: DriverStatus,
   pub driver_suggest: DriverSuggest,
+  /// Sense data for the command that was just issued
   pub sense: Option <Sense>,
+  /// Sense data for a deferred error (SPC-3 Section 4.5.5). This belongs to
+  /// an earlier command that was already reported as successful, such as a
+  /// buffered write that never reached the medium. The command that was
+  /// just issued was not performed and may be issued again.
+  pub deferred_error: Option <Sense>,
+}
+
+impl DeviceStatus {
+  pub fn has_deferred_error(&self) -> bool {
+    self.deferred_error.is_some()
+  }
+
+  /// The command was rejected because another I_T nexus holds a
+  /// reservation. No sense data is returned with this status.
+  pub fn is_reservation_conflict(&self) -> bool {
+    matches!(self.status, Status::ReservationConflict)
+  }
 }
 
 impl fmt::Display for DeviceStatus {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    if let Err(e) = write!(f, concat!(
+    write!(f, concat!(
         "Status:        {:?}\n",
         "HostStatus:    {:?}\n",
         "DriverStatus:  {:?}\n",
@@ -167,8 +198,10 @@ impl fmt::Display for DeviceStatus {
         self.host_status,
         self.driver_status,
         self.driver_suggest,
-    ){
-      return Err(e);
+    )?;
+
+    if let Some(sense) = &self.deferred_error {
+      write!(f, "\n== Deferred Error ==\n{}", sense)?;
     }
 
     if let Some(sense) = &self.sense {
@@ -179,9 +212,47 @@ impl fmt::Display for DeviceStatus {
   }
 }
 
+/// Turn anything but GOOD status into an error naming the command, for
+/// helpers that return `Result <_, String>`
+pub(crate) fn check_good(name: &str, status: DeviceStatus) -> Result <DeviceStatus, String> {
+  match status.status {
+    Status::Good => { Ok(status) },
+    _ => { Err(format!("{} failed:\n{}", name, status)) },
+  }
+}
+
+/// Result of a TEST UNIT READY, see [`Device::test_unit_ready`]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnitReadiness {
+  Ready,
+  /// NOT READY with an operation in progress, such as a load after LOAD
+  /// UNLOAD (ASC/ASCQ 04/01). Progress is out of 65536 when the device
+  /// reports it. Also returned for BUSY status.
+  BecomingReady(Option <u16>),
+  /// NOT READY, MEDIUM NOT PRESENT (ASC 3A)
+  MediumNotPresent,
+  /// A unit attention condition was reported and cleared, for example after
+  /// a reset or a medium change. The command should be issued again.
+  UnitAttention,
+  /// A deferred error for an earlier command was reported instead, see
+  /// [`DeviceStatus::deferred_error`]. The command should be issued again.
+  DeferredError,
+}
+
+/// Called with the sense data whenever a deferred error is reported. Send
+/// and Sync so a Device can still be moved to and shared between threads.
+pub type DeferredErrorHandler = Box <dyn Fn(&Sense) + Send + Sync>;
+
 /// SCSI device handle
 pub struct Device {
   sg_fd: Option <libc::c_int>,
+  deferred_error_handler: Option <DeferredErrorHandler>,
+  timeout: Option <Duration>,
+  use_recommended_timeouts: bool,
+  /// Result of REPORT SUPPORTED OPERATION CODES, kept until the device is
+  /// closed
+  supported_commands: Mutex <Option <Result <Vec <CommandDescriptor>, String>>>,
 }
 
 impl Device {
@@ -216,9 +287,43 @@ impl Device {
   pub fn new() -> Device {
     Device {
       sg_fd: None,
+      deferred_error_handler: None,
+      timeout: None,
+      use_recommended_timeouts: false,
+      supported_commands: Mutex::new(None),
     }
   }
 
+  /// Register a handler that is called every time a command reports a
+  /// deferred error. Writers should use this to invalidate any data they
+  /// have accounted for as written but that may still have been buffered.
+  pub fn set_deferred_error_handler(&mut self, handler: DeferredErrorHandler) {
+    self.deferred_error_handler = Some(handler);
+  }
+
+  pub fn clear_deferred_error_handler(&mut self) {
+    self.deferred_error_handler = None;
+  }
+
+  /// Timeout for every command, or None for the driver default
+  pub fn set_timeout(&mut self, timeout: Option <Duration>) {
+    self.timeout = timeout;
+  }
+
+  /// Use the recommended command timeout reported by the device for each
+  /// command that has one, falling back to [`Device::set_timeout`]. Enabling
+  /// this issues REPORT SUPPORTED OPERATION CODES if it has not been issued
+  /// yet. Opening or closing the device turns this off again, as the newly
+  /// opened device may report different timeouts, so enable it after
+  /// [`Device::open`].
+  pub fn set_use_recommended_timeouts(&mut self, enable: bool) -> Result <(), String> {
+    if enable {
+      self.load_supported_commands()?;
+    }
+    self.use_recommended_timeouts = enable;
+    Ok(())
+  }
+
   pub fn open(&mut self, path: &str) -> Result <(), String> {
     let tmp = CString::new(path);
     if tmp.is_err() {
@@ -235,6 +340,8 @@ impl Device {
     }
 
     self.sg_fd = Some(fd);
+    self.use_recommended_timeouts = false;
+    *self.command_cache() = None;
     Ok(())
   }
 
@@ -243,38 +350,140 @@ impl Device {
       unsafe { libc::close(fd); }
       self.sg_fd = None;
     }
+    self.use_recommended_timeouts = false;
+    *self.command_cache() = None;
+  }
+
+  fn command_cache(&self) -> MutexGuard <'_, Option <Result <Vec <CommandDescriptor>, String>>> {
+    self.supported_commands.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Issue REPORT SUPPORTED OPERATION CODES unless it was already issued
+  /// since the device was opened. A device that rejects the command as not
+  /// supported is remembered too, so it is only asked once. Other failures,
+  /// such as a unit attention, are returned but not kept.
+  fn load_supported_commands(&self) -> Result <Vec <CommandDescriptor>, String> {
+    if let Some(t) = self.command_cache().as_ref() {
+      return t.clone();
+    }
+
+    //The cache is not locked while the command is issued, since issuing a
+    //command looks up its timeout there
+    let cmd = ReportSupportedOperationCodes::all_commands();
+    let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+    let status = self.issue_cmd_with_output(&cmd, &mut buf)?;
+    let unsupported = status.sense.as_ref()
+        .is_some_and(|t| matches!(t.sense_key, SenseKey::IllegalRequest));
+
+    let result = check_good("REPORT SUPPORTED OPERATION CODES", status).and_then(|_| {
+      match cmd.parse_buffer(&buf) {
+        Some(ReportSupportedOperationCodesOutput::AllCommands(t)) => { Ok(t) },
+        _ => { Err("Could not parse REPORT SUPPORTED OPERATION CODES data".to_string()) },
+      }
+    });
+
+    if result.is_ok() || unsupported {
+      *self.command_cache() = Some(result.clone());
+    }
+    result
   }
 
-  fn issue_cmd_internal <T>(&self, cdb: &T, mut header: scsi_sg::sg_io_hdr) -> Result <DeviceStatus, String>
+  /// Every command the device reports as supported, with timeouts when the
+  /// device provides them. Issued once and cached, along with a rejection of
+  /// the command, until the device is closed.
+  pub fn supported_commands(&self) -> Result <Vec <CommandDescriptor>, String> {
+    self.load_supported_commands()
+  }
+
+  fn find_supported_command <T>(&self) -> Result <Option <CommandDescriptor>, String>
+    where T: Command + Default,
+  {
+    let cdb = match T::default().to_bytes() {
+      Ok(bytes) => { bytes },
+      Err(e) => { return Err(format!("Error converting CDB to bytes: {}", e)); },
+    };
+
+    Ok(self.load_supported_commands()?.into_iter().find(|t| t.matches(&cdb)))
+  }
+
+  /// Whether the device implements command `T`, identified by operation
+  /// code and service action
+  pub fn supports <T>(&self) -> Result <bool, String>
+    where T: Command + Default,
+  {
+    Ok(self.find_supported_command::<T>()?.is_some())
+  }
+
+  /// Recommended timeout for command `T`, None if the device does not
+  /// report one
+  pub fn recommended_timeout <T>(&self) -> Result <Option <Duration>, String>
+    where T: Command + Default,
+  {
+    Ok(self.find_supported_command::<T>()?
+        .and_then(|t| t.timeouts)
+        .and_then(|t| t.recommended()))
+  }
+
+  /// Reserve the device until the returned guard is released or dropped
+  pub fn reserve(&self, kind: ReservationKind) -> Result <ReservationGuard<'_>, ReservationError> {
+    ReservationGuard::acquire(self, kind)
+  }
+
+  fn timeout_for(&self, cdb: &[u8]) -> Option <Duration> {
+    //Only the cache is consulted, a command is never issued from here
+    if self.use_recommended_timeouts {
+      let recommended = self.command_cache().iter().flatten().flatten()
+          .find(|t| t.matches(cdb))
+          .and_then(|t| t.timeouts.as_ref())
+          .and_then(|t| t.recommended());
+      if recommended.is_some() {
+        return recommended;
+      }
+    }
+
+    self.timeout
+  }
+
+  fn issue_cmd_internal <T>(&self, cdb: &T, mut header: scsi_sg::sg_io_hdr,
+      timeout: Option <Duration>) -> Result <DeviceStatus, String>
     where T: Command,
   {
-    if self.sg_fd == None {
+    if self.sg_fd.is_none() {
       return Err("No device is currently open".to_string());
     }
 
-    let bytes = cdb.to_bytes();
-    if bytes.is_err() {
-      return Err(format!("Error converting CDB to bytes: {}", bytes.unwrap_err().to_string()));
-    }
-    let mut bytes = bytes.unwrap();
+    let mut bytes = match cdb.to_bytes() {
+      Ok(bytes) => { bytes },
+      Err(e) => { return Err(format!("Error converting CDB to bytes: {}", e)); },
+    };
 
     let mut sense_buffer: [u8; 255] = [0; 255];
     header.cmd_len = bytes.len() as u8;
     header.cmdp = bytes.as_mut_ptr();
     header.sbp = sense_buffer.as_mut_ptr();
     header.mx_sb_len = 255;
+    if let Some(timeout) = timeout.or_else(|| self.timeout_for(&bytes)) {
+      header.timeout = timeout.as_millis().min(u32::MAX.into()) as u32;
+    }
 
     //Send to device
     unsafe {
       let _rc = libc::ioctl(self.sg_fd.unwrap(), scsi_sg::SG_IO.into(), &mut header);
     }
 
+    let (sense, deferred_error) = split_sense(&sense_buffer);
+
+    if let (Some(sense), Some(handler)) = (&deferred_error, &self.deferred_error_handler) {
+      handler(sense);
+    }
+
     Ok(DeviceStatus {
       status: Status::from_u8(header.status),
       host_status: HostStatus::from_u16(header.host_status),
       driver_status: DriverStatus::from_u16(header.driver_status),
       driver_suggest: DriverSuggest::from_u16(header.driver_status),
-      sense: Sense::from_buf(&sense_buffer),
+      sense,
+      deferred_error,
     })
   }
 
@@ -284,7 +493,7 @@ impl Device {
     let mut header = Self::DEFAULT_HEADER;
     header.dxfer_direction = scsi_sg::SG_DXFER_NONE;
 
-    self.issue_cmd_internal(cdb, header)
+    self.issue_cmd_internal(cdb, header, None)
   }
 
   pub fn issue_cmd_with_input <T>
@@ -296,7 +505,22 @@ impl Device {
     header.dxfer_len = buffer.len() as u32;
     header.dxferp = buffer.as_ptr() as *const _ as *mut libc::c_void;
 
-    self.issue_cmd_internal(cdb, header)
+    self.issue_cmd_internal(cdb, header, None)
+  }
+
+  /// Same as [`Device::issue_cmd_with_input`], with a timeout for this
+  /// command only that takes precedence over [`Device::set_timeout`] and
+  /// recommended timeouts
+  pub fn issue_cmd_with_input_and_timeout <T>
+      (&self, cdb: &T, buffer: &[u8], timeout: Duration) -> Result <DeviceStatus, String>
+    where T: Command + Input,
+  {
+    let mut header = Self::DEFAULT_HEADER;
+    header.dxfer_direction = scsi_sg::SG_DXFER_TO_DEV;
+    header.dxfer_len = buffer.len() as u32;
+    header.dxferp = buffer.as_ptr() as *const _ as *mut libc::c_void;
+
+    self.issue_cmd_internal(cdb, header, Some(timeout))
   }
 
   /// Issue a command that will return data into a buffer
@@ -309,7 +533,85 @@ impl Device {
     header.dxfer_len = buffer.len() as u32;
     header.dxferp = buffer.as_mut_ptr() as *mut _ as *mut libc::c_void;
 
-    self.issue_cmd_internal(cdb, header)
+    self.issue_cmd_internal(cdb, header, None)
+  }
+
+  /// Issue a TEST UNIT READY and interpret the sense data. Any condition
+  /// that will not clear by waiting is returned as an error.
+  pub fn test_unit_ready(&self) -> Result <UnitReadiness, String> {
+    unit_readiness(self.issue_cmd(&TestUnitReady::new())?)
+  }
+
+  /// Poll with TEST UNIT READY until the device is ready, for example after
+  /// LOAD UNLOAD or a reset. Fails immediately if no medium is present.
+  pub fn wait_until_ready(&self, timeout: Duration) -> Result <(), String> {
+    self.wait_until_ready_with_progress(timeout, |_| {})
+  }
+
+  /// Same as [`Device::wait_until_ready`], calling `progress` with the
+  /// progress indication (out of 65536) each time the device reports it is
+  /// becoming ready
+  pub fn wait_until_ready_with_progress <F>
+      (&self, timeout: Duration, mut progress: F) -> Result <(), String>
+    where F: FnMut(Option <u16>),
+  {
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+    let start = Instant::now();
+
+    loop {
+      match self.test_unit_ready()? {
+        UnitReadiness::Ready => { return Ok(()); },
+        UnitReadiness::MediumNotPresent => { return Err("Medium not present".to_string()); },
+        UnitReadiness::BecomingReady(t) => { progress(t); },
+        // Cleared by reporting it, the next poll sees the real state
+        UnitReadiness::UnitAttention | UnitReadiness::DeferredError => {},
+      }
+
+      if start.elapsed() >= timeout {
+        return Err(format!("Device not ready after {:?}", timeout));
+      }
+      thread::sleep(POLL_INTERVAL);
+    }
+  }
+}
+
+/// Interpret the status of a TEST UNIT READY, see [`Device::test_unit_ready`]
+fn unit_readiness(status: DeviceStatus) -> Result <UnitReadiness, String> {
+  if status.has_deferred_error() {
+    return Ok(UnitReadiness::DeferredError);
+  }
+
+  match (&status.status, &status.sense) {
+    (Status::Good, _) => { Ok(UnitReadiness::Ready) },
+    (Status::Busy, _) => { Ok(UnitReadiness::BecomingReady(None)) },
+    (Status::CheckCondition, Some(sense)) => {
+      let asc_ascq = (sense.additional_sense_code as u16) << 8 |
+          sense.additional_sense_code_qualifier as u16;
+      let progress = match &sense.sense_key_specific {
+        SenseKeySpecific::NotReady(t) => { Some(t.progress) },
+        _ => { None },
+      };
+
+      match (&sense.sense_key, asc_ascq) {
+        (SenseKey::UnitAttention, _) => { Ok(UnitReadiness::UnitAttention) },
+        (SenseKey::NotReady, 0x3A00..=0x3AFF) => { Ok(UnitReadiness::MediumNotPresent) },
+        (SenseKey::NotReady, 0x0401 | 0x0404 | 0x0407) => {
+          Ok(UnitReadiness::BecomingReady(progress))
+        },
+        _ => { Err(format!("TEST UNIT READY failed:\n{}", status)) },
+      }
+    },
+    _ => { Err(format!("TEST UNIT READY failed:\n{}", status)) },
+  }
+}
+
+/// Parse returned sense data into (current, deferred). A deferred error
+/// (response code 0x71 or 0x73) belongs to an earlier command, so it must
+/// never be reported as the sense of the command that returned it.
+fn split_sense(buf: &[u8]) -> (Option <Sense>, Option <Sense>) {
+  match Sense::from_buf(buf) {
+    Some(sense) if sense.deferred => { (None, Some(sense)) },
+    sense => { (sense, None) },
   }
 }
 
@@ -331,25 +633,48 @@ impl Default for Device {
 // C Functions
 
 #[no_mangle]
-pub extern fn device_status_free(device_status: *mut DeviceStatus) {
+pub extern "C" fn device_status_free(device_status: *mut DeviceStatus) {
   let boxed: Box <DeviceStatus> = unsafe{ Box::from_raw(device_status) };
   drop(boxed);
 }
 
 #[no_mangle]
-pub extern fn device_status_to_stdout(device_status: *const DeviceStatus) {
+pub extern "C" fn device_status_to_stdout(device_status: *const DeviceStatus) {
   let device_status = unsafe { &*device_status };
   println!("{}", device_status);
 }
 
 #[no_mangle]
-pub extern fn device_close(device: *mut Device) {
+pub extern "C" fn device_status_has_deferred_error(device_status: *const DeviceStatus) -> bool {
+  let device_status = unsafe { &*device_status };
+  device_status.has_deferred_error()
+}
+
+#[no_mangle]
+pub extern "C" fn device_set_deferred_error_handler(
+    device: *mut Device,
+    handler: Option <extern "C" fn(*const Sense, *mut c_void)>,
+    user_data: *mut c_void) {
+  let device = unsafe { &mut *device };
+  match handler {
+    Some(handler) => {
+      let user_data = user_data as usize;
+      device.set_deferred_error_handler(Box::new(move |sense: &Sense| {
+        handler(sense as *const Sense, user_data as *mut c_void);
+      }));
+    },
+    None => { device.clear_deferred_error_handler(); },
+  }
+}
+
+#[no_mangle]
+pub extern "C" fn device_close(device: *mut Device) {
   let device = unsafe { &mut *device };
   device.close();
 }
 
 #[no_mangle]
-pub extern fn device_open(device: *mut Device, path: *const c_char) -> bool {
+pub extern "C" fn device_open(device: *mut Device, path: *const c_char) -> bool {
   let device = unsafe { &mut *device };
   let path = unsafe { CStr::from_ptr(path) };
   let path = path.to_str();
@@ -366,13 +691,13 @@ pub extern fn device_open(device: *mut Device, path: *const c_char) -> bool {
 }
 
 #[no_mangle]
-pub extern fn device_new() -> *mut Device {
-  let boxed_device: Box <Device> = Box::new(Device::new());
+pub extern "C" fn device_new() -> *mut Device {
+  let boxed_device: Box <Device> = Box::default();
   Box::into_raw(boxed_device)
 }
 
 #[no_mangle]
-pub extern fn device_delete(device: *mut Device) {
+pub extern "C" fn device_delete(device: *mut Device) {
   if device.is_null() {
     return;
   }
@@ -383,3 +708,79 @@ pub extern fn device_delete(device: *mut Device) {
 
   drop(boxed_device);
 }
+
+#[test]
+fn device_send_test() {
+  fn assert_send <T: Send>() {}
+  assert_send::<Device>();
+}
+
+#[test]
+fn device_sync_test() {
+  fn assert_sync <T: Sync>() {}
+  assert_sync::<Device>();
+}
+
+#[test]
+fn split_sense_test() {
+  // Fixed format, MEDIUM ERROR, WRITE ERROR
+  let mut fixed = [
+    0x70, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x0A,
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+  // Descriptor format, MEDIUM ERROR, WRITE ERROR
+  let mut descriptor = [0x72, 0x03, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+  let (sense, deferred) = split_sense(&fixed);
+  assert!(sense.is_some_and(|t| !t.deferred));
+  assert!(deferred.is_none());
+  let (sense, deferred) = split_sense(&descriptor);
+  assert!(sense.is_some_and(|t| !t.deferred));
+  assert!(deferred.is_none());
+
+  fixed[0] = 0x71;
+  let (sense, deferred) = split_sense(&fixed);
+  assert!(sense.is_none());
+  assert!(deferred.is_some_and(|t| t.deferred && t.additional_sense_code == 0x0C));
+  descriptor[0] = 0x73;
+  let (sense, deferred) = split_sense(&descriptor);
+  assert!(sense.is_none());
+  assert!(deferred.is_some_and(|t| t.deferred && t.additional_sense_code == 0x0C));
+
+  assert!(matches!(split_sense(&[0; 18]), (None, None)));
+}
+
+#[test]
+fn unit_readiness_test() {
+  let status = |status: u8, sense: &[u8]| {
+    let (sense, deferred_error) = split_sense(sense);
+    DeviceStatus {
+      status: Status::from_u8(status),
+      host_status: HostStatus::from_u16(0),
+      driver_status: DriverStatus::from_u16(0),
+      driver_suggest: DriverSuggest::from_u16(0),
+      sense,
+      deferred_error,
+    }
+  };
+  let fixed = |response_code: u8, sense_key: u8, asc: u8, ascq: u8| {
+    vec![
+      response_code, 0x00, sense_key, 0x00, 0x00, 0x00, 0x00, 0x0A,
+      0x00, 0x00, 0x00, 0x00, asc, ascq, 0x00, 0x00, 0x00, 0x00,
+    ]
+  };
+
+  assert_eq!(unit_readiness(status(0x00, &[])), Ok(UnitReadiness::Ready));
+  assert_eq!(unit_readiness(status(0x08, &[])), Ok(UnitReadiness::BecomingReady(None)));
+  assert_eq!(unit_readiness(status(0x02, &fixed(0x70, 0x06, 0x28, 0x00))),
+      Ok(UnitReadiness::UnitAttention));
+  assert_eq!(unit_readiness(status(0x02, &fixed(0x70, 0x02, 0x3A, 0x00))),
+      Ok(UnitReadiness::MediumNotPresent));
+  assert_eq!(unit_readiness(status(0x02, &fixed(0x70, 0x02, 0x04, 0x01))),
+      Ok(UnitReadiness::BecomingReady(None)));
+  assert!(unit_readiness(status(0x02, &fixed(0x70, 0x03, 0x11, 0x00))).is_err());
+
+  // Deferred write error reported to the TEST UNIT READY
+  assert_eq!(unit_readiness(status(0x02, &fixed(0x71, 0x03, 0x0C, 0x00))),
+      Ok(UnitReadiness::DeferredError));
+}
@@ -35,6 +35,11 @@ Finally, the result is a DeviceStatus which contains some Enums
 for various status plus an optional Sense structure [^better_device_status].
 
 
+# Features
+
+* `serde`: Derives `Serialize` and `Deserialize` for [`Sense`](struct@Sense),
+  [`DeviceStatus`](struct@DeviceStatus) and the command and output structures.
+
 [`SG_IO`]: https://www.kernel.org/doc/html/latest/scsi/scsi-generic.html
 
 [^better_device_status]: (Test footnote) Possibly make this a bit more abstract
@@ -43,8 +48,11 @@ or add functions for understanding various combinations of status.
 
 /// SCSI Command structures implement this trait
 pub trait Command {
+  /// Command specific interpretation of the sense data
+  type SenseInfo: std::fmt::Debug + std::fmt::Display;
+
   fn to_bytes(&self) -> Result <Vec <u8>, &'static str>;
-  fn parse_sense(&self, sense: &Sense) -> String;
+  fn parse_sense(&self, sense: &Sense) -> Self::SenseInfo;
 }
 
 /// Command marker trait to indicate it has associated input to be sent to the SCSI device.
@@ -60,14 +68,161 @@ pub trait NoIO {
 }
 
 pub mod commands {
+  mod sense_info;
+  pub use sense_info::PositionSense;
+  pub use sense_info::InvalidField;
+  pub use sense_info::ReadSenseInfo;
+  pub use sense_info::WriteSenseInfo;
+  pub use sense_info::WriteFilemarksSenseInfo;
+  pub use sense_info::VerifySenseInfo;
+  pub use sense_info::SpaceSenseInfo;
+  pub use sense_info::LocateSenseInfo;
+  pub use sense_info::MediumOperationSenseInfo;
+  pub use sense_info::ParameterSenseInfo;
+
   //SPC-3
-  //mod inquiry;
-  //mod log_select;
-  //mod log_sense;
-  //mod mode_select_6;
-  //mod mode_select_10;
-  //mod mode_sense_6;
-  //mod mode_sense_10;
+  //Command modules are private and re-export their command. Helper modules
+  //that wrap one or more commands are public, so their functions can be
+  //reached, and re-export their main types.
+  pub mod cartridge;
+  pub use cartridge::CartridgeIdentity;
+
+  pub mod diagnostic;
+  pub use diagnostic::DiagnosticPage;
+
+  pub mod firmware;
+  pub use firmware::ReadBufferOutput;
+
+  pub mod identifying_information;
+  pub use identifying_information::IdentifyingInformationOutput;
+
+  mod inquiry;
+  pub use inquiry::Inquiry;
+  pub use inquiry::InquiryOutput;
+
+  pub mod log;
+  pub use log::LogPage;
+  pub use log::LogParameter;
+  pub use log::LogPageType;
+
+  pub mod log_pages;
+
+  mod log_select;
+  pub use log_select::LogSelect;
+
+  mod log_sense;
+  pub use log_sense::LogSense;
+
+  pub mod mam;
+  pub use mam::Attribute;
+
+  pub mod mode;
+  pub use mode::ModeParameters;
+  pub use mode::ModePage;
+  pub use mode::ModePageType;
+
+  pub mod mode_pages;
+
+  mod mode_select_6;
+  pub use mode_select_6::ModeSelect6;
+
+  mod mode_select_10;
+  pub use mode_select_10::ModeSelect10;
+
+  mod mode_sense_6;
+  pub use mode_sense_6::ModeSense6;
+
+  mod mode_sense_10;
+  pub use mode_sense_10::ModeSense10;
+
+  pub mod persistent_reservation;
+  pub use persistent_reservation::PersistentReserveInOutput;
+  pub use persistent_reservation::ReservationError;
+
+  mod persistent_reserve_in;
+  pub use persistent_reserve_in::PersistentReserveIn;
+
+  mod persistent_reserve_out;
+  pub use persistent_reserve_out::PersistentReserveOut;
+
+  mod read_attribute;
+  pub use read_attribute::ReadAttribute;
+
+  mod read_buffer;
+  pub use read_buffer::ReadBuffer;
+
+  mod read_media_serial_number;
+  pub use read_media_serial_number::ReadMediaSerialNumber;
+
+  mod receive_diagnostic_results;
+  pub use receive_diagnostic_results::ReceiveDiagnosticResults;
+
+  mod release_6;
+  pub use release_6::Release6;
+
+  mod report_identifying_information;
+  pub use report_identifying_information::ReportIdentifyingInformation;
+
+  mod report_luns;
+  pub use report_luns::ReportLuns;
+
+  mod report_supported_operation_codes;
+  pub use report_supported_operation_codes::ReportSupportedOperationCodes;
+  pub use report_supported_operation_codes::ReportSupportedOperationCodesOutput;
+  pub use report_supported_operation_codes::CommandDescriptor;
+  pub use report_supported_operation_codes::CommandTimeoutsDescriptor;
+  pub use report_supported_operation_codes::OneCommandDescriptor;
+
+  mod report_timestamp;
+  pub use report_timestamp::ReportTimestamp;
+
+  mod request_sense;
+  pub use request_sense::RequestSense;
+
+  pub mod reservation;
+  pub use reservation::ReservationGuard;
+  pub use reservation::ReservationKind;
+
+  mod reserve_6;
+  pub use reserve_6::Reserve6;
+
+  mod security_protocol_in;
+  pub use security_protocol_in::SecurityProtocolIn;
+
+  mod security_protocol_out;
+  pub use security_protocol_out::SecurityProtocolOut;
+
+  mod send_diagnostic;
+  pub use send_diagnostic::SendDiagnostic;
+
+  mod set_identifying_information;
+  pub use set_identifying_information::SetIdentifyingInformation;
+
+  mod set_timestamp;
+  pub use set_timestamp::SetTimestamp;
+
+  pub mod tape_alert;
+  pub use tape_alert::TapeAlertFlags;
+  pub use tape_alert::TapeAlertLog;
+
+  pub mod tape_encryption;
+  pub use tape_encryption::SetDataEncryption;
+  pub use tape_encryption::DataEncryptionStatus;
+
+  mod test_unit_ready;
+  pub use test_unit_ready::TestUnitReady;
+
+  pub mod timestamp;
+  pub use timestamp::Timestamp;
+
+  pub mod vpd;
+  pub use vpd::VpdPage;
+
+  mod write_attribute;
+  pub use write_attribute::WriteAttribute;
+
+  mod write_buffer;
+  pub use write_buffer::WriteBuffer;
 
   //SSC-4
   mod allow_overwrite;
@@ -169,11 +324,33 @@ mod scsi_sg;
 
 mod device;
 pub use device::Device;
+pub use device::DeferredErrorHandler;
 pub use device::DeviceStatus;
 pub use device::DriverSuggest;
 pub use device::DriverStatus;
 pub use device::Status;
+pub use device::UnitReadiness;
 pub use device::HostStatus;
 
 mod sense;
 pub use sense::Sense;
+pub use sense::SenseKey;
+pub use sense::SenseKeySpecific;
+pub use sense::SenseFieldPointer;
+pub use sense::SenseActualRetryCount;
+pub use sense::SenseProgress;
+pub use sense::SenseSegmentPointer;
+pub use sense::Residue;
+pub use sense::additional_sense_code_description;
+pub use sense::find_additional_sense_code;
+
+mod lun;
+pub use lun::Lun;
+pub use lun::LunAddress;
+
+pub mod sysfs;
+pub use sysfs::SgDevice;
+
+mod cdb;
+pub use cdb::Cdb;
+pub use cdb::CdbSenseInfo;
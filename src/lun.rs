@@ -0,0 +1,147 @@
+use std::convert::TryInto;
+use std::fmt;
+
+/// SAM-3 Section 4.9 eight byte logical unit number, as returned by REPORT
+/// LUNS
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lun(pub [u8; 8]);
+
+/// One level of a LUN, SAM-3 Section 4.9.6 through 4.9.9
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LunAddress {
+  /// Peripheral device addressing. A bus identifier of zero addresses a
+  /// logical unit on the current level, otherwise a target on that bus.
+  Peripheral { bus_identifier: u8, target_or_lun: u8 },
+  /// Flat space addressing
+  FlatSpace { lun: u16 },
+  /// Logical unit addressing
+  LogicalUnit { target: u8, bus_number: u8, lun: u8 },
+  /// Extended logical unit addressing with a well known logical unit such as
+  /// REPORT LUNS (0x01)
+  WellKnown(u8),
+  /// Any other extended logical unit addressing. The address is
+  /// `2 + 2 * length` bytes minus the first byte.
+  Extended { length: u8, extended_address_method: u8, address: u64 },
+}
+
+impl LunAddress {
+  pub const ADDRESS_METHOD_PERIPHERAL: u8 = 0x0;
+  pub const ADDRESS_METHOD_FLAT_SPACE: u8 = 0x1;
+  pub const ADDRESS_METHOD_LOGICAL_UNIT: u8 = 0x2;
+  pub const ADDRESS_METHOD_EXTENDED: u8 = 0x3;
+
+  /// Decode the level at the start of `buf`, returning it and its length
+  fn from_bytes(buf: &[u8]) -> Option <(LunAddress, usize)> {
+    if buf.len() < 2 {
+      return None;
+    }
+
+    let address = match buf[0] >> 6 {
+      Self::ADDRESS_METHOD_PERIPHERAL => {
+        LunAddress::Peripheral { bus_identifier: buf[0] & 0x3F, target_or_lun: buf[1] }
+      },
+      Self::ADDRESS_METHOD_FLAT_SPACE => {
+        LunAddress::FlatSpace { lun: u16::from_be_bytes([buf[0] & 0x3F, buf[1]]) }
+      },
+      Self::ADDRESS_METHOD_LOGICAL_UNIT => {
+        LunAddress::LogicalUnit {
+          target: buf[0] & 0x3F,
+          bus_number: buf[1] >> 5,
+          lun: buf[1] & 0x1F,
+        }
+      },
+      _ => {
+        let length = (buf[0] >> 4) & 0x3;
+        let extended_address_method = buf[0] & 0x0F;
+        let size = 2 + 2 * length as usize;
+        let address = buf.get(1..size)?.iter().fold(0, |acc, b| (acc << 8) | u64::from(*b));
+        if length == 0 && extended_address_method == 0x1 {
+          return Some((LunAddress::WellKnown(buf[1]), size));
+        }
+        return Some((LunAddress::Extended { length, extended_address_method, address }, size));
+      },
+    };
+
+    Some((address, 2))
+  }
+}
+
+impl Lun {
+  pub const REPORT_LUNS_WELL_KNOWN: Lun = Lun([0xC1, 0x01, 0, 0, 0, 0, 0, 0]);
+
+  pub fn from_bytes(buf: &[u8]) -> Option <Lun> {
+    Some(Lun(buf.get(0..8)?.try_into().unwrap()))
+  }
+
+  /// Each addressing level, stopping once the remaining levels are zero
+  pub fn levels(&self) -> Vec <LunAddress> {
+    let mut rv = Vec::new();
+    let mut offset = 0;
+    while offset < 8 {
+      if offset > 0 && self.0[offset..].iter().all(|t| *t == 0) {
+        break;
+      }
+
+      match LunAddress::from_bytes(&self.0[offset..]) {
+        Some((address, size)) => {
+          rv.push(address);
+          offset += size;
+        },
+        None => { break; },
+      }
+    }
+
+    rv
+  }
+
+  /// The LUN number used by Linux in sysfs and H:C:T:L names, which packs
+  /// each two byte level into 16 bits starting with the first level
+  pub fn to_linux_lun(&self) -> u64 {
+    self.0.chunks(2).enumerate().fold(0, |acc, (i, t)| {
+      acc | (u64::from(u16::from_be_bytes([t[0], t[1]])) << (i * 16))
+    })
+  }
+
+  pub fn from_linux_lun(lun: u64) -> Lun {
+    let mut rv = [0; 8];
+    for (i, t) in rv.chunks_mut(2).enumerate() {
+      t.copy_from_slice(&((lun >> (i * 16)) as u16).to_be_bytes());
+    }
+    Lun(rv)
+  }
+}
+
+impl fmt::Display for Lun {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}",
+        self.0[0], self.0[1], self.0[2], self.0[3],
+        self.0[4], self.0[5], self.0[6], self.0[7])
+  }
+}
+
+#[test]
+fn lun_test() {
+  let lun = Lun([0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+  assert_eq!(lun.levels(), vec![LunAddress::Peripheral { bus_identifier: 0, target_or_lun: 1 }]);
+  assert_eq!(lun.to_linux_lun(), 1);
+
+  let lun = Lun([0x40, 0x05, 0, 0, 0, 0, 0, 0]);
+  assert_eq!(lun.levels(), vec![LunAddress::FlatSpace { lun: 5 }]);
+  assert_eq!(Lun::from_linux_lun(lun.to_linux_lun()), lun);
+
+  let lun = Lun([0x01, 0x02, 0x80, 0x23, 0, 0, 0, 0]);
+  assert_eq!(lun.levels(), vec![
+    LunAddress::Peripheral { bus_identifier: 1, target_or_lun: 2 },
+    LunAddress::LogicalUnit { target: 0, bus_number: 1, lun: 3 },
+  ]);
+  assert_eq!(lun.to_linux_lun(), 0x8023_0102);
+
+  assert_eq!(Lun::REPORT_LUNS_WELL_KNOWN.levels(), vec![LunAddress::WellKnown(0x01)]);
+
+  let lun = Lun([0xE2, 0x00, 0x00, 0x00, 0x12, 0x34, 0, 0]);
+  assert_eq!(lun.levels(), vec![
+    LunAddress::Extended { length: 2, extended_address_method: 2, address: 0x1234 },
+  ]);
+}
@@ -13,6 +13,7 @@ use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::convert::TryInto;
+use std::time::Duration;
 
 struct ShellCommand {
   func: fn(&mut scsi::Device, &Matches),
@@ -67,7 +68,7 @@ fn main() {
       match opts.parse(&tokens[1..]) {
         Ok(m) => {
           if !m.free.is_empty() || m.opt_present("help") {
-            println!("{}{}", cmd.options.short_usage(&tokens[0]), cmd.options.usage(""));
+            println!("{}{}", cmd.options.short_usage(tokens[0]), cmd.options.usage(""));
             continue;
           }
 
@@ -75,7 +76,7 @@ fn main() {
         },
         Err(e) => {
           eprintln!("{}\n{}{}", e,
-              opts.short_usage(&tokens[0]), opts.usage(""));
+              opts.short_usage(tokens[0]), opts.usage(""));
         },
       }
     } else {
@@ -100,6 +101,240 @@ fn fill_commands(commands: &mut BTreeMap <&str, ShellCommand>) {
   options.optflag("i", "immed", "immediate flag");
   commands.insert("rewind", ShellCommand { func:rewind, options });
 
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("e", "evpd", "enable vital product data flag");
+  options.optopt("p", "page_code", "vital product data page to return", "<u8>");
+  options.optopt("l", "allocation_length", "length of output buffer for the response", "<u16>");
+  commands.insert("inquiry", ShellCommand { func:inquiry, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("", "ppc", "parameter pointer control flag");
+  options.optflag("", "sp", "save parameters flag");
+  options.optopt("c", "pc", concat!(
+      "page control\n",
+      "THRESHOLD: 0\n",
+      "CUMULATIVE: 1\n",
+      "DEFAULT_THRESHOLD: 2\n",
+      "DEFAULT_CUMULATIVE: 3"),
+      "<u8>");
+  options.optopt("p", "page_code", "log page to return; 0 for supported pages", "<u8>");
+  options.optopt("s", "subpage_code", "log subpage to return", "<u8>");
+  options.optopt("", "parameter_pointer", "first parameter code to return", "<u16>");
+  commands.insert("log_sense", ShellCommand { func:log_sense, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("", "dbd", "disable block descriptors flag");
+  options.optopt("c", "pc", concat!(
+      "page control\n",
+      "CURRENT: 0\n",
+      "CHANGEABLE: 1\n",
+      "DEFAULT: 2\n",
+      "SAVED: 3"),
+      "<u8>");
+  options.optopt("p", "page_code", "mode page to return; 63 for all pages", "<u8>");
+  options.optopt("s", "subpage_code", "mode subpage to return; 255 for all subpages", "<u8>");
+  commands.insert("mode_sense_6", ShellCommand { func:mode_sense_6, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("", "llbaa", "long LBA accepted flag");
+  options.optflag("", "dbd", "disable block descriptors flag");
+  options.optopt("c", "pc", concat!(
+      "page control\n",
+      "CURRENT: 0\n",
+      "CHANGEABLE: 1\n",
+      "DEFAULT: 2\n",
+      "SAVED: 3"),
+      "<u8>");
+  options.optopt("p", "page_code", "mode page to return; 63 for all pages", "<u8>");
+  options.optopt("s", "subpage_code", "mode subpage to return; 255 for all subpages", "<u8>");
+  commands.insert("mode_sense_10", ShellCommand { func:mode_sense_10, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("s", "service_action", concat!(
+      "service action\n",
+      "READ_KEYS: 0\n",
+      "READ_RESERVATION: 1\n",
+      "REPORT_CAPABILITIES: 2\n",
+      "READ_FULL_STATUS: 3"),
+      "<u8>");
+  commands.insert("persistent_reserve_in",
+      ShellCommand { func:persistent_reserve_in, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("s", "service_action", concat!(
+      "service action\n",
+      "REGISTER: 0\n",
+      "RESERVE: 1\n",
+      "RELEASE: 2\n",
+      "CLEAR: 3\n",
+      "PREEMPT: 4\n",
+      "PREEMPT_AND_ABORT: 5\n",
+      "REGISTER_AND_IGNORE_EXISTING_KEY: 6"),
+      "<u8>");
+  options.optopt("t", "type", "persistent reservation type", "<u8>");
+  options.optopt("k", "reservation_key", "reservation key", "<u64>");
+  options.optopt("", "service_action_reservation_key", "service action reservation key", "<u64>");
+  options.optflag("", "aptpl", "activate persist through power loss flag");
+  commands.insert("persistent_reserve_out",
+      ShellCommand { func:persistent_reserve_out, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("s", "service_action", concat!(
+      "service action\n",
+      "ATTRIBUTE_VALUES: 0\n",
+      "ATTRIBUTE_LIST: 1\n",
+      "LOGICAL_VOLUME_LIST: 2\n",
+      "PARTITION_LIST: 3\n",
+      "SUPPORTED_ATTRIBUTES: 5"),
+      "<u8>");
+  options.optopt("p", "partition_number", "partition to read attributes from", "<u8>");
+  options.optopt("f", "first_attribute_identifier", "first attribute to return", "<u16>");
+  commands.insert("read_attribute", ShellCommand { func:read_attribute, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("m", "mode", concat!(
+      "mode\n",
+      "COMBINED_HEADER_AND_DATA: 0\n",
+      "DATA: 2\n",
+      "DESCRIPTOR: 3\n",
+      "ECHO_BUFFER: 10\n",
+      "ECHO_BUFFER_DESCRIPTOR: 11\n",
+      "ERROR_HISTORY: 28"),
+      "<u8>");
+  options.optopt("b", "buffer_id", "buffer to read", "<u8>");
+  options.optopt("o", "buffer_offset", "offset into the buffer", "<u32>");
+  options.optopt("l", "allocation_length", "length of output buffer for the response", "<u32>");
+  commands.insert("read_buffer", ShellCommand { func:read_buffer, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("b", "buffer_id", "microcode buffer", "<u8>");
+  options.optopt("m", "mode", concat!(
+      "write buffer mode\n",
+      "DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_ACTIVATE: 6\n",
+      "DOWNLOAD_MICROCODE_WITH_OFFSETS_AND_SAVE: 7\n",
+      "DOWNLOAD_MICROCODE_WITH_OFFSETS_SAVE_AND_DEFER_ACTIVATE: 14 (default)"),
+      "<u8>");
+  options.reqopt("", "input_file", "file containing the firmware image", "<str>");
+  commands.insert("update_firmware", ShellCommand { func:update_firmware, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("b", "buffer_id", "save this data buffer instead of the error history", "<u8>");
+  options.reqopt("", "output_file", "file to write the dump to", "<str>");
+  commands.insert("save_dump", ShellCommand { func:save_dump, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("c", "self_test_code", concat!(
+      "self-test to run\n",
+      "DEFAULT: 0\n",
+      "BACKGROUND_SHORT: 1\n",
+      "BACKGROUND_EXTENDED: 2\n",
+      "FOREGROUND_SHORT: 5\n",
+      "FOREGROUND_EXTENDED: 6"),
+      "<u8>");
+  options.optopt("w", "wait", "poll a background self-test for up to this many seconds", "<u64>");
+  commands.insert("self_test", ShellCommand { func:self_test, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("t", "information_type", "information type; 0 for the device alias", "<u8>");
+  options.optopt("s", "information", "text to store; clears the information if absent", "<str>");
+  commands.insert("set_identifying_information",
+      ShellCommand { func:set_identifying_information, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  commands.insert("report_timestamp", ShellCommand { func:report_timestamp, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  commands.insert("set_timestamp", ShellCommand { func:set_timestamp, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  commands.insert("read_media_serial_number",
+      ShellCommand { func:read_media_serial_number, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("l", "label", "label to check against the medium, such as a library barcode", "<str>");
+  commands.insert("cartridge_identity", ShellCommand { func:cartridge_identity, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("p", "page_code", "diagnostic page to return; 0 for supported pages", "<u8>");
+  commands.insert("receive_diagnostic_results",
+      ShellCommand { func:receive_diagnostic_results, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  commands.insert("release_6", ShellCommand { func:release_6, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  commands.insert("reserve_6", ShellCommand { func:reserve_6, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("t", "information_type", concat!(
+      "information type\n",
+      "PERIPHERAL_DEVICE: 0\n",
+      "SUPPORTED_INFORMATION_TYPES: 127"),
+      "<u8>");
+  commands.insert("report_identifying_information",
+      ShellCommand { func:report_identifying_information, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("s", "select_report", concat!(
+      "logical units to report\n",
+      "SELECT_LOGICAL_UNITS: 0\n",
+      "SELECT_WELL_KNOWN: 1\n",
+      "SELECT_ALL: 2"),
+      "<u8>");
+  commands.insert("report_luns", ShellCommand { func:report_luns, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("t", "rctd", "return command timeouts descriptors flag");
+  options.optopt("r", "reporting_options", concat!(
+      "reporting options\n",
+      "ALL_COMMANDS: 0\n",
+      "ONE_COMMAND: 1\n",
+      "ONE_COMMAND_SERVICE_ACTION: 2"),
+      "<u8>");
+  options.optopt("o", "requested_operation_code", "operation code to report", "<u8>");
+  options.optopt("s", "requested_service_action", "service action to report", "<u16>");
+  commands.insert("report_supported_operation_codes",
+      ShellCommand { func:report_supported_operation_codes, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optflag("d", "desc", "return descriptor format sense data flag");
+  commands.insert("request_sense", ShellCommand { func:request_sense, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("p", "security_protocol", "security protocol; 32 for tape data encryption", "<u8>");
+  options.optopt("s", "security_protocol_specific", "page to return", "<u16>");
+  commands.insert("security_protocol_in",
+      ShellCommand { func:security_protocol_in, options });
+
+  options = Options::new();
+  options.optflag("?", "help", "");
+  options.optopt("w", "wait", "poll until ready for up to this many seconds", "<u64>");
+  commands.insert("test_unit_ready", ShellCommand { func:test_unit_ready, options });
+
   options = Options::new();
   options.optflag("?", "help", "");
   options.optflag("s", "sili", "suppress incorrect-length indicator flag");
@@ -383,12 +618,16 @@ macro_rules! get_opt_or_return {
 }
 
 macro_rules! print_status_or_return_error {
-  ( $res:expr, $cmd:expr ) => {
+  ( $device:expr, $res:expr, $cmd:expr ) => {
     match $res {
       Ok(status) => {
         println!("{}", status);
         if let Some(sense) = status.sense {
-          println!("{}", $cmd.parse_sense(&sense));
+          let info = $cmd.parse_sense(&sense).to_string();
+          if !info.is_empty() {
+            print!("== Command Specific ==\n{}", info);
+          }
+          print_tape_alerts($device, &sense);
         }
       },
       Err(e) => {
@@ -399,6 +638,21 @@ macro_rules! print_status_or_return_error {
   }
 }
 
+/// An informational exception only says that the drive flagged something,
+/// the TapeAlert log page says what
+fn print_tape_alerts(device: &scsi::Device, sense: &scsi::Sense) {
+  match commands::tape_alert::read_tape_alert_flags_for_sense(device, sense) {
+    Ok(Some(flags)) => {
+      println!("== TapeAlert ==");
+      for info in flags.active() {
+        println!("{}", info);
+      }
+    },
+    Ok(None) => {},
+    Err(e) => { eprintln!("Failed to read TapeAlert flags: {}", e); },
+  }
+}
+
 
 //Command functions
 
@@ -409,7 +663,491 @@ fn rewind(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
+}
+
+fn inquiry(device: &mut scsi::Device, matches: &Matches) {
+  let mut cmd = commands::Inquiry {
+    evpd: matches.opt_present("evpd"),
+    page_code: get_opt_or_return!("page_code", matches),
+    allocation_length: get_opt_or_return!("allocation_length", matches),
+  };
+
+  if cmd.allocation_length == 0 {
+    cmd.allocation_length = if cmd.evpd {
+      commands::Inquiry::VPD_LEN as u16
+    } else {
+      commands::Inquiry::FULL_LEN as u16
+    };
+  }
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  if cmd.evpd {
+    match cmd.parse_vpd(&buf) {
+      Some(t) => { println!("Known output:\n{}", t); },
+      None => { println!("Unknown output:\n{:x?}", buf); },
+    }
+  } else {
+    match cmd.parse_buffer(&buf) {
+      Some(t) => { println!("Known output:\n{}", t); },
+      None => { println!("Unknown output:\n{:x?}", buf); },
+    }
+  }
+}
+
+fn log_sense(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::LogSense {
+    ppc: matches.opt_present("ppc"),
+    sp: matches.opt_present("sp"),
+    pc: get_opt_or_return!("pc", matches),
+    page_code: get_opt_or_return!("page_code", matches),
+    subpage_code: get_opt_or_return!("subpage_code", matches),
+    parameter_pointer: get_opt_or_return!("parameter_pointer", matches),
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t.decode()); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn mode_sense_6(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ModeSense6 {
+    dbd: matches.opt_present("dbd"),
+    pc: get_opt_or_return!("pc", matches),
+    page_code: get_opt_or_return!("page_code", matches),
+    subpage_code: get_opt_or_return!("subpage_code", matches),
+    allocation_length: u8::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn mode_sense_10(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ModeSense10 {
+    llbaa: matches.opt_present("llbaa"),
+    dbd: matches.opt_present("dbd"),
+    pc: get_opt_or_return!("pc", matches),
+    page_code: get_opt_or_return!("page_code", matches),
+    subpage_code: get_opt_or_return!("subpage_code", matches),
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn persistent_reserve_in(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::PersistentReserveIn {
+    service_action: get_opt_or_return!("service_action", matches),
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{:#?}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn persistent_reserve_out(device: &mut scsi::Device, matches: &Matches) {
+  let parameters = commands::persistent_reservation::PersistentReserveOutParameters {
+    reservation_key: get_opt_or_return!("reservation_key", matches),
+    service_action_reservation_key:
+        get_opt_or_return!("service_action_reservation_key", matches),
+    aptpl: matches.opt_present("aptpl"),
+    ..Default::default()
+  };
+  let buf = parameters.to_bytes();
+
+  let cmd = commands::PersistentReserveOut {
+    service_action: get_opt_or_return!("service_action", matches),
+    scope: commands::PersistentReserveOut::LU_SCOPE,
+    reservation_type: get_opt_or_return!("type", matches),
+    parameter_list_length: buf.len() as u32,
+  };
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_input(&cmd, &buf);
+  print_status_or_return_error!(device, result, cmd);
+}
+
+fn release_6(device: &mut scsi::Device, _matches: &Matches) {
+  let cmd = commands::Release6::new();
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd(&cmd);
+  print_status_or_return_error!(device, result, cmd);
+}
+
+fn reserve_6(device: &mut scsi::Device, _matches: &Matches) {
+  let cmd = commands::Reserve6::new();
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd(&cmd);
+  print_status_or_return_error!(device, result, cmd);
+}
+
+fn read_attribute(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReadAttribute {
+    service_action: get_opt_or_return!("service_action", matches),
+    partition_number: get_opt_or_return!("partition_number", matches),
+    first_attribute_identifier: get_opt_or_return!("first_attribute_identifier", matches),
+    allocation_length: 0x10000,
+    ..commands::ReadAttribute::new()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(commands::mam::ReadAttributeOutput::Values(attributes)) => {
+      println!("Known output:");
+      for attribute in attributes {
+        println!("{}", attribute);
+      }
+    },
+    Some(t) => { println!("Known output:\n{:#X?}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn read_buffer(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReadBuffer {
+    mode: get_opt_or_return!("mode", matches),
+    buffer_id: get_opt_or_return!("buffer_id", matches),
+    buffer_offset: get_opt_or_return!("buffer_offset", matches),
+    allocation_length: match matches.opt_get("allocation_length") {
+      Ok(m) => { m.unwrap_or(0x2000) },
+      Err(e) => { eprintln!("Invalid allocation_length: {}", e); return },
+    },
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{:#X?}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn update_firmware(device: &mut scsi::Device, matches: &Matches) {
+  let buffer_id: u8 = get_opt_or_return!("buffer_id", matches);
+  let mode = match matches.opt_get("mode") {
+    Ok(m) => {
+      m.unwrap_or(commands::WriteBuffer::DOWNLOAD_MICROCODE_WITH_OFFSETS_SAVE_AND_DEFER_ACTIVATE)
+    },
+    Err(e) => { eprintln!("Invalid mode: {}", e); return },
+  };
+
+  let input_file_name = matches.opt_str("input_file").unwrap();
+  let image = match std::fs::read(&input_file_name) {
+    Ok(t) => { t },
+    Err(e) => { eprintln!("failed to read {}: {}", input_file_name, e); return; },
+  };
+
+  let result = commands::firmware::update_firmware_with_progress(
+      device, &image, buffer_id, mode,
+      |sent, total| { println!("Sent {} of {} bytes", sent, total); });
+  match result {
+    Ok(status) => { println!("{}", status); },
+    Err(e) => { eprintln!("Failed: {}", e); },
+  }
+}
+
+fn save_dump(device: &mut scsi::Device, matches: &Matches) {
+  let output_file_name = matches.opt_str("output_file").unwrap();
+  let result = match matches.opt_get::<u8>("buffer_id") {
+    Ok(Some(buffer_id)) => {
+      commands::firmware::save_buffer(device, buffer_id, &output_file_name)
+    },
+    Ok(None) => { commands::firmware::save_dump(device, &output_file_name) },
+    Err(e) => { eprintln!("Invalid buffer_id: {}", e); return },
+  };
+
+  match result {
+    Ok(length) => { println!("Wrote {} bytes to {}", length, output_file_name); },
+    Err(e) => { eprintln!("Failed: {}", e); },
+  }
+}
+
+fn receive_diagnostic_results(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReceiveDiagnosticResults {
+    pcv: true,
+    page_code: get_opt_or_return!("page_code", matches),
+    allocation_length: u16::MAX,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{:#X?}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn self_test(device: &mut scsi::Device, matches: &Matches) {
+  let self_test_code: u8 = get_opt_or_return!("self_test_code", matches);
+  let wait = match matches.opt_get("wait") {
+    Ok(m) => { Duration::from_secs(m.unwrap_or(3600)) },
+    Err(e) => { eprintln!("Invalid wait: {}", e); return },
+  };
+
+  let result = commands::diagnostic::run_self_test_with_progress(device, self_test_code, wait,
+      |progress| {
+        if let Some(t) = progress {
+          println!("Progress: {:.1}%", t as f64 * 100.0 / 65536.0);
+        }
+      });
+  match result {
+    Ok(t) => { print!("{}", t); },
+    Err(e) => { eprintln!("Failed: {}", e); },
+  }
+}
+
+fn report_identifying_information(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReportIdentifyingInformation {
+    information_type: get_opt_or_return!("information_type", matches),
+    ..commands::ReportIdentifyingInformation::new()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(commands::identifying_information::IdentifyingInformationOutput::Information(t)) => {
+      println!("Known output:\n{}", String::from_utf8_lossy(&t));
+    },
+    Some(t) => { println!("Known output:\n{:#X?}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn set_identifying_information(device: &mut scsi::Device, matches: &Matches) {
+  let information = matches.opt_str("information").unwrap_or_default();
+  let cmd = commands::SetIdentifyingInformation {
+    information_type: get_opt_or_return!("information_type", matches),
+    parameter_list_length: information.len() as u32,
+  };
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_input(&cmd, information.as_bytes());
+  print_status_or_return_error!(device, result, cmd);
+}
+
+fn report_timestamp(device: &mut scsi::Device, _matches: &Matches) {
+  let cmd = commands::ReportTimestamp::new();
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn set_timestamp(device: &mut scsi::Device, _matches: &Matches) {
+  match commands::timestamp::sync_timestamp(device) {
+    Ok(status) => { println!("{}", status); },
+    Err(e) => { eprintln!("Failed: {}", e); },
+  }
+}
+
+fn read_media_serial_number(device: &mut scsi::Device, _matches: &Matches) {
+  let cmd = commands::ReadMediaSerialNumber::new();
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", String::from_utf8_lossy(&t)); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn cartridge_identity(device: &mut scsi::Device, matches: &Matches) {
+  let identity = match commands::cartridge::read_cartridge_identity(device) {
+    Ok(t) => { t },
+    Err(e) => { eprintln!("Failed: {}", e); return; },
+  };
+  print!("{}", identity);
+
+  if let Some(label) = matches.opt_str("label") {
+    match identity.matches_label(&label) {
+      Some(true) => { println!("Label {} matches the medium", label); },
+      Some(false) => { println!("Label {} does NOT match the medium", label); },
+      None => { println!("The medium records no label to compare with"); },
+    }
+  }
+}
+
+fn report_luns(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReportLuns {
+    select_report: get_opt_or_return!("select_report", matches),
+    ..commands::ReportLuns::new()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(luns) => {
+      println!("Known output:");
+      for lun in luns {
+        println!("{} {:?}", lun, lun.levels());
+      }
+    },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn report_supported_operation_codes(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::ReportSupportedOperationCodes {
+    rctd: matches.opt_present("rctd"),
+    reporting_options: get_opt_or_return!("reporting_options", matches),
+    requested_operation_code: get_opt_or_return!("requested_operation_code", matches),
+    requested_service_action: get_opt_or_return!("requested_service_action", matches),
+    allocation_length: 0x10000,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn request_sense(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::RequestSense {
+    desc: matches.opt_present("desc"),
+    ..commands::RequestSense::new()
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length.into()];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  match cmd.parse_buffer(&buf) {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn security_protocol_in(device: &mut scsi::Device, matches: &Matches) {
+  let cmd = commands::SecurityProtocolIn {
+    security_protocol: get_opt_or_return!("security_protocol", matches),
+    security_protocol_specific: get_opt_or_return!("security_protocol_specific", matches),
+    inc_512: false,
+    allocation_length: 0x2000,
+  };
+
+  let mut buf: Vec <u8> = vec![0; cmd.allocation_length as usize];
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd_with_output(&cmd, &mut buf);
+  print_status_or_return_error!(device, result, cmd);
+
+  let output = match cmd.security_protocol {
+    commands::SecurityProtocolIn::TAPE_DATA_ENCRYPTION => {
+      commands::tape_encryption::TapeEncryptionPage::from_bytes(&buf).map(|t| format!("{:#?}", t))
+    },
+    commands::SecurityProtocolIn::SECURITY_PROTOCOL_INFORMATION => {
+      commands::SecurityProtocolIn::parse_supported_protocols(&buf).map(|t| format!("{:02X?}", t))
+    },
+    _ => { None },
+  };
+  match output {
+    Some(t) => { println!("Known output:\n{}", t); },
+    None => { println!("Unknown output:\n{:x?}", buf); },
+  }
+}
+
+fn test_unit_ready(device: &mut scsi::Device, matches: &Matches) {
+  if matches.opt_present("wait") {
+    let seconds: u64 = get_opt_or_return!("wait", matches);
+    let result = device.wait_until_ready_with_progress(Duration::from_secs(seconds), |p| {
+      match p {
+        Some(p) => { println!("Becoming ready: {:.1}%", p as f64 * 100.0 / 65536.0); },
+        None => { println!("Becoming ready"); },
+      }
+    });
+    match result {
+      Ok(()) => { println!("Ready"); },
+      Err(e) => { eprintln!("{}", e); },
+    }
+    return;
+  }
+
+  let cmd = commands::TestUnitReady::new();
+
+  println!("Issuing: {:#?}", cmd);
+  let result = device.issue_cmd(&cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn read_6(device: &mut scsi::Device, matches: &Matches) {
@@ -419,12 +1157,11 @@ fn read_6(device: &mut scsi::Device, matches: &Matches) {
     transfer_length: get_opt_or_return!("transfer_length", matches), 
   };
 
-  let mut buf: Vec <u8> = Vec::new();
-  buf.resize(cmd.transfer_length as usize, 0);
+  let mut buf: Vec <u8> = vec![0; cmd.transfer_length as usize];
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   //BLAH! Rewrite this to be more flat!
   match matches.opt_str("output_file") {
@@ -460,7 +1197,7 @@ fn allow_overwrite(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn erase_16(device: &mut scsi::Device, matches: &Matches) {
@@ -478,7 +1215,7 @@ fn erase_16(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn erase_6(device: &mut scsi::Device, matches: &Matches) {
@@ -492,7 +1229,7 @@ fn erase_6(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn format_medium(device: &mut scsi::Device, matches: &Matches) {
@@ -540,7 +1277,7 @@ fn format_medium(device: &mut scsi::Device, matches: &Matches) {
     println!("Input buffer: {:x?}", buf);
   }
   let result = device.issue_cmd_with_input(&cmd, &buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn write_6(device: &mut scsi::Device, matches: &Matches) {
@@ -584,7 +1321,7 @@ fn write_6(device: &mut scsi::Device, matches: &Matches) {
     println!("Input buffer: {:x?}", buf);
   }
   let result = device.issue_cmd_with_input(&cmd, &buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn load_unload(device: &mut scsi::Device, matches: &Matches) {
@@ -598,7 +1335,7 @@ fn load_unload(device: &mut scsi::Device, matches: &Matches) {
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn locate_10(device: &mut scsi::Device, matches: &Matches) {
@@ -612,7 +1349,7 @@ fn locate_10(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn locate_16(device: &mut scsi::Device, matches: &Matches) {
@@ -628,7 +1365,7 @@ fn locate_16(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn prevent_allow_medium_removal(device: &mut scsi::Device, matches: &Matches) {
@@ -638,7 +1375,7 @@ fn prevent_allow_medium_removal(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn read_16(device: &mut scsi::Device, matches: &Matches) {
@@ -654,7 +1391,7 @@ fn read_16(device: &mut scsi::Device, matches: &Matches) {
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   //BLAH! Rewrite this to be more flat!
   match matches.opt_str("output_file") {
@@ -690,7 +1427,7 @@ fn read_block_limits(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, &mut buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   match cmd.parse_buffer(&buf) {
     Some(t) => { println!("Known output:\n{:#?}", t); },
@@ -712,7 +1449,7 @@ fn read_position(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, &mut buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   match cmd.parse_buffer(&buf) {
     Some(t) => { println!("Known output:\n{:#?}", t); },
@@ -730,12 +1467,11 @@ fn read_reverse_16(device: &mut scsi::Device, matches: &Matches) {
     transfer_length: get_opt_or_return!("transfer_length", matches),
   };
 
-  let mut buf: Vec <u8> = Vec::new();
-  buf.resize(cmd.transfer_length as usize, 0);
+  let mut buf: Vec <u8> = vec![0; cmd.transfer_length as usize];
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   //BLAH! Rewrite this to be more flat!
   match matches.opt_str("output_file") {
@@ -770,12 +1506,11 @@ fn read_reverse_6(device: &mut scsi::Device, matches: &Matches) {
     transfer_length: get_opt_or_return!("transfer_length", matches),
   };
   
-  let mut buf: Vec <u8> = Vec::new();
-  buf.resize(cmd.transfer_length as usize, 0);
+  let mut buf: Vec <u8> = vec![0; cmd.transfer_length as usize];
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   //BLAH! Rewrite this to be more flat!
   match matches.opt_str("output_file") {
@@ -809,12 +1544,11 @@ fn recover_buffered_data(device: &mut scsi::Device, matches: &Matches) {
     transfer_length: get_opt_or_return!("transfer_length", matches),
   };
 
-  let mut buf: Vec <u8> = Vec::new();
-  buf.resize(cmd.transfer_length as usize, 0);
+  let mut buf: Vec <u8> = vec![0; cmd.transfer_length as usize];
   
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 
   //BLAH! Rewrite this to be more flat!
   match matches.opt_str("output_file") {
@@ -852,7 +1586,7 @@ fn report_density_support(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd_with_output(&cmd, buf.as_mut_slice());
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
   
   match cmd.parse_buffer(&buf) {
     Some(t) => { println!("Known output:\n{:#?}", t); },
@@ -869,7 +1603,7 @@ fn set_capacity(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn space_16(device: &mut scsi::Device, matches: &Matches) {
@@ -888,7 +1622,7 @@ fn space_16(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn space_6(device: &mut scsi::Device, matches: &Matches) {
@@ -899,7 +1633,7 @@ fn space_6(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn verify_16(device: &mut scsi::Device, matches: &Matches) {
@@ -926,7 +1660,7 @@ fn verify_16(device: &mut scsi::Device, matches: &Matches) {
   }
   let mut buf: Vec <u8> = vec![0; buf_len.unwrap()];
 
-  if buf.len() > 0 {
+  if !buf.is_empty() {
     let input_file_name: String = get_opt_or_return!("input_file", matches);
     let file = File::open(&input_file_name);
     if let Err(e) = file {
@@ -955,7 +1689,7 @@ fn verify_16(device: &mut scsi::Device, matches: &Matches) {
     println!("Input buffer: {:x?}", buf);
   }
   let result = device.issue_cmd_with_input(&cmd, &buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn verify_6(device: &mut scsi::Device, matches: &Matches) {
@@ -979,7 +1713,7 @@ fn verify_6(device: &mut scsi::Device, matches: &Matches) {
   }
   let mut buf: Vec <u8> = vec![0; buf_len.unwrap()];
 
-  if buf.len() > 0 {
+  if !buf.is_empty() {
     let input_file_name: String = get_opt_or_return!("input_file", matches);
     let file = File::open(&input_file_name);
     if let Err(e) = file {
@@ -1008,7 +1742,7 @@ fn verify_6(device: &mut scsi::Device, matches: &Matches) {
     println!("Input buffer: {:x?}", buf);
   }
   let result = device.issue_cmd_with_input(&cmd, &buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn write_16(device: &mut scsi::Device, matches: &Matches) {
@@ -1058,7 +1792,7 @@ fn write_16(device: &mut scsi::Device, matches: &Matches) {
     println!("Input buffer: {:x?}", buf);
   }
   let result = device.issue_cmd_with_input(&cmd, &buf);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn write_filemarks_16(device: &mut scsi::Device, matches: &Matches) {
@@ -1074,7 +1808,7 @@ fn write_filemarks_16(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
 
 fn write_filemarks_6(device: &mut scsi::Device, matches: &Matches) {
@@ -1085,5 +1819,5 @@ fn write_filemarks_6(device: &mut scsi::Device, matches: &Matches) {
 
   println!("Issuing: {:#?}", cmd);
   let result = device.issue_cmd(&cmd);
-  print_status_or_return_error!(result, cmd);
+  print_status_or_return_error!(device, result, cmd);
 }
@@ -7,6 +7,7 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(dead_code)]
+#![allow(clippy::useless_transmute)]
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -196,7 +197,7 @@ fn bindgen_test_layout_sg_iovec() {
         concat!("Alignment of ", stringify!(sg_iovec))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_iovec>())).iov_base as *const _ as usize },
+        ::std::mem::offset_of!(sg_iovec, iov_base),
         0usize,
         concat!(
             "Offset of field: ",
@@ -206,7 +207,7 @@ fn bindgen_test_layout_sg_iovec() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_iovec>())).iov_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_iovec, iov_len),
         8usize,
         concat!(
             "Offset of field: ",
@@ -256,7 +257,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         concat!("Alignment of ", stringify!(sg_io_hdr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).interface_id as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, interface_id),
         0usize,
         concat!(
             "Offset of field: ",
@@ -266,7 +267,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).dxfer_direction as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, dxfer_direction),
         4usize,
         concat!(
             "Offset of field: ",
@@ -276,7 +277,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).cmd_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, cmd_len),
         8usize,
         concat!(
             "Offset of field: ",
@@ -286,7 +287,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).mx_sb_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, mx_sb_len),
         9usize,
         concat!(
             "Offset of field: ",
@@ -296,7 +297,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).iovec_count as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, iovec_count),
         10usize,
         concat!(
             "Offset of field: ",
@@ -306,7 +307,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).dxfer_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, dxfer_len),
         12usize,
         concat!(
             "Offset of field: ",
@@ -316,7 +317,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).dxferp as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, dxferp),
         16usize,
         concat!(
             "Offset of field: ",
@@ -326,7 +327,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).cmdp as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, cmdp),
         24usize,
         concat!(
             "Offset of field: ",
@@ -336,7 +337,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).sbp as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, sbp),
         32usize,
         concat!(
             "Offset of field: ",
@@ -346,7 +347,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).timeout as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, timeout),
         40usize,
         concat!(
             "Offset of field: ",
@@ -356,7 +357,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).flags as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, flags),
         44usize,
         concat!(
             "Offset of field: ",
@@ -366,7 +367,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).pack_id as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, pack_id),
         48usize,
         concat!(
             "Offset of field: ",
@@ -376,7 +377,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).usr_ptr as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, usr_ptr),
         56usize,
         concat!(
             "Offset of field: ",
@@ -386,7 +387,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).status as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, status),
         64usize,
         concat!(
             "Offset of field: ",
@@ -396,7 +397,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).masked_status as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, masked_status),
         65usize,
         concat!(
             "Offset of field: ",
@@ -406,7 +407,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).msg_status as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, msg_status),
         66usize,
         concat!(
             "Offset of field: ",
@@ -416,7 +417,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).sb_len_wr as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, sb_len_wr),
         67usize,
         concat!(
             "Offset of field: ",
@@ -426,7 +427,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).host_status as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, host_status),
         68usize,
         concat!(
             "Offset of field: ",
@@ -436,7 +437,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).driver_status as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, driver_status),
         70usize,
         concat!(
             "Offset of field: ",
@@ -446,7 +447,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).resid as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, resid),
         72usize,
         concat!(
             "Offset of field: ",
@@ -456,7 +457,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).duration as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, duration),
         76usize,
         concat!(
             "Offset of field: ",
@@ -466,7 +467,7 @@ fn bindgen_test_layout_sg_io_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_io_hdr>())).info as *const _ as usize },
+        ::std::mem::offset_of!(sg_io_hdr, info),
         80usize,
         concat!(
             "Offset of field: ",
@@ -502,7 +503,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         concat!("Alignment of ", stringify!(sg_scsi_id))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).host_no as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, host_no),
         0usize,
         concat!(
             "Offset of field: ",
@@ -512,7 +513,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).channel as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, channel),
         4usize,
         concat!(
             "Offset of field: ",
@@ -522,7 +523,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).scsi_id as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, scsi_id),
         8usize,
         concat!(
             "Offset of field: ",
@@ -532,7 +533,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).lun as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, lun),
         12usize,
         concat!(
             "Offset of field: ",
@@ -542,7 +543,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).scsi_type as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, scsi_type),
         16usize,
         concat!(
             "Offset of field: ",
@@ -552,7 +553,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).h_cmd_per_lun as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, h_cmd_per_lun),
         20usize,
         concat!(
             "Offset of field: ",
@@ -562,7 +563,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).d_queue_depth as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, d_queue_depth),
         22usize,
         concat!(
             "Offset of field: ",
@@ -572,7 +573,7 @@ fn bindgen_test_layout_sg_scsi_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_scsi_id>())).unused as *const _ as usize },
+        ::std::mem::offset_of!(sg_scsi_id, unused),
         24usize,
         concat!(
             "Offset of field: ",
@@ -607,7 +608,7 @@ fn bindgen_test_layout_sg_req_info() {
         concat!("Alignment of ", stringify!(sg_req_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).req_state as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, req_state),
         0usize,
         concat!(
             "Offset of field: ",
@@ -617,7 +618,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).orphan as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, orphan),
         1usize,
         concat!(
             "Offset of field: ",
@@ -627,7 +628,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).sg_io_owned as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, sg_io_owned),
         2usize,
         concat!(
             "Offset of field: ",
@@ -637,7 +638,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).problem as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, problem),
         3usize,
         concat!(
             "Offset of field: ",
@@ -647,7 +648,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).pack_id as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, pack_id),
         4usize,
         concat!(
             "Offset of field: ",
@@ -657,7 +658,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).usr_ptr as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, usr_ptr),
         8usize,
         concat!(
             "Offset of field: ",
@@ -667,7 +668,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).duration as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, duration),
         16usize,
         concat!(
             "Offset of field: ",
@@ -677,7 +678,7 @@ fn bindgen_test_layout_sg_req_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_req_info>())).unused as *const _ as usize },
+        ::std::mem::offset_of!(sg_req_info, unused),
         20usize,
         concat!(
             "Offset of field: ",
@@ -720,7 +721,7 @@ fn bindgen_test_layout_sg_header() {
         concat!("Alignment of ", stringify!(sg_header))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_header>())).pack_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_header, pack_len),
         0usize,
         concat!(
             "Offset of field: ",
@@ -730,7 +731,7 @@ fn bindgen_test_layout_sg_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_header>())).reply_len as *const _ as usize },
+        ::std::mem::offset_of!(sg_header, reply_len),
         4usize,
         concat!(
             "Offset of field: ",
@@ -740,7 +741,7 @@ fn bindgen_test_layout_sg_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_header>())).pack_id as *const _ as usize },
+        ::std::mem::offset_of!(sg_header, pack_id),
         8usize,
         concat!(
             "Offset of field: ",
@@ -750,7 +751,7 @@ fn bindgen_test_layout_sg_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_header>())).result as *const _ as usize },
+        ::std::mem::offset_of!(sg_header, result),
         12usize,
         concat!(
             "Offset of field: ",
@@ -760,7 +761,7 @@ fn bindgen_test_layout_sg_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sg_header>())).sense_buffer as *const _ as usize },
+        ::std::mem::offset_of!(sg_header, sense_buffer),
         20usize,
         concat!(
             "Offset of field: ",
@@ -783,8 +783,10 @@ static ADDITIONAL_SENSE_CODE_STRINGS: phf::Map <u16, &str> = phf_map! {
 
 /// SPC-3 Section 4.5.6 Table 27
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SenseKey {
+  #[default]
   NoSense,
   RecoveredError,
   NotReady,
@@ -802,12 +804,8 @@ pub enum SenseKey {
   Unknown(u8),
 }
 
-impl Default for SenseKey {
-  fn default() -> Self { SenseKey::NoSense }
-}
-
 impl SenseKey {
-  fn from_u8(val: u8) -> SenseKey {
+  pub fn from_u8(val: u8) -> SenseKey {
     match val {
       0x00 => { SenseKey::NoSense },
       0x01 => { SenseKey::RecoveredError },
@@ -826,11 +824,44 @@ impl SenseKey {
       _ => { SenseKey::Unknown(val) },
     }
   }
+
+  pub fn to_u8(&self) -> u8 {
+    match self {
+      SenseKey::NoSense => { 0x00 },
+      SenseKey::RecoveredError => { 0x01 },
+      SenseKey::NotReady => { 0x02 },
+      SenseKey::MediumError => { 0x03 },
+      SenseKey::HardwareError => { 0x04 },
+      SenseKey::IllegalRequest => { 0x05 },
+      SenseKey::UnitAttention => { 0x06 },
+      SenseKey::DataProtect => { 0x07 },
+      SenseKey::BlankCheck => { 0x08 },
+      SenseKey::VendorSpecific => { 0x09 },
+      SenseKey::CopyAborted => { 0x0A },
+      SenseKey::AbortedCommand => { 0x0B },
+      SenseKey::VolumeOverflow => { 0x0D },
+      SenseKey::Miscompare => { 0x0E },
+      SenseKey::Unknown(val) => { *val },
+    }
+  }
+
+  /// Parse a sense key name such as "Medium Error" or "MEDIUM_ERROR" as
+  /// printed by the Linux kernel and sg3_utils
+  pub fn from_name(name: &str) -> Option <SenseKey> {
+    let name: String = name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    (0x00..=0x0F_u8).map(SenseKey::from_u8)
+        .find(|key| format!("{:?}", key).to_ascii_lowercase() == name)
+  }
 }
 
 /// SPC-3 Section 4.5.2.4.2
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SenseFieldPointer {
   pub c_d: bool,
   pub bpv: bool,
@@ -856,6 +887,7 @@ impl SenseFieldPointer {
 /// SPC-3 Section 4.5.2.4.3
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SenseActualRetryCount {
   pub actual_retry_count: u16,
 }
@@ -875,6 +907,7 @@ impl SenseActualRetryCount {
 /// SPC-3 Section 4.5.2.4.4
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SenseProgress {
   pub progress: u16,
 }
@@ -894,6 +927,7 @@ impl SenseProgress {
 /// SPC-3 Section 4.5.2.4.5
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SenseSegmentPointer {
   pub sd: bool,
   pub bpv: bool,
@@ -918,8 +952,10 @@ impl SenseSegmentPointer {
 
 /// SPC-3 Section 4.5.2.4
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SenseKeySpecific {
+  #[default]
   Nothing,
   IllegalRequest(SenseFieldPointer),
   Error(SenseActualRetryCount),
@@ -927,10 +963,6 @@ pub enum SenseKeySpecific {
   CopyAborted(SenseSegmentPointer),
 }
 
-impl Default for SenseKeySpecific {
-  fn default() -> Self { SenseKeySpecific::Nothing }
-}
-
 impl SenseKeySpecific {
   pub fn from_buf(key: &SenseKey, buf: &[u8]) -> SenseKeySpecific {
     if buf.len() < 3 {
@@ -939,25 +971,25 @@ impl SenseKeySpecific {
 
     match key {
       SenseKey::IllegalRequest => {
-        if let Some(tmp) = SenseFieldPointer::from_buf(&buf) {
+        if let Some(tmp) = SenseFieldPointer::from_buf(buf) {
           return SenseKeySpecific::IllegalRequest(tmp);
         }
       },
       SenseKey::HardwareError |
       SenseKey::MediumError |
       SenseKey::RecoveredError => {
-        if let Some(tmp) = SenseActualRetryCount::from_buf(&buf){
+        if let Some(tmp) = SenseActualRetryCount::from_buf(buf){
           return SenseKeySpecific::Error(tmp);
         }
       },
       SenseKey::NoSense |
       SenseKey::NotReady => {
-        if let Some(tmp) = SenseProgress::from_buf(&buf) {
+        if let Some(tmp) = SenseProgress::from_buf(buf) {
           return SenseKeySpecific::NotReady(tmp);
         }
       },
       SenseKey::CopyAborted => {
-        if let Some(tmp) = SenseSegmentPointer::from_buf(&buf) {
+        if let Some(tmp) = SenseSegmentPointer::from_buf(buf) {
           return SenseKeySpecific::CopyAborted(tmp);
         }
       },
@@ -968,15 +1000,32 @@ impl SenseKeySpecific {
   }
 }
 
+/// SSC-4 residue reported in the INFORMATION field for read and write type
+/// commands. The unit depends on the FIXED bit of the command.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Residue {
+  /// FIXED set: requested transfer length minus the number of logical blocks
+  /// actually transferred.
+  Blocks(i64),
+  /// FIXED clear: requested transfer length minus the actual logical block
+  /// length. Negative when the block on the medium is larger than requested.
+  Bytes(i64),
+}
+
 /// SPC-3 Section 4.5
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sense {
+  pub descriptor_format: bool,
   pub deferred: bool,
   pub filemark: bool,
   pub eom: bool,
   pub ili: bool,
   pub sense_key: SenseKey,
+  pub information_valid: bool,
   pub information: [u8; 8],
+  pub command_specific_information_valid: bool,
   pub command_specific_information: [u8; 8],
   pub additional_sense_code: u8,
   pub additional_sense_code_qualifier: u8,
@@ -986,24 +1035,33 @@ pub struct Sense {
   pub additional_sense_bytes: Vec <u8>,
 }
 
+/// Description of an ASC/ASCQ pair from SPC-3 Table 28
+pub fn additional_sense_code_description(asc: u8, ascq: u8) -> Option <&'static str> {
+  let asc_ascq_key = (asc as u16) << 8 | ascq as u16;
+
+  match asc_ascq_key {
+    0x4080..=0x40FF_u16 => Some("DIAGNOSTIC FAILURE ON COMPONENT (Look at ASCQ)"),
+    0x4D00..=0x4DFF_u16 => Some("TAGGED OVERLAPPED COMMANDS (Look at ASCQ)"),
+    0x7000..=0x70FF_u16 => Some("DECOMPRESSION EXCEPTION SHORT ALGORITHM ID (Look at ASCQ)"),
+    _ => ADDITIONAL_SENSE_CODE_STRINGS.get(&asc_ascq_key).copied(),
+  }
+}
+
+/// Reverse lookup of an ASC/ASCQ pair from its description, ignoring case.
+/// Useful for sense data that was only logged as text.
+pub fn find_additional_sense_code(description: &str) -> Option <(u8, u8)> {
+  let description = description.trim();
+  ADDITIONAL_SENSE_CODE_STRINGS.entries()
+      .find(|(_, msg)| msg.eq_ignore_ascii_case(description))
+      .map(|(key, _)| ((key >> 8) as u8, (key & 0xFF) as u8))
+}
+
 impl fmt::Display for Sense {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    let asc_ascq_key =
-        (self.additional_sense_code as u16) << 8 | 
-        self.additional_sense_code_qualifier as u16;
-
-    let sense_code_msg = match asc_ascq_key {
-      0x4080..=0x40FF_u16 => "DIAGNOSTIC FAILURE ON COMPONENT (Look at ASCQ)",
-      0x4D00..=0x4DFF_u16 => "TAGGED OVERLAPPED COMMANDS (Look at ASCQ)",
-      0x7000..=0x70FF_u16 => "DECOMPRESSION EXCEPTION SHORT ALGORITHM ID (Look at ASCQ)",
-      _ => if let Some(msg) = ADDITIONAL_SENSE_CODE_STRINGS.get(&asc_ascq_key) {
-        msg
-      } else {
-        "UNKNOWN"
-      },
-    };
+    let sense_code_msg = additional_sense_code_description(
+        self.additional_sense_code, self.additional_sense_code_qualifier)
+        .unwrap_or("UNKNOWN");
 
-    //TODO Finish printing all info
     write!(f, concat!(
         "Flags:{}{}{}{}\n",
         "ASC/ASCQ: {:#04x}/{:#04x} {}\n",
@@ -1017,7 +1075,25 @@ impl fmt::Display for Sense {
         self.additional_sense_code, self.additional_sense_code_qualifier, sense_code_msg,
         self.sense_key,
         self.sense_key_specific,
-    )
+    )?;
+
+    if let Some(info) = self.information_value() {
+      writeln!(f, "Information: {:#x} ({})", info, self.information_signed().unwrap())?;
+    }
+    if let Some(info) = self.command_specific_information_value() {
+      writeln!(f, "Command Specific Information: {:#x}", info)?;
+    }
+    if self.field_replaceable_unit_code != 0 {
+      writeln!(f, "Field Replaceable Unit Code: {:#04x}", self.field_replaceable_unit_code)?;
+    }
+    if !self.vendor_specific_sense_data.is_empty() {
+      writeln!(f, "Vendor Specific Sense Data: {:02x?}", self.vendor_specific_sense_data)?;
+    }
+    if !self.additional_sense_bytes.is_empty() {
+      writeln!(f, "Additional Sense Bytes: {:02x?}", self.additional_sense_bytes)?;
+    }
+
+    Ok(())
   }
 }
 
@@ -1043,6 +1119,7 @@ impl Sense {
     };
 
     if buf[0] & 0x80 == 0x80 {
+      rv.information_valid = true;
       rv.information[4..].copy_from_slice(&buf[3..7]);
     }
 
@@ -1052,7 +1129,8 @@ impl Sense {
       return Some(rv);
     }
     if let Some(slice) = buf.get(8..12) {
-      rv.command_specific_information[4..].copy_from_slice(&slice);
+      rv.command_specific_information_valid = true;
+      rv.command_specific_information[4..].copy_from_slice(slice);
     }
 
     if additional_sense_length < 5 {
@@ -1081,7 +1159,7 @@ impl Sense {
     }
     if let Some(slice) = buf.get(15..18) {
       if slice[0] & 0x80 == 0x80 {
-        rv.sense_key_specific = SenseKeySpecific::from_buf(&rv.sense_key, &slice);
+        rv.sense_key_specific = SenseKeySpecific::from_buf(&rv.sense_key, slice);
       }
     }
 
@@ -1105,6 +1183,7 @@ impl Sense {
     }
 
     let mut rv: Sense = Sense {
+      descriptor_format: true,
       deferred: response_code == 0x73,
       sense_key: SenseKey::from_u8(buf[1] & 0x0F),
       additional_sense_code: buf[2],
@@ -1123,12 +1202,14 @@ impl Sense {
       match dtype {
         0x00 => {
           if let Some(info) = buf.get(index..(index + 12)) {
+            rv.information_valid = info[2] & 0x80 == 0x80;
             rv.information.copy_from_slice(&info[4..12]);
           }
           index += 12;
         },
         0x01 => {
           if let Some(cmd_info) = buf.get(index..(index + 12)) {
+            rv.command_specific_information_valid = true;
             rv.command_specific_information.copy_from_slice(&cmd_info[4..12]);
           }
           index += 12;
@@ -1191,13 +1272,166 @@ impl Sense {
 
     Sense::from_buf_fixed(buf)
   }
+
+  /// Sign extends a field that is 4 bytes wide in fixed format and 8 bytes
+  /// wide in descriptor format.
+  fn signed_field(&self, field: &[u8; 8]) -> i64 {
+    if self.descriptor_format {
+      i64::from_be_bytes(*field)
+    } else {
+      i32::from_be_bytes(field[4..8].try_into().unwrap()) as i64
+    }
+  }
+
+  /// INFORMATION field as an unsigned value, or None if VALID is not set
+  pub fn information_value(&self) -> Option <u64> {
+    if self.information_valid {
+      Some(u64::from_be_bytes(self.information))
+    } else {
+      None
+    }
+  }
+
+  /// INFORMATION field interpreted as a two's complement value, or None if
+  /// VALID is not set. SSC-4 uses this for residues and counts that were not
+  /// processed, which may be negative.
+  pub fn information_signed(&self) -> Option <i64> {
+    if self.information_valid {
+      Some(self.signed_field(&self.information))
+    } else {
+      None
+    }
+  }
+
+  /// COMMAND-SPECIFIC INFORMATION field, or None if it was not returned
+  pub fn command_specific_information_value(&self) -> Option <u64> {
+    if self.command_specific_information_valid {
+      Some(u64::from_be_bytes(self.command_specific_information))
+    } else {
+      None
+    }
+  }
+
+  /// COMMAND-SPECIFIC INFORMATION field interpreted as a two's complement
+  /// value, or None if it was not returned
+  pub fn command_specific_information_signed(&self) -> Option <i64> {
+    if self.command_specific_information_valid {
+      Some(self.signed_field(&self.command_specific_information))
+    } else {
+      None
+    }
+  }
+
+  /// Informational exception (ASC 0x5D, FAILURE PREDICTION THRESHOLD
+  /// EXCEEDED) as configured by the Informational Exceptions Control mode
+  /// page. On a tape drive the cause is in the TapeAlert log page, see
+  /// [`read_tape_alert_flags_for_sense`]. The false exception raised by the
+  /// TEST bit of that mode page is excluded, see
+  /// [`Sense::is_informational_exception_test`].
+  ///
+  /// [`read_tape_alert_flags_for_sense`]: crate::commands::tape_alert::read_tape_alert_flags_for_sense
+  pub fn is_informational_exception(&self) -> bool {
+    self.additional_sense_code == 0x5D && self.additional_sense_code_qualifier != 0xFF
+  }
+
+  /// FAILURE PREDICTION THRESHOLD EXCEEDED (FALSE), the test informational
+  /// exception requested with the TEST bit
+  pub fn is_informational_exception_test(&self) -> bool {
+    self.additional_sense_code == 0x5D && self.additional_sense_code_qualifier == 0xFF
+  }
+
+  /// SSC-4 residue for read, write and verify type commands. `fixed` is the
+  /// FIXED bit of the command that returned this sense.
+  pub fn residue(&self, fixed: bool) -> Option <Residue> {
+    let value = self.information_signed()?;
+    if fixed {
+      Some(Residue::Blocks(value))
+    } else {
+      Some(Residue::Bytes(value))
+    }
+  }
+}
+
+#[test]
+fn sense_key_name_test() {
+  assert!(matches!(SenseKey::from_name("Medium Error"), Some(SenseKey::MediumError)));
+  assert!(matches!(SenseKey::from_name("ILLEGAL_REQUEST"), Some(SenseKey::IllegalRequest)));
+  assert!(SenseKey::from_name("Not A Key").is_none());
+  assert_eq!(find_additional_sense_code("Unrecovered read error"), Some((0x11, 0x00)));
+}
+
+#[test]
+fn fixed_information_test() {
+  //ILI, VALID, information of -512
+  let buf = [
+    0xF0, 0x00, 0x20, 0xFF, 0xFF, 0xFE, 0x00, 0x0A,
+    0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert!(sense.ili);
+  assert_eq!(sense.information_value(), Some(0xFFFF_FE00));
+  assert_eq!(sense.information_signed(), Some(-512));
+  assert_eq!(sense.residue(false), Some(Residue::Bytes(-512)));
+  assert_eq!(sense.command_specific_information_value(), Some(7));
+}
+
+#[test]
+fn fixed_information_not_valid_test() {
+  let buf = [
+    0x70, 0x00, 0x20, 0x00, 0x00, 0x00, 0x10, 0x0A,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert_eq!(sense.information_value(), None);
+  assert_eq!(sense.information_signed(), None);
+  assert_eq!(sense.residue(true), None);
+}
+
+#[test]
+fn descriptor_information_test() {
+  //Information descriptor with VALID and a residue of 3 blocks
+  let buf = [
+    0x72, 0x03, 0x11, 0x00, 0x00, 0x00, 0x00, 0x0C,
+    0x00, 0x0A, 0x80, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+  ];
+
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert!(sense.descriptor_format);
+  assert_eq!(sense.information_signed(), Some(3));
+  assert_eq!(sense.residue(true), Some(Residue::Blocks(3)));
+  assert_eq!(sense.command_specific_information_value(), None);
 }
 
 
 // C Functions
 
 #[no_mangle]
-pub extern fn sense_to_stdout(sense: *const Sense) {
+pub extern "C" fn sense_to_stdout(sense: *const Sense) {
   let sense = unsafe { &*sense };
   println!("{}", sense);
 }
+
+#[test]
+fn informational_exception_test() {
+  let mut buf = [
+    0x70, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0A,
+    0x00, 0x00, 0x00, 0x00, 0x5D, 0x00, 0x00, 0x00, 0x00, 0x00,
+  ];
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert!(sense.is_informational_exception());
+  assert!(!sense.is_informational_exception_test());
+
+  buf[13] = 0xFF;
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert!(!sense.is_informational_exception());
+  assert!(sense.is_informational_exception_test());
+
+  buf[12] = 0x11;
+  buf[13] = 0x00;
+  let sense = Sense::from_buf(&buf).unwrap();
+  assert!(!sense.is_informational_exception());
+  assert!(!sense.is_informational_exception_test());
+}
@@ -0,0 +1,118 @@
+use crate::lun::Lun;
+use std::fs;
+use std::path::Path;
+
+const SCSI_GENERIC_CLASS: &str = "/sys/class/scsi_generic";
+
+/// A SCSI generic node and the logical unit it is attached to
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SgDevice {
+  /// Device node, such as /dev/sg3
+  pub path: String,
+  pub host: u32,
+  pub channel: u32,
+  pub target: u32,
+  /// LUN in the Linux packed format, see [`Lun::to_linux_lun`]
+  pub lun: u64,
+  pub peripheral_device_type: Option <u8>,
+  pub vendor: Option <String>,
+  pub model: Option <String>,
+}
+
+impl SgDevice {
+  pub fn scsi_lun(&self) -> Lun {
+    Lun::from_linux_lun(self.lun)
+  }
+
+  /// Whether both logical units are on the same SCSI target, such as a tape
+  /// drive and a media changer behind one bridge
+  pub fn same_target(&self, other: &SgDevice) -> bool {
+    self.host == other.host && self.channel == other.channel && self.target == other.target
+  }
+}
+
+/// Split a H:C:T:L name
+fn parse_hctl(name: &str) -> Option <(u32, u32, u32, u64)> {
+  let mut parts = name.split(':');
+  let rv = (
+    parts.next()?.parse().ok()?,
+    parts.next()?.parse().ok()?,
+    parts.next()?.parse().ok()?,
+    parts.next()?.parse().ok()?,
+  );
+
+  if parts.next().is_some() {
+    return None;
+  }
+  Some(rv)
+}
+
+fn read_attribute(dir: &Path, name: &str) -> Option <String> {
+  fs::read_to_string(dir.join(name)).ok().map(|t| t.trim().to_string())
+}
+
+fn read_sg_device(class_entry: &Path) -> Option <SgDevice> {
+  let name = class_entry.file_name()?.to_str()?;
+  let device = class_entry.join("device");
+  let target = fs::read_link(&device).ok()?;
+  let (host, channel, target, lun) = parse_hctl(target.file_name()?.to_str()?)?;
+
+  Some(SgDevice {
+    path: format!("/dev/{}", name),
+    host,
+    channel,
+    target,
+    lun,
+    peripheral_device_type: read_attribute(&device, "type").and_then(|t| t.parse().ok()),
+    vendor: read_attribute(&device, "vendor"),
+    model: read_attribute(&device, "model"),
+  })
+}
+
+/// Every SCSI generic node on the system
+pub fn list_sg_devices() -> Result <Vec <SgDevice>, String> {
+  let entries = fs::read_dir(SCSI_GENERIC_CLASS)
+      .map_err(|e| format!("Could not read {}: {}", SCSI_GENERIC_CLASS, e))?;
+
+  let mut rv: Vec <SgDevice> = entries
+      .filter_map(|t| t.ok())
+      .filter_map(|t| read_sg_device(&t.path()))
+      .collect();
+  rv.sort_by_key(|t| (t.host, t.channel, t.target, t.lun));
+  Ok(rv)
+}
+
+/// SCSI generic node for a device path such as /dev/sg3
+pub fn find_sg_device_by_path(path: &str) -> Result <Option <SgDevice>, String> {
+  Ok(list_sg_devices()?.into_iter().find(|t| t.path == path))
+}
+
+/// SCSI generic node for one LUN on a target, typically a LUN returned by
+/// REPORT LUNS issued through another LUN of the same target
+pub fn find_sg_device(host: u32, channel: u32, target: u32, lun: &Lun)
+    -> Result <Option <SgDevice>, String> {
+  let lun = lun.to_linux_lun();
+  Ok(list_sg_devices()?.into_iter().find(|t| {
+    t.host == host && t.channel == channel && t.target == target && t.lun == lun
+  }))
+}
+
+/// SCSI generic nodes for every LUN on the same target as `path`, including
+/// `path` itself
+pub fn sg_devices_on_target(path: &str) -> Result <Vec <SgDevice>, String> {
+  let devices = list_sg_devices()?;
+  let device = match devices.iter().find(|t| t.path == path) {
+    Some(t) => { t.clone() },
+    None => { return Err(format!("{} is not a SCSI generic device", path)); },
+  };
+
+  Ok(devices.into_iter().filter(|t| t.same_target(&device)).collect())
+}
+
+#[test]
+fn parse_hctl_test() {
+  assert_eq!(parse_hctl("2:0:1:65536"), Some((2, 0, 1, 65536)));
+  assert_eq!(parse_hctl("host2"), None);
+  assert_eq!(parse_hctl("2:0:1:0:0"), None);
+}